@@ -1,8 +1,11 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
-use wavegen::{dc_bias, sawtooth, sine, square, Precision, SampleType, Waveform};
+use wavegen::{
+    dc_bias, sawtooth, sine, square, Component, DcBias, Interpolation, PeriodicFunction, Precision,
+    SampleType, Sawtooth, Sine, Square, StaticWaveform, Waveform,
+};
 
-fn sample_waveform<T: SampleType, P: Precision>(n: usize) -> Vec<T> {
-    let wf = Waveform::<T, P>::with_components(
+fn waveform<T: SampleType, P: Precision>() -> Waveform<T, P> {
+    Waveform::<T, P>::with_components(
         P::from(44100.0).unwrap(),
         vec![
             sine!(
@@ -22,9 +25,96 @@ fn sample_waveform<T: SampleType, P: Precision>(n: usize) -> Vec<T> {
             ),
             dc_bias!(P::from(0.1).unwrap()),
         ],
+    )
+}
+
+fn static_waveform<T: SampleType, P: Precision>() -> StaticWaveform<T, impl Component<P>, P> {
+    StaticWaveform::<T, _, P>::new(
+        P::from(44100.0).unwrap(),
+        (
+            Sine::new(
+                P::from(2048.).unwrap(),
+                P::from(1).unwrap(),
+                P::from(0).unwrap(),
+            ),
+            Sawtooth::new(
+                P::from(1024.).unwrap(),
+                P::from(1).unwrap(),
+                P::from(0).unwrap(),
+            ),
+            Square::new(
+                P::from(512.).unwrap(),
+                P::from(1).unwrap(),
+                P::from(0).unwrap(),
+            ),
+            DcBias::new(P::from(0.1).unwrap()),
+        ),
+    )
+}
+
+fn sample_static_waveform<T: SampleType, P: Precision>(n: usize) -> Vec<T> {
+    static_waveform::<T, P>().iter().take(n).collect()
+}
+
+fn fill_static_waveform<T: SampleType + Default + Clone, P: Precision>(n: usize) -> Vec<T> {
+    let mut buffer = vec![T::default(); n];
+    static_waveform::<T, P>().iter().fill(&mut buffer);
+
+    buffer
+}
+
+fn sample_sine<P: Precision + SampleType>(
+    n: usize,
+    interpolation: Option<Interpolation>,
+) -> Vec<P> {
+    let sine = sine!(
+        P::from(2048.).unwrap(),
+        P::from(1).unwrap(),
+        P::from(0).unwrap()
+    );
+    let sine = match interpolation {
+        Some(interpolation) => sine.tabulated(1024, interpolation),
+        None => sine,
+    };
+
+    Waveform::<P, P>::with_components(P::from(44100.0).unwrap(), vec![sine])
+        .iter()
+        .take(n)
+        .collect()
+}
+
+fn sample_waveform<T: SampleType, P: Precision>(n: usize) -> Vec<T> {
+    waveform::<T, P>().iter().take(n).collect()
+}
+
+fn fill_waveform<T: SampleType + Default + Clone, P: Precision>(n: usize) -> Vec<T> {
+    let mut buffer = vec![T::default(); n];
+    waveform::<T, P>().iter().fill(&mut buffer);
+
+    buffer
+}
+
+fn render<P: Precision + SampleType>(n: usize, function: &str, fill: bool) -> Vec<P> {
+    let (frequency, amplitude, phase) = (
+        P::from(440.).unwrap(),
+        P::from(1).unwrap(),
+        P::from(0).unwrap(),
     );
+    let function = match function {
+        "sine" => PeriodicFunction::sine(frequency, amplitude, phase),
+        "square" => PeriodicFunction::square(frequency, amplitude, phase),
+        _ => PeriodicFunction::sawtooth(frequency, amplitude, phase),
+    };
+    let wf = Waveform::<P, P>::with_components(P::from(44100.0).unwrap(), vec![function]);
 
-    wf.iter().take(n).collect()
+    if fill {
+        let mut buffer = vec![P::zero(); n];
+        wf.iter().fill(&mut buffer);
+
+        buffer
+    } else {
+        wf.iter().take(n).collect()
+    }
 }
 
 fn criterion_benchmark(c: &mut Criterion) {
@@ -43,6 +133,80 @@ fn criterion_benchmark(c: &mut Criterion) {
         b.iter(|| sample_waveform::<f64, f64>(black_box(25000)));
     });
     group.finish();
+
+    let mut group = c.benchmark_group("44.1 kHz 25000 samples, take and collect vs fill");
+    group.bench_function("f32 sample @ f32 precision, collect", |b| {
+        b.iter(|| sample_waveform::<f32, f32>(black_box(25000)));
+    });
+    group.bench_function("f32 sample @ f32 precision, fill", |b| {
+        b.iter(|| fill_waveform::<f32, f32>(black_box(25000)));
+    });
+    group.bench_function("f64 sample @ f64 precision, collect", |b| {
+        b.iter(|| sample_waveform::<f64, f64>(black_box(25000)));
+    });
+    group.bench_function("f64 sample @ f64 precision, fill", |b| {
+        b.iter(|| fill_waveform::<f64, f64>(black_box(25000)));
+    });
+    group.bench_function("i16 sample @ f32 precision, collect", |b| {
+        b.iter(|| sample_waveform::<i16, f32>(black_box(25000)));
+    });
+    group.bench_function("i16 sample @ f32 precision, fill", |b| {
+        b.iter(|| fill_waveform::<i16, f32>(black_box(25000)));
+    });
+    group.finish();
+
+    let mut group =
+        c.benchmark_group("44.1 kHz 25000 samples, take and collect vs fill per function");
+    for name in ["sine", "square", "sawtooth"] {
+        for fill in [false, true] {
+            let method = if fill { "fill" } else { "collect" };
+
+            group.bench_function(format!("f32 {name}, {method}"), |b| {
+                b.iter(|| render::<f32>(black_box(25000), name, fill));
+            });
+            group.bench_function(format!("f64 {name}, {method}"), |b| {
+                b.iter(|| render::<f64>(black_box(25000), name, fill));
+            });
+        }
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("44.1 kHz 25000 samples, boxed vs static components");
+    group.bench_function("f32 sample @ f32 precision, boxed collect", |b| {
+        b.iter(|| sample_waveform::<f32, f32>(black_box(25000)));
+    });
+    group.bench_function("f32 sample @ f32 precision, static collect", |b| {
+        b.iter(|| sample_static_waveform::<f32, f32>(black_box(25000)));
+    });
+    group.bench_function("f32 sample @ f32 precision, boxed fill", |b| {
+        b.iter(|| fill_waveform::<f32, f32>(black_box(25000)));
+    });
+    group.bench_function("f32 sample @ f32 precision, static fill", |b| {
+        b.iter(|| fill_static_waveform::<f32, f32>(black_box(25000)));
+    });
+    group.bench_function("f64 sample @ f64 precision, boxed collect", |b| {
+        b.iter(|| sample_waveform::<f64, f64>(black_box(25000)));
+    });
+    group.bench_function("f64 sample @ f64 precision, static collect", |b| {
+        b.iter(|| sample_static_waveform::<f64, f64>(black_box(25000)));
+    });
+    group.finish();
+
+    let mut group = c.benchmark_group("44.1 kHz 25000 samples, exact vs tabulated sine");
+    for (name, interpolation) in [
+        ("exact", None),
+        ("1024 values, no interpolation", Some(Interpolation::None)),
+        ("1024 values, linear", Some(Interpolation::Linear)),
+        ("1024 values, cubic", Some(Interpolation::Cubic)),
+    ] {
+        group.bench_function(format!("f32 precision, {name}"), |b| {
+            b.iter(|| sample_sine::<f32>(black_box(25000), interpolation));
+        });
+        group.bench_function(format!("f64 precision, {name}"), |b| {
+            b.iter(|| sample_sine::<f64>(black_box(25000), interpolation));
+        });
+    }
+    group.finish();
 }
 
 criterion_group!(benches, criterion_benchmark);
@@ -0,0 +1,520 @@
+//! Band-limited oscillators.
+
+use crate::nyquist::{Shape, Spectrum};
+use crate::{Kind, PeriodicFunction, Precision, Two};
+
+/// Defines the band-limiting method used by band-limited oscillators.
+///
+/// Both methods need to know the sample rate, so they only take effect once the function is added to a [`Waveform`].
+///
+/// [`Waveform`]: crate::Waveform
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BandLimit {
+    /// Polynomial band-limited step correction around the discontinuities of the naive waveform.
+    ///
+    /// Cheap, with a constant cost per sample, but only attenuates the aliasing.
+    PolyBlep,
+    /// Sum of all the harmonics of the waveform below the Nyquist frequency.
+    ///
+    /// Free of aliasing, but the cost per sample grows with the number of harmonics, that is with lower frequencies.
+    Additive,
+}
+
+/// Polynomial band-limited step residual for a step of size 2 at `x == 0`, with `x` and `dt` in periods.
+#[inline]
+fn poly_blep<P: Precision>(x: P, dt: P) -> P {
+    if x < dt {
+        let x = x / dt;
+
+        x + x - x * x - P::one()
+    } else if x > P::one() - dt {
+        let x = (x - P::one()) / dt;
+
+        x * x + x + x + P::one()
+    } else {
+        P::zero()
+    }
+}
+
+/// Number of harmonics of `frequency` strictly below the Nyquist frequency.
+#[inline]
+pub(crate) fn harmonics<P: Precision>(frequency: P, sample_rate: P) -> u64 {
+    let nyquist = sample_rate / P::two();
+
+    ((nyquist / frequency.abs()).ceil() - P::one())
+        .to_u64()
+        .unwrap_or(0)
+}
+
+/// Sums `coefficient(n) * sin(2 * PI * n * x)` and `coefficient(n) * cos(2 * PI * n * x)` for `n` in `1..=harmonics`.
+#[inline]
+fn additive<P: Precision>(x: P, harmonics: u64, coefficient: impl Fn(P) -> P) -> (P, P) {
+    let (sin_1, cos_1) = (P::two() * P::PI() * x).sin_cos();
+    let (mut sin_n, mut cos_n) = (sin_1, cos_1);
+    let (mut sin_sum, mut cos_sum) = (P::zero(), P::zero());
+    let mut n = P::one();
+
+    for _ in 0..harmonics {
+        let c = coefficient(n);
+        sin_sum = sin_sum + c * sin_n;
+        cos_sum = cos_sum + c * cos_n;
+
+        // angle addition, to avoid calling sin and cos for every harmonic
+        let next_sin = sin_n * cos_1 + cos_n * sin_1;
+        cos_n = cos_n * cos_1 - sin_n * sin_1;
+        sin_n = next_sin;
+        n = n + P::one();
+    }
+
+    (sin_sum, cos_sum)
+}
+
+/// Phase of `t`, in `[0, 1)` periods.
+#[inline]
+fn phase_of<P: Precision>(t: P, frequency: P, phase: P) -> P {
+    let x = (t * frequency + phase).fract();
+
+    if x < P::zero() {
+        x + P::one()
+    } else {
+        x
+    }
+}
+
+impl<P: Precision> PeriodicFunction<P> {
+    /// Band-limited sawtooth function builder.
+    ///
+    /// Same as [`PeriodicFunction::sawtooth`] with positive `frequency`, but band-limited with given `method` once added
+    /// to a [`Waveform`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wavegen::{BandLimit, PeriodicFunction, Waveform};
+    ///
+    /// let wf = Waveform::<f32>::with_components(
+    ///     44100.0,
+    ///     vec![PeriodicFunction::band_limited_sawtooth(3000.0, 1.0, 0.0, BandLimit::PolyBlep)],
+    /// );
+    /// ```
+    ///
+    /// [`Waveform`]: crate::Waveform
+    #[inline]
+    pub fn band_limited_sawtooth(
+        frequency: impl Into<P>,
+        amplitude: impl Into<P>,
+        phase: impl Into<P>,
+        method: BandLimit,
+    ) -> Self {
+        let frequency = frequency.into();
+        let amplitude = amplitude.into();
+        let phase = phase.into();
+
+        Self::sawtooth(frequency, amplitude, phase)
+            .with_bind(move |sample_rate| {
+                let dt = frequency / sample_rate;
+                let harmonics = harmonics(frequency, sample_rate);
+                let scale = P::two().neg() * amplitude / P::PI();
+
+                move |t| {
+                    let x = phase_of(t, frequency, phase);
+
+                    match method {
+                        BandLimit::PolyBlep => {
+                            amplitude * (P::two() * x - P::one() - poly_blep(x, dt))
+                        }
+                        BandLimit::Additive => scale * additive(x, harmonics, P::recip).0,
+                    }
+                }
+            })
+            .band_limited(frequency)
+            .with_kind(Kind::BandLimitedSawtooth {
+                frequency,
+                amplitude,
+                phase,
+                method,
+            })
+    }
+
+    /// Band-limited square function builder.
+    ///
+    /// Same as [`PeriodicFunction::square`] with positive `frequency`, but band-limited with given `method` once added
+    /// to a [`Waveform`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wavegen::{BandLimit, PeriodicFunction, Waveform};
+    ///
+    /// let wf = Waveform::<f32>::with_components(
+    ///     44100.0,
+    ///     vec![PeriodicFunction::band_limited_square(3000.0, 1.0, 0.0, BandLimit::Additive)],
+    /// );
+    /// ```
+    ///
+    /// [`Waveform`]: crate::Waveform
+    #[inline]
+    pub fn band_limited_square(
+        frequency: impl Into<P>,
+        amplitude: impl Into<P>,
+        phase: impl Into<P>,
+        method: BandLimit,
+    ) -> Self {
+        let frequency = frequency.into();
+        let amplitude = amplitude.into();
+        let phase = phase.into();
+        let half = P::one() / P::two();
+
+        Self::square(frequency, amplitude, phase)
+            .with_bind(move |sample_rate| {
+                let dt = frequency / sample_rate;
+                let harmonics = harmonics(frequency, sample_rate);
+                let scale = P::two() * P::two() * amplitude / P::PI();
+
+                move |t| {
+                    // the naive square shifts by `phase` seconds, not periods
+                    let x = phase_of(t - phase, frequency, P::zero());
+
+                    match method {
+                        BandLimit::PolyBlep => {
+                            let naive = if x < half { P::one() } else { P::one().neg() };
+
+                            amplitude
+                                * (naive + poly_blep(x, dt) - poly_blep((x + half).fract(), dt))
+                        }
+                        BandLimit::Additive => {
+                            let (sum, _) = additive(x, harmonics, |n| {
+                                if (n / P::two()).fract() == P::zero() {
+                                    P::zero()
+                                } else {
+                                    n.recip()
+                                }
+                            });
+
+                            scale * sum
+                        }
+                    }
+                }
+            })
+            .band_limited(frequency)
+            .with_kind(Kind::BandLimitedSquare {
+                frequency,
+                amplitude,
+                phase,
+                method,
+            })
+    }
+
+    /// Band-limited bipolar pulse function builder.
+    ///
+    /// Same as [`PeriodicFunction::pulse`] with positive `frequency`, but band-limited with given `method` once added
+    /// to a [`Waveform`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wavegen::{BandLimit, PeriodicFunction, Waveform};
+    ///
+    /// let wf = Waveform::<f32>::with_components(
+    ///     44100.0,
+    ///     vec![PeriodicFunction::band_limited_pulse(3000.0, 1.0, 0.0, 0.1, BandLimit::PolyBlep)],
+    /// );
+    /// ```
+    ///
+    /// [`Waveform`]: crate::Waveform
+    #[inline]
+    pub fn band_limited_pulse(
+        frequency: impl Into<P>,
+        amplitude: impl Into<P>,
+        phase: impl Into<P>,
+        duty: impl Into<P>,
+        method: BandLimit,
+    ) -> Self {
+        let frequency = frequency.into();
+        let amplitude = amplitude.into();
+        let phase = phase.into();
+        let duty = duty.into().max(P::zero()).min(P::one());
+
+        Self::pulse(frequency, amplitude, phase, duty)
+            .with_bind(move |sample_rate| {
+                let unipolar =
+                    band_limited_unipolar_pulse(frequency, phase, duty, sample_rate, method);
+
+                move |t| amplitude * (P::two() * unipolar(t) - P::one())
+            })
+            .band_limited(frequency)
+            .with_kind(Kind::BandLimitedPulse {
+                frequency,
+                amplitude,
+                phase,
+                duty,
+                method,
+            })
+    }
+
+    /// Band-limited unipolar pulse function builder.
+    ///
+    /// Same as [`PeriodicFunction::unipolar_pulse`] with positive `frequency`, but band-limited with given `method`
+    /// once added to a [`Waveform`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wavegen::{BandLimit, PeriodicFunction, Waveform};
+    ///
+    /// let wf = Waveform::<f32>::with_components(
+    ///     44100.0,
+    ///     vec![PeriodicFunction::band_limited_unipolar_pulse(3000.0, 1.0, 0.0, 0.1, BandLimit::Additive)],
+    /// );
+    /// ```
+    ///
+    /// [`Waveform`]: crate::Waveform
+    #[inline]
+    pub fn band_limited_unipolar_pulse(
+        frequency: impl Into<P>,
+        amplitude: impl Into<P>,
+        phase: impl Into<P>,
+        duty: impl Into<P>,
+        method: BandLimit,
+    ) -> Self {
+        let frequency = frequency.into();
+        let amplitude = amplitude.into();
+        let phase = phase.into();
+        let duty = duty.into().max(P::zero()).min(P::one());
+
+        Self::unipolar_pulse(frequency, amplitude, phase, duty)
+            .with_bind(move |sample_rate| {
+                let unipolar =
+                    band_limited_unipolar_pulse(frequency, phase, duty, sample_rate, method);
+
+                move |t| amplitude * unipolar(t)
+            })
+            .band_limited(frequency)
+            .with_kind(Kind::BandLimitedUnipolarPulse {
+                frequency,
+                amplitude,
+                phase,
+                duty,
+                method,
+            })
+    }
+
+    /// Band-limited functions have no significant harmonics above the Nyquist frequency.
+    #[inline]
+    fn band_limited(mut self, frequency: P) -> Self {
+        self.spectrum = Some(Spectrum {
+            fundamental: frequency.abs(),
+            shape: Shape::Tone,
+        });
+
+        self
+    }
+}
+
+/// Band-limited pulse of amplitude `1`, swinging between `0` and `1`.
+#[inline]
+fn band_limited_unipolar_pulse<P: Precision>(
+    frequency: P,
+    phase: P,
+    duty: P,
+    sample_rate: P,
+    method: BandLimit,
+) -> impl Fn(P) -> P + Clone {
+    let dt = frequency / sample_rate;
+    let harmonics = harmonics(frequency, sample_rate);
+    let half = P::one() / P::two();
+    let two_over_pi: P = P::two() / P::PI();
+    let pi_duty = P::PI() * duty;
+
+    move |t| {
+        let x = phase_of(t, frequency, phase);
+
+        match method {
+            BandLimit::PolyBlep => {
+                let naive = if x < duty { P::one() } else { P::zero() };
+                let falling = (x - duty + P::one()).fract();
+
+                naive + half * (poly_blep(x, dt) - poly_blep(falling, dt))
+            }
+            BandLimit::Additive => {
+                // harmonics are centered on the middle of the pulse
+                let (_, sum) = additive(x - half * duty, harmonics, |n| (n * pi_duty).sin() / n);
+
+                duty + two_over_pi * sum
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BandLimit, PeriodicFunction, Waveform};
+    use alloc::{vec, vec::Vec};
+    use float_cmp::approx_eq;
+    use paste::paste;
+    use rustfft::{num_complex::Complex, FftPlanner};
+
+    const SAMPLE_RATE: usize = 44100;
+    const FREQUENCY: usize = 1001;
+
+    /// Energy of the spectrum outside of the harmonics of `FREQUENCY`, relative to the total energy, in dB.
+    ///
+    /// One second of samples is analysed, so each FFT bin is exactly 1 Hz wide and all the harmonics fall right into
+    /// their bins. Aliased harmonics do not, as `FREQUENCY` and `SAMPLE_RATE` are chosen so that they fold in between.
+    #[allow(clippy::cast_precision_loss)]
+    fn alias_energy_db(f: PeriodicFunction<f64>) -> f64 {
+        let wf = Waveform::<f64, f64>::with_components(SAMPLE_RATE as f64, vec![f]);
+        let mut buffer: Vec<_> = wf
+            .iter()
+            .take(SAMPLE_RATE)
+            .map(|s| Complex::new(s, 0.0))
+            .collect();
+        FftPlanner::new()
+            .plan_fft_forward(SAMPLE_RATE)
+            .process(&mut buffer);
+
+        let (harmonic, alias) = buffer[..SAMPLE_RATE / 2].iter().enumerate().fold(
+            (0.0, 0.0),
+            |(harmonic, alias), (i, c)| {
+                if i % FREQUENCY == 0 {
+                    (harmonic + c.norm_sqr(), alias)
+                } else {
+                    (harmonic, alias + c.norm_sqr())
+                }
+            },
+        );
+
+        10.0 * (alias / (harmonic + alias)).log10()
+    }
+
+    macro_rules! test_alias_energy {
+        ($($name:ident: $naive:expr, $band_limited:ident($($arg:expr),*) => $poly_blep:expr)*) => {
+            $(
+                paste! {
+                    #[test]
+                    #[allow(clippy::cast_precision_loss)]
+                    fn [<band_limited_ $name _has_low_alias_energy>]() {
+                        let f = FREQUENCY as f64;
+                        let naive = alias_energy_db($naive);
+                        let poly_blep = alias_energy_db(PeriodicFunction::$band_limited(f, $($arg,)* BandLimit::PolyBlep));
+                        let additive = alias_energy_db(PeriodicFunction::$band_limited(f, $($arg,)* BandLimit::Additive));
+
+                        assert!(naive > -25.0, "naive: {} dB", naive);
+                        assert!(poly_blep < $poly_blep, "PolyBLEP: {} dB", poly_blep);
+                        assert!(additive < -150.0, "additive: {} dB", additive);
+                    }
+                }
+            )*
+        };
+    }
+
+    test_alias_energy! {
+        sawtooth: PeriodicFunction::sawtooth(FREQUENCY as f64, 1.0, 0.0), band_limited_sawtooth(1.0, 0.0) => -30.0
+        square: PeriodicFunction::square(FREQUENCY as f64, 1.0, 0.0), band_limited_square(1.0, 0.0) => -30.0
+        pulse: PeriodicFunction::pulse(FREQUENCY as f64, 1.0, 0.0, 0.2), band_limited_pulse(1.0, 0.0, 0.2) => -30.0
+        unipolar_pulse: PeriodicFunction::unipolar_pulse(FREQUENCY as f64, 1.0, 0.0, 0.2), band_limited_unipolar_pulse(1.0, 0.0, 0.2) => -30.0
+    }
+
+    macro_rules! test_naive_outside_waveform {
+        ($($name:ident: $naive:ident($($arg:expr),*))*) => {
+            $(
+                paste! {
+                    #[test]
+                    fn [<band_limited_ $name _is_naive_outside_of_waveform>]() {
+                        let naive = PeriodicFunction::<f64>::$naive($($arg),*);
+                        let band_limited = PeriodicFunction::<f64>::[<band_limited_ $naive>]($($arg,)* BandLimit::Additive);
+
+                        for t in [0.0, 0.1, 0.37, 0.9] {
+                            assert!(approx_eq!(f64, naive.sample(t), band_limited.sample(t)));
+                        }
+                    }
+                }
+            )*
+        };
+    }
+
+    test_naive_outside_waveform! {
+        sawtooth: sawtooth(3.0, 2.0, 0.1)
+        square: square(3.0, 2.0, 0.1)
+        pulse: pulse(3.0, 2.0, 0.1, 0.3)
+        unipolar_pulse: unipolar_pulse(3.0, 2.0, 0.1, 0.3)
+    }
+
+    macro_rules! test_matches_naive_at_low_frequency {
+        ($($name:ident: $naive:ident($($arg:expr),*))*) => {
+            $(
+                paste! {
+                    #[test]
+                    fn [<band_limited_ $name _matches_naive_away_from_edges>]() {
+                        for method in [BandLimit::PolyBlep, BandLimit::Additive] {
+                            let naive = Waveform::<f64, f64>::with_components(
+                                10000.0,
+                                vec![PeriodicFunction::$naive($($arg),*)],
+                            );
+                            let band_limited = Waveform::<f64, f64>::with_components(
+                                10000.0,
+                                vec![PeriodicFunction::[<band_limited_ $naive>]($($arg,)* method)],
+                            );
+
+                            // 1 Hz sampled at 10 kHz; compare points of the first period far from the edges
+                            for (i, (a, b)) in naive.iter().zip(band_limited.iter()).take(1000).enumerate() {
+                                if [50, 200, 250, 350, 700, 750, 900].contains(&i) {
+                                    assert!(approx_eq!(f64, a, b, epsilon = 0.05), "{:?} at {}: {} != {}", method, i, a, b);
+                                }
+                            }
+                        }
+                    }
+                }
+            )*
+        };
+    }
+
+    test_matches_naive_at_low_frequency! {
+        sawtooth: sawtooth(1.0, 1.0, 0.0)
+        square: square(1.0, 1.0, 0.0)
+        pulse: pulse(1.0, 1.0, 0.0, 0.3)
+        unipolar_pulse: unipolar_pulse(1.0, 1.0, 0.0, 0.3)
+    }
+
+    macro_rules! test_poly_blep_approaches_additive {
+        ($($name:ident: $naive:ident($($arg:expr),*) => $ratio:expr)*) => {
+            $(
+                paste! {
+                    #[test]
+                    fn [<band_limited_ $name _poly_blep_approaches_additive>]() {
+                        let render = |f: PeriodicFunction<f64>| {
+                            Waveform::<f64, f64>::with_components(44100.0, vec![f])
+                                .iter()
+                                .take(4410)
+                                .collect::<alloc::vec::Vec<_>>()
+                        };
+                        let rms = |a: &[f64], b: &[f64]| {
+                            let sum: f64 = a.iter().zip(b).map(|(x, y)| (x - y) * (x - y)).sum();
+
+                            (sum / 4410.0).sqrt()
+                        };
+                        let naive = render(PeriodicFunction::$naive($($arg),*));
+                        let poly_blep = render(PeriodicFunction::[<band_limited_ $naive>]($($arg,)* BandLimit::PolyBlep));
+                        let additive = render(PeriodicFunction::[<band_limited_ $naive>]($($arg,)* BandLimit::Additive));
+
+                        let naive_error = rms(&naive, &additive);
+                        let poly_blep_error = rms(&poly_blep, &additive);
+
+                        // the band limit makes a difference at this frequency...
+                        assert!(naive_error > 0.05, "naive: {}", naive_error);
+                        // ...and PolyBLEP gets most of the way to the ideal, band-limited wave
+                        assert!(poly_blep_error < naive_error / $ratio, "PolyBLEP: {}, naive: {}", poly_blep_error, naive_error);
+                    }
+                }
+            )*
+        };
+    }
+
+    test_poly_blep_approaches_additive! {
+        sawtooth: sawtooth(441.0, 1.0, 0.0) => 2.0
+        square: square(441.0, 1.0, 0.0) => 2.0
+        pulse: pulse(441.0, 1.0, 0.0, 0.3) => 2.0
+        unipolar_pulse: unipolar_pulse(441.0, 1.0, 0.0, 0.3) => 2.0
+    }
+}
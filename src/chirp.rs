@@ -0,0 +1,380 @@
+//! Frequency sweep (chirp) builders.
+
+use crate::nyquist::{Shape, Spectrum};
+use crate::{finite, Error, Kind, PeriodicFunction, Precision, Two};
+use alloc::boxed::Box;
+use core::cmp::Ordering;
+
+/// Defines how the instantaneous frequency of a chirp changes between its start and end frequency.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Sweep {
+    /// Frequency changes linearly with time.
+    Linear,
+    /// Frequency changes exponentially with time, spending equal time in each octave.
+    /// Also known as logarithmic sweep.
+    ///
+    /// Start and end frequencies have to be non-zero and of the same sign.
+    Exponential,
+    /// Period changes linearly with time.
+    ///
+    /// Start and end frequencies have to be non-zero and of the same sign.
+    Hyperbolic,
+}
+
+/// Defines what a chirp does after its sweep duration has elapsed.
+///
+/// The phase of the chirp is continuous in all modes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AfterSweep {
+    /// Keep oscillating at the end frequency.
+    Hold,
+    /// Start the sweep over from the start frequency.
+    Repeat,
+    /// Sweep back to the start frequency, then forward again, and so on.
+    PingPong,
+}
+
+#[derive(Clone, Copy)]
+struct SweepPhase<P: Precision> {
+    start: P,
+    end: P,
+    duration: P,
+    sweep: Sweep,
+}
+
+impl<P: Precision> SweepPhase<P> {
+    /// Phase, in periods, accumulated from the sweep start until time `t`, where `0 <= t <= duration`.
+    fn at(&self, t: P) -> P {
+        let Self {
+            start,
+            end,
+            duration,
+            sweep,
+        } = *self;
+
+        if start == end {
+            return start * t;
+        }
+
+        match sweep {
+            Sweep::Linear => start * t + (end - start) * t * t / (P::two() * duration),
+            Sweep::Exponential => {
+                let ln_ratio = (end / start).ln();
+
+                start * duration * ((ln_ratio * t / duration).exp() - P::one()) / ln_ratio
+            }
+            Sweep::Hyperbolic => {
+                let delta = start - end;
+
+                start * end * duration / delta * (P::one() + delta * t / (end * duration)).ln()
+            }
+        }
+    }
+}
+
+impl<P: Precision> PeriodicFunction<P> {
+    /// Chirp (swept sine) function builder. See the [`macro`] for more info.
+    ///
+    /// The frequency sweeps from `start_frequency` to `end_frequency` over `duration` seconds, following the `sweep` law.
+    /// What happens after that is defined by `after`.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if the parameters do not describe a valid sweep. See [`PeriodicFunction::try_chirp`].
+    ///
+    /// [`macro`]: ../macro.chirp.html
+    #[inline]
+    pub fn chirp(
+        start_frequency: impl Into<P>,
+        end_frequency: impl Into<P>,
+        duration: impl Into<P>,
+        amplitude: impl Into<P>,
+        phase: impl Into<P>,
+        sweep: Sweep,
+        after: AfterSweep,
+    ) -> Self {
+        match Self::try_chirp(
+            start_frequency,
+            end_frequency,
+            duration,
+            amplitude,
+            phase,
+            sweep,
+            after,
+        ) {
+            Ok(f) => f,
+            Err(error) => panic!("{}", error),
+        }
+    }
+
+    /// Fallible version of [`PeriodicFunction::chirp`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidDuration`] if `duration` is not a finite, positive, non-zero number,
+    /// [`Error::InvalidFrequency`] if the start and end frequencies of a [`Sweep::Exponential`] or
+    /// [`Sweep::Hyperbolic`] sweep are zero or of opposite signs, or another [`Error`] if any of the other parameters
+    /// is not a finite number.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wavegen::{AfterSweep, Error, PeriodicFunction, Sweep};
+    ///
+    /// assert!(PeriodicFunction::<f32>::try_chirp(10.0, 100.0, 1.0, 1.0, 0.0, Sweep::Linear, AfterSweep::Hold).is_ok());
+    /// assert_eq!(
+    ///     Some(Error::InvalidDuration),
+    ///     PeriodicFunction::<f32>::try_chirp(10.0, 100.0, 0.0, 1.0, 0.0, Sweep::Linear, AfterSweep::Hold).err()
+    /// );
+    /// assert_eq!(
+    ///     Some(Error::InvalidFrequency),
+    ///     PeriodicFunction::<f32>::try_chirp(0.0, 100.0, 1.0, 1.0, 0.0, Sweep::Exponential, AfterSweep::Hold).err()
+    /// );
+    /// ```
+    #[inline]
+    pub fn try_chirp(
+        start_frequency: impl Into<P>,
+        end_frequency: impl Into<P>,
+        duration: impl Into<P>,
+        amplitude: impl Into<P>,
+        phase: impl Into<P>,
+        sweep: Sweep,
+        after: AfterSweep,
+    ) -> Result<Self, Error> {
+        let start = finite(start_frequency.into(), Error::InvalidFrequency)?;
+        let end = finite(end_frequency.into(), Error::InvalidFrequency)?;
+        let duration = finite(duration.into(), Error::InvalidDuration)?;
+        let amplitude = finite(amplitude.into(), Error::InvalidAmplitude)?;
+        let phase = finite(phase.into(), Error::InvalidPhase)?;
+
+        if duration <= P::zero() {
+            return Err(Error::InvalidDuration);
+        }
+        // the exponential and hyperbolic laws divide by both frequencies, and cannot cross zero
+        if sweep != Sweep::Linear
+            && (start * end).partial_cmp(&P::zero()) != Some(Ordering::Greater)
+        {
+            return Err(Error::InvalidFrequency);
+        }
+
+        let sweep = SweepPhase {
+            start,
+            end,
+            duration,
+            sweep,
+        };
+        let total = sweep.at(duration);
+
+        let mut f = Self::new(Box::new(move |t| {
+            let cycles = if t <= P::zero() {
+                sweep.start * t
+            } else if t <= duration {
+                sweep.at(t)
+            } else {
+                match after {
+                    AfterSweep::Hold => total + sweep.end * (t - duration),
+                    AfterSweep::Repeat => {
+                        let n = (t / duration).floor();
+                        let tau = t - n * duration;
+
+                        (n * total).fract() + sweep.at(tau)
+                    }
+                    AfterSweep::PingPong => {
+                        let round_trip = P::two() * duration;
+                        let n = (t / round_trip).floor();
+                        let tau = t - n * round_trip;
+                        let cycles = if tau <= duration {
+                            sweep.at(tau)
+                        } else {
+                            P::two() * total - sweep.at(round_trip - tau)
+                        };
+
+                        (n * P::two() * total).fract() + cycles
+                    }
+                }
+            };
+
+            amplitude * (P::two() * P::PI() * (cycles.fract() + phase)).sin()
+        }));
+        f.spectrum = Some(Spectrum {
+            fundamental: sweep.start.abs().max(sweep.end.abs()),
+            shape: Shape::Tone,
+        });
+
+        Ok(f.with_kind(Kind::Chirp {
+            start_frequency: sweep.start,
+            end_frequency: sweep.end,
+            duration,
+            amplitude,
+            phase,
+            sweep: sweep.sweep,
+            after,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{AfterSweep, Error, PeriodicFunction, Sweep};
+    use float_cmp::approx_eq;
+    use paste::paste;
+
+    const EPS: f64 = 1e-3;
+
+    /// Estimates the instantaneous frequency at `t` by counting zero crossings in a short window.
+    #[allow(clippy::cast_precision_loss)]
+    fn frequency_at(f: &PeriodicFunction<f64>, t: f64) -> f64 {
+        let window = 0.1;
+        let steps = 100_000;
+        let dt = window / f64::from(steps);
+        let crossings = (0..steps)
+            .map(|i| t - window / 2.0 + f64::from(i) * dt)
+            .map(|t| f.sample(t))
+            .collect::<alloc::vec::Vec<_>>()
+            .windows(2)
+            .filter(|w| w[0].signum() != w[1].signum())
+            .count();
+
+        crossings as f64 / (2.0 * window)
+    }
+
+    macro_rules! test_sweep_frequency {
+        ($($name:ident: $sweep:expr, $after:expr => [$(($t:expr, $f:expr)),*])*) => {
+            $(
+                paste! {
+                    #[test]
+                    fn [<$name _chirp_has_expected_frequency>]() {
+                        let chirp = PeriodicFunction::<f64>::chirp(100.0, 400.0, 2.0, 1.0, 0.0, $sweep, $after);
+
+                        $(
+                            let f = frequency_at(&chirp, $t);
+                            assert!(approx_eq!(f64, f, $f, epsilon = 10.0), "f({}) = {}", $t, f);
+                        )*
+                    }
+                }
+            )*
+        };
+    }
+
+    test_sweep_frequency! {
+        linear_hold: Sweep::Linear, AfterSweep::Hold => [(0.1, 115.0), (1.0, 250.0), (1.9, 385.0), (3.0, 400.0), (10.0, 400.0)]
+        linear_repeat: Sweep::Linear, AfterSweep::Repeat => [(1.0, 250.0), (2.1, 115.0), (3.0, 250.0), (5.9, 385.0)]
+        linear_ping_pong: Sweep::Linear, AfterSweep::PingPong => [(1.0, 250.0), (2.1, 385.0), (3.9, 115.0), (4.1, 115.0)]
+        exponential_hold: Sweep::Exponential, AfterSweep::Hold => [(1.0, 200.0), (1.5, 282.8), (5.0, 400.0)]
+        exponential_ping_pong: Sweep::Exponential, AfterSweep::PingPong => [(1.0, 200.0), (3.0, 200.0), (5.0, 200.0)]
+        hyperbolic_hold: Sweep::Hyperbolic, AfterSweep::Hold => [(0.5, 123.1), (1.0, 160.0), (1.5, 228.6), (2.5, 400.0)]
+    }
+
+    macro_rules! test_phase_continuity {
+        ($($name:ident: $sweep:expr, $after:expr)*) => {
+            $(
+                paste! {
+                    #[test]
+                    fn [<$name _chirp_is_continuous>]() {
+                        let chirp = PeriodicFunction::<f64>::chirp(100.0, 400.0, 2.0, 1.0, 0.0, $sweep, $after);
+
+                        for t in [2.0, 4.0, 6.0] {
+                            let before = chirp.sample(t - 1e-6);
+                            let after = chirp.sample(t + 1e-6);
+
+                            assert!(approx_eq!(f64, before, after, epsilon = 0.01), "discontinuity at {}", t);
+                        }
+                    }
+                }
+            )*
+        };
+    }
+
+    test_phase_continuity! {
+        linear_hold: Sweep::Linear, AfterSweep::Hold
+        linear_repeat: Sweep::Linear, AfterSweep::Repeat
+        linear_ping_pong: Sweep::Linear, AfterSweep::PingPong
+        exponential_repeat: Sweep::Exponential, AfterSweep::Repeat
+        exponential_ping_pong: Sweep::Exponential, AfterSweep::PingPong
+        hyperbolic_repeat: Sweep::Hyperbolic, AfterSweep::Repeat
+        hyperbolic_ping_pong: Sweep::Hyperbolic, AfterSweep::PingPong
+    }
+
+    #[test]
+    fn chirp_with_equal_frequencies_is_sine() {
+        let sine = PeriodicFunction::<f64>::sine(50.0, 2.0, 0.25);
+
+        for sweep in [Sweep::Linear, Sweep::Exponential, Sweep::Hyperbolic] {
+            let chirp = PeriodicFunction::<f64>::chirp(
+                50.0,
+                50.0,
+                1.0,
+                2.0,
+                0.25,
+                sweep,
+                AfterSweep::Repeat,
+            );
+
+            for t in [0.0, 0.1, 0.33, 0.9, 1.5, 7.25] {
+                assert!(approx_eq!(
+                    f64,
+                    chirp.sample(t),
+                    sine.sample(t),
+                    epsilon = EPS
+                ));
+            }
+        }
+    }
+
+    macro_rules! test_invalid_sweep {
+        ($($name:ident: $start:expr, $end:expr, $duration:expr, $sweep:expr => $error:expr)*) => {
+            $(
+                paste! {
+                    #[test]
+                    fn [<try_chirp_rejects_ $name>]() {
+                        let chirp = PeriodicFunction::<f64>::try_chirp($start, $end, $duration, 1.0, 0.0, $sweep, AfterSweep::Hold);
+
+                        assert_eq!(Some($error), chirp.err());
+                    }
+                }
+            )*
+        };
+    }
+
+    test_invalid_sweep! {
+        zero_duration: 10.0, 100.0, 0.0, Sweep::Linear => Error::InvalidDuration
+        negative_duration: 10.0, 100.0, -1.0, Sweep::Exponential => Error::InvalidDuration
+        infinite_duration: 10.0, 100.0, f64::INFINITY, Sweep::Linear => Error::InvalidDuration
+        nan_start: f64::NAN, 100.0, 1.0, Sweep::Linear => Error::InvalidFrequency
+        infinite_end: 10.0, f64::INFINITY, 1.0, Sweep::Hyperbolic => Error::InvalidFrequency
+        zero_exponential_start: 0.0, 100.0, 1.0, Sweep::Exponential => Error::InvalidFrequency
+        zero_hyperbolic_end: 10.0, 0.0, 1.0, Sweep::Hyperbolic => Error::InvalidFrequency
+        exponential_sign_change: -10.0, 100.0, 1.0, Sweep::Exponential => Error::InvalidFrequency
+        hyperbolic_sign_change: 10.0, -100.0, 1.0, Sweep::Hyperbolic => Error::InvalidFrequency
+    }
+
+    #[test]
+    fn linear_chirp_may_start_at_zero_frequency() {
+        assert!(PeriodicFunction::<f64>::try_chirp(
+            0.0,
+            100.0,
+            1.0,
+            1.0,
+            0.0,
+            Sweep::Linear,
+            AfterSweep::Hold
+        )
+        .is_ok());
+    }
+
+    #[test]
+    #[should_panic(expected = "duration")]
+    fn chirp_panics_on_zero_duration() {
+        let _ = PeriodicFunction::<f64>::chirp(
+            10.0,
+            100.0,
+            0.0,
+            1.0,
+            0.0,
+            Sweep::Linear,
+            AfterSweep::Hold,
+        );
+    }
+}
@@ -0,0 +1,751 @@
+//! Statically dispatched waveform components.
+
+#[cfg(feature = "alloc")]
+use crate::PeriodicFunction;
+use crate::{
+    into_target_type_sanitized, reduced_time, samples_per_period, Error, Precision, SampleType, Two,
+};
+use core::marker::PhantomData;
+
+/// A component of a [`StaticWaveform`], sampled at a given time.
+///
+/// Unlike the boxed [`PeriodicFunction`], which is also a [`Component`], the built-in [`Sine`], [`Square`],
+/// [`Sawtooth`] and [`DcBias`] components are plain structs, so summing them up in a tuple or an array lets the
+/// compiler inline every one of them.
+pub trait Component<P: Precision> {
+    /// Returns the sample value at time `t`, in seconds.
+    fn sample(&self, t: P) -> P;
+
+    /// Frequency the component repeats with, if known.
+    ///
+    /// Time passed to [`Component::sample`] is reduced modulo the period of this frequency, so the phase does not
+    /// drift after a long time of sampling.
+    fn frequency(&self) -> Option<P> {
+        None
+    }
+
+    /// Returns the value of the sample at `index`, at given `sample_rate`.
+    ///
+    /// Tuples and arrays of components sum the samples of their members, each at its own reduced time.
+    #[inline]
+    fn sample_at(&self, index: u64, sample_rate: f64) -> P {
+        let time = reduced_time(
+            index,
+            sample_rate,
+            samples_per_period(sample_rate, self.frequency()),
+        );
+
+        self.sample(time)
+    }
+
+    /// Adds the sample values at `times` to `samples`, pairwise.
+    ///
+    /// This is the block path of [`WaveformIterator::fill`](crate::WaveformIterator::fill). The built-in components
+    /// are sampled in a plain loop over the block, which the compiler can inline and vectorise.
+    #[inline]
+    fn add_samples(&self, times: &[P], samples: &mut [P]) {
+        for (y, &t) in samples.iter_mut().zip(times) {
+            *y = *y + self.sample(t);
+        }
+    }
+
+    /// Adapts the component to the `sample_rate` of the [`StaticWaveform`] it is added to.
+    ///
+    /// This is what makes band-limited [`PeriodicFunction`]s aware of the sample rate. Other components are returned
+    /// as they are.
+    #[inline]
+    #[must_use]
+    fn bound(self, _sample_rate: P) -> Self
+    where
+        Self: Sized,
+    {
+        self
+    }
+}
+
+/// Makes the frequency known only if it is normal, same as for the built-in [`PeriodicFunction`]s.
+#[inline]
+fn normal<P: Precision>(frequency: P) -> Option<P> {
+    let frequency = frequency.abs();
+
+    if frequency.is_normal() {
+        Some(frequency)
+    } else {
+        None
+    }
+}
+
+macro_rules! periodic_component {
+    ($(#[$meta:meta])* $name:ident, $builder:ident, |$self:ident, $t:ident| $sample:expr) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq)]
+        pub struct $name<P = f32> {
+            /// Frequency, in Hz.
+            pub frequency: P,
+            /// Amplitude.
+            pub amplitude: P,
+            /// Phase, as a fraction of the period.
+            pub phase: P,
+        }
+
+        impl<P: Precision> $name<P> {
+            /// Initializes new component with given parameters.
+            #[inline]
+            pub fn new(frequency: impl Into<P>, amplitude: impl Into<P>, phase: impl Into<P>) -> Self {
+                Self {
+                    frequency: frequency.into(),
+                    amplitude: amplitude.into(),
+                    phase: phase.into(),
+                }
+            }
+        }
+
+        impl<P: Precision> Component<P> for $name<P> {
+            #[inline]
+            fn sample(&$self, $t: P) -> P {
+                $sample
+            }
+
+            #[inline]
+            fn frequency(&self) -> Option<P> {
+                normal(self.frequency)
+            }
+        }
+
+        #[cfg(feature = "alloc")]
+        impl<P: Precision> From<$name<P>> for PeriodicFunction<P> {
+            fn from(component: $name<P>) -> Self {
+                PeriodicFunction::$builder(component.frequency, component.amplitude, component.phase)
+            }
+        }
+    };
+}
+
+periodic_component!(
+    /// Sine component, same as the one built by [`PeriodicFunction::sine`].
+    Sine,
+    sine,
+    |self, t| {
+        let radians = (P::two() * P::PI() * self.frequency * t) + (self.phase * P::two() * P::PI());
+
+        radians.sin() * self.amplitude
+    }
+);
+
+periodic_component!(
+    /// Square component, same as the one built by [`PeriodicFunction::square`].
+    Square,
+    square,
+    |self, t| {
+        let power = (P::two() * (t - self.phase) * self.frequency).floor();
+
+        self.amplitude * (-P::one()).powf(power)
+    }
+);
+
+periodic_component!(
+    /// Sawtooth component, same as the one built by [`PeriodicFunction::sawtooth`].
+    Sawtooth,
+    sawtooth,
+    |self, t| {
+        P::two() * self.amplitude * (t * self.frequency + self.phase).fract() - self.amplitude
+    }
+);
+
+/// DC bias component, same as the one built by [`PeriodicFunction::dc_bias`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DcBias<P = f32> {
+    /// Constant value of the component.
+    pub bias: P,
+}
+
+impl<P: Precision> DcBias<P> {
+    /// Initializes new component with given bias.
+    #[inline]
+    pub fn new(bias: impl Into<P>) -> Self {
+        Self { bias: bias.into() }
+    }
+}
+
+impl<P: Precision> Component<P> for DcBias<P> {
+    #[inline]
+    fn sample(&self, _: P) -> P {
+        self.bias
+    }
+
+    #[inline]
+    fn sample_at(&self, _: u64, _: f64) -> P {
+        self.bias
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<P: Precision> From<DcBias<P>> for PeriodicFunction<P> {
+    fn from(component: DcBias<P>) -> Self {
+        PeriodicFunction::dc_bias(component.bias)
+    }
+}
+
+/// Any of the built-in components, or a plain function, so that different components can be put in one array.
+///
+/// Together with [`StaticWaveform`], it makes up a waveform that needs no heap allocation at all:
+///
+/// ```
+/// use wavegen::{DcBias, Oscillator, Sine, Square, StaticWaveform};
+///
+/// let wf = StaticWaveform::<i16, _>::new(
+///     8000.,
+///     [
+///         Oscillator::from(Sine::new(440., 8000., 0.)),
+///         Square::new(1., 1000., 0.).into(),
+///         DcBias::new(100.).into(),
+///         Oscillator::Custom(|t| 500. * t),
+///     ],
+/// );
+/// let mut buffer = [0i16; 64];
+///
+/// assert_eq!(64, wf.iter().fill(&mut buffer));
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub enum Oscillator<P = f32> {
+    /// [`Sine`] component.
+    Sine(Sine<P>),
+    /// [`Square`] component.
+    Square(Square<P>),
+    /// [`Sawtooth`] component.
+    Sawtooth(Sawtooth<P>),
+    /// [`DcBias`] component.
+    DcBias(DcBias<P>),
+    /// Function of time, with no known frequency.
+    Custom(fn(P) -> P),
+}
+
+impl<P: Precision> Component<P> for Oscillator<P> {
+    #[inline]
+    fn sample(&self, t: P) -> P {
+        match self {
+            Oscillator::Sine(c) => c.sample(t),
+            Oscillator::Square(c) => c.sample(t),
+            Oscillator::Sawtooth(c) => c.sample(t),
+            Oscillator::DcBias(c) => c.sample(t),
+            Oscillator::Custom(f) => f(t),
+        }
+    }
+
+    #[inline]
+    fn frequency(&self) -> Option<P> {
+        match self {
+            Oscillator::Sine(c) => c.frequency(),
+            Oscillator::Square(c) => c.frequency(),
+            Oscillator::Sawtooth(c) => c.frequency(),
+            Oscillator::DcBias(c) => c.frequency(),
+            Oscillator::Custom(_) => None,
+        }
+    }
+
+    #[inline]
+    fn add_samples(&self, times: &[P], samples: &mut [P]) {
+        match self {
+            Oscillator::Sine(c) => c.add_samples(times, samples),
+            Oscillator::Square(c) => c.add_samples(times, samples),
+            Oscillator::Sawtooth(c) => c.add_samples(times, samples),
+            Oscillator::DcBias(c) => c.add_samples(times, samples),
+            Oscillator::Custom(f) => {
+                for (y, &t) in samples.iter_mut().zip(times) {
+                    *y = *y + f(t);
+                }
+            }
+        }
+    }
+}
+
+macro_rules! oscillator_from {
+    ($($name:ident)*) => {
+        $(
+            impl<P> From<$name<P>> for Oscillator<P> {
+                fn from(component: $name<P>) -> Self {
+                    Oscillator::$name(component)
+                }
+            }
+        )*
+    };
+}
+
+oscillator_from!(Sine Square Sawtooth DcBias);
+
+#[cfg(feature = "alloc")]
+impl<P: Precision> Component<P> for PeriodicFunction<P> {
+    #[inline]
+    fn sample(&self, t: P) -> P {
+        PeriodicFunction::sample(self, t)
+    }
+
+    #[inline]
+    fn frequency(&self) -> Option<P> {
+        self.frequency
+    }
+
+    #[inline]
+    fn sample_at(&self, index: u64, sample_rate: f64) -> P {
+        if let Some(clock) = &self.clock {
+            return clock(index);
+        }
+
+        let time = reduced_time(
+            index,
+            sample_rate,
+            samples_per_period(sample_rate, self.frequency),
+        );
+
+        PeriodicFunction::sample(self, time)
+    }
+
+    #[inline]
+    fn bound(self, sample_rate: P) -> Self {
+        PeriodicFunction::bound(self, sample_rate)
+    }
+}
+
+impl<P: Precision, C: Component<P>, const N: usize> Component<P> for [C; N] {
+    #[inline]
+    fn sample(&self, t: P) -> P {
+        self.iter().map(|c| c.sample(t)).sum()
+    }
+
+    #[inline]
+    fn sample_at(&self, index: u64, sample_rate: f64) -> P {
+        self.iter().map(|c| c.sample_at(index, sample_rate)).sum()
+    }
+
+    #[inline]
+    fn bound(self, sample_rate: P) -> Self {
+        self.map(|c| c.bound(sample_rate))
+    }
+}
+
+macro_rules! tuple_component {
+    ($($c:ident: $i:tt),+) => {
+        impl<P: Precision, $($c: Component<P>),+> Component<P> for ($($c,)+) {
+            #[inline]
+            fn sample(&self, t: P) -> P {
+                P::zero() $(+ self.$i.sample(t))+
+            }
+
+            #[inline]
+            fn sample_at(&self, index: u64, sample_rate: f64) -> P {
+                P::zero() $(+ self.$i.sample_at(index, sample_rate))+
+            }
+
+            #[inline]
+            fn bound(self, sample_rate: P) -> Self {
+                ($(self.$i.bound(sample_rate),)+)
+            }
+        }
+    };
+}
+
+tuple_component!(A: 0);
+tuple_component!(A: 0, B: 1);
+tuple_component!(A: 0, B: 1, C: 2);
+tuple_component!(A: 0, B: 1, C: 2, D: 3);
+tuple_component!(A: 0, B: 1, C: 2, D: 3, E: 4);
+tuple_component!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5);
+tuple_component!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6);
+tuple_component!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6, H: 7);
+
+/// A [`Waveform`](crate::Waveform) variant, generic over its components.
+///
+/// The components are usually a tuple or an array of [`Component`]s, known at compile time, so sampling them needs
+/// no indirect calls. With the `alloc` feature, boxed [`PeriodicFunction`]s can still be mixed in, as one of the tuple
+/// members.
+///
+/// Unlike [`Waveform`](crate::Waveform), it does not allocate, so it is also available without the `alloc` feature, on
+/// targets with no global allocator.
+///
+/// # Examples
+///
+/// ```
+/// use wavegen::{sine, DcBias, Sine, Square, StaticWaveform, Waveform};
+///
+/// let wf = StaticWaveform::<f32, _>::new(100.0, (Sine::new(1., 1., 0.), Square::new(3., 1., 0.), DcBias::new(0.5)));
+/// let boxed = Waveform::<f32>::with_components(100.0, vec![sine!(1.), Square::new(3., 1., 0.).into(), DcBias::new(0.5).into()]);
+///
+/// assert_eq!(boxed.iter().take(100).collect::<Vec<_>>(), wf.iter().take(100).collect::<Vec<_>>());
+/// ```
+#[derive(Debug, Clone)]
+pub struct StaticWaveform<T: SampleType, C, P: Precision = f32> {
+    sample_rate: P,
+    components: C,
+    _phantom: PhantomData<T>,
+}
+
+impl<T: SampleType, C: Component<P>, P: Precision> StaticWaveform<T, C, P> {
+    /// Initializes new [`StaticWaveform`] with given components.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if `sample_rate` is not a finite, positive, non-zero number.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wavegen::{Sine, StaticWaveform};
+    ///
+    /// let wf = StaticWaveform::<i16, _>::new(44100.0, [Sine::new(440., 1000., 0.), Sine::new(660., 1000., 0.)]);
+    /// ```
+    pub fn new(sample_rate: impl Into<P>, components: C) -> Self {
+        match Self::try_new(sample_rate, components) {
+            Ok(wf) => wf,
+            Err(error) => panic!("{}", error),
+        }
+    }
+
+    /// Fallible version of [`StaticWaveform::new`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidSampleRate`] if `sample_rate` is not a finite, positive, non-zero number.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wavegen::{Error, Sine, StaticWaveform};
+    ///
+    /// assert_eq!(
+    ///     Some(Error::InvalidSampleRate),
+    ///     StaticWaveform::<f32, _>::try_new(0.0, Sine::new(1., 1., 0.)).err()
+    /// );
+    /// ```
+    pub fn try_new(sample_rate: impl Into<P>, components: C) -> Result<Self, Error> {
+        let sample_rate = sample_rate.into();
+        if !(sample_rate.is_normal() && sample_rate.is_sign_positive()) {
+            return Err(Error::InvalidSampleRate);
+        }
+
+        Ok(StaticWaveform {
+            sample_rate,
+            components: components.bound(sample_rate),
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Gets sample rate of this [`StaticWaveform`].
+    pub fn sample_rate(&self) -> &P {
+        &self.sample_rate
+    }
+
+    /// Returns the components this [`StaticWaveform`] consists of.
+    pub fn components(&self) -> &C {
+        &self.components
+    }
+
+    /// Returns an iterator over this [`StaticWaveform`] samples.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wavegen::{DcBias, StaticWaveform};
+    ///
+    /// let wf = StaticWaveform::<f32, _>::new(100.0, (DcBias::new(1.), DcBias::new(2.)));
+    ///
+    /// assert_eq!(vec![3.0; 5], wf.iter().take(5).collect::<Vec<_>>());
+    /// ```
+    pub fn iter(&self) -> StaticWaveformIterator<'_, T, C, P> {
+        StaticWaveformIterator {
+            inner: self,
+            sample_rate: self.sample_rate.to_f64().unwrap_or(f64::NAN),
+            index: 0,
+        }
+    }
+}
+
+impl<'a, T: SampleType, C: Component<P>, P: Precision> IntoIterator
+    for &'a StaticWaveform<T, C, P>
+{
+    type Item = T;
+
+    type IntoIter = StaticWaveformIterator<'a, T, C, P>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An iterator that allows to sample a [`StaticWaveform`].
+///
+/// Just like [`WaveformIterator`](crate::WaveformIterator), it ends early only if a sample turns out to be NaN, and the
+/// sample type cannot represent it.
+#[derive(Debug)]
+pub struct StaticWaveformIterator<'a, T: SampleType, C, P: Precision> {
+    inner: &'a StaticWaveform<T, C, P>,
+    sample_rate: f64,
+    index: u64,
+}
+
+impl<T: SampleType, C, P: Precision> Clone for StaticWaveformIterator<'_, T, C, P> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner,
+            sample_rate: self.sample_rate,
+            index: self.index,
+        }
+    }
+}
+
+impl<T: SampleType, C: Component<P>, P: Precision> StaticWaveformIterator<'_, T, C, P> {
+    /// Fills `buffer` with the next samples, advancing the iterator by its length.
+    ///
+    /// Returns the number of samples written, which is less than the length of `buffer` only if a sample turned out to
+    /// be NaN, and the sample type cannot represent it. The rest of `buffer` is left untouched in that case, and the
+    /// iterator is advanced just past the NaN sample, as if it was yielded by [`Iterator::next`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wavegen::{Sine, StaticWaveform};
+    ///
+    /// let wf = StaticWaveform::<f32, _>::new(44100.0, Sine::new(440., 1., 0.));
+    /// let mut buffer = [0.0; 512];
+    ///
+    /// assert_eq!(512, wf.iter().fill(&mut buffer));
+    /// assert_eq!(wf.iter().take(512).collect::<Vec<_>>(), buffer);
+    /// ```
+    pub fn fill(&mut self, buffer: &mut [T]) -> usize {
+        for (i, y) in buffer.iter_mut().enumerate() {
+            let sample = self.raw_sample(self.index);
+            self.index = self.index.wrapping_add(1);
+
+            match into_target_type_sanitized(sample) {
+                Some(sample) => *y = sample,
+                None => return i,
+            }
+        }
+
+        buffer.len()
+    }
+
+    #[inline]
+    fn raw_sample(&self, index: u64) -> P {
+        self.inner.components.sample_at(index, self.sample_rate)
+    }
+}
+
+impl<T: SampleType, C: Component<P>, P: Precision> Iterator
+    for StaticWaveformIterator<'_, T, C, P>
+{
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let sample = self.raw_sample(self.index);
+        self.index = self.index.wrapping_add(1);
+
+        into_target_type_sanitized(sample)
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.index = self.index.wrapping_add(n as u64);
+
+        self.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use float_cmp::approx_eq;
+
+    #[test]
+    fn fill_matches_iterator() {
+        let wf = StaticWaveform::<i16, _>::new(
+            8000.0,
+            [Sine::new(440., 1000., 0.), Sine::new(660., 1000., 0.)],
+        );
+        let mut iter = wf.iter();
+        let mut buffer = [0; 300];
+
+        assert_eq!(100, iter.fill(&mut buffer[..100]));
+        assert_eq!(200, iter.fill(&mut buffer[100..]));
+        assert!(wf.iter().zip(buffer.iter()).all(|(y, &b)| y == b));
+    }
+
+    #[test]
+    fn fill_stops_at_nan() {
+        let wf = StaticWaveform::<i16, _, f64>::new(100.0, DcBias::new(f64::NAN));
+        let mut buffer = [-1; 4];
+
+        assert_eq!(0, wf.iter().fill(&mut buffer));
+        assert_eq!([-1; 4], buffer);
+    }
+
+    #[test]
+    fn fill_advances_just_past_nan() {
+        let wf = StaticWaveform::<i16, _, f64>::new(
+            100.0,
+            Oscillator::Custom(|t| {
+                if (0.1..0.11).contains(&t) {
+                    f64::NAN
+                } else {
+                    1.0
+                }
+            }),
+        );
+        let mut iter = wf.iter();
+        let mut buffer = [-1; 20];
+
+        assert_eq!(10, iter.fill(&mut buffer));
+        assert_eq!([1, -1], buffer[9..11]);
+        assert_eq!(9, iter.fill(&mut buffer[11..]));
+        assert_eq!([1; 9], buffer[11..]);
+    }
+
+    #[test]
+    fn oscillator_blocks_match_samples() {
+        let times: [f64; 5] = [0.0, 0.013, 0.25, 0.4, 1.7];
+        for oscillator in [
+            Oscillator::from(Sine::new(7., 2., 0.25)),
+            Square::new(7., 2., 0.25).into(),
+            Sawtooth::new(7., 2., 0.25).into(),
+            DcBias::new(-3.).into(),
+            Oscillator::Custom(|t| t * 2.0),
+        ] {
+            let mut samples = [1.0; 5];
+            oscillator.add_samples(&times, &mut samples);
+
+            for (y, &t) in samples.iter().zip(&times) {
+                assert!(approx_eq!(f64, *y, 1.0 + oscillator.sample(t)));
+            }
+        }
+    }
+
+    #[test]
+    fn oscillators_match_their_components() {
+        let square = Square::new(7., 2., 0.25);
+        let oscillators: [Oscillator<f64>; 5] = [
+            Sine::new(7., 2., 0.25).into(),
+            square.into(),
+            Sawtooth::new(7., 2., 0.25).into(),
+            DcBias::new(-3.).into(),
+            Oscillator::Custom(|t| t * 2.0),
+        ];
+        let wf = StaticWaveform::<f64, _, f64>::new(100.0, oscillators);
+        let expected = StaticWaveform::<f64, _, f64>::new(
+            100.0,
+            (
+                Sine::new(7., 2., 0.25),
+                square,
+                Sawtooth::new(7., 2., 0.25),
+                DcBias::new(-3.),
+            ),
+        );
+
+        assert_eq!(Some(7.0), oscillators[1].frequency());
+        assert_eq!(None, oscillators[4].frequency());
+        assert!((0..1000)
+            .zip(wf.iter().zip(expected.iter()))
+            .all(|(i, (y, e))| approx_eq!(f64, y, e + f64::from(i) / 50.0)));
+    }
+
+    #[test]
+    fn invalid_sample_rate_is_rejected() {
+        for sample_rate in [0.0, -1.0, f32::NAN, f32::INFINITY] {
+            assert_eq!(
+                Some(Error::InvalidSampleRate),
+                StaticWaveform::<f32, _>::try_new(sample_rate, DcBias::new(1.)).err()
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "sample rate has to be a finite, positive, non-zero number")]
+    fn new_panics_on_invalid_sample_rate() {
+        StaticWaveform::<f32, _>::new(0.0, DcBias::new(1.));
+    }
+
+    #[cfg(feature = "alloc")]
+    mod periodic_function {
+        use super::*;
+        use crate::{dc_bias, sawtooth, sine, square, BandLimit, PeriodicFunction, Waveform};
+        use alloc::{vec, vec::Vec};
+        use paste::paste;
+
+        macro_rules! test_matches_builder {
+            ($($name:ident: $component:expr => [$($function:expr),+])*) => {
+                $(
+                    paste! {
+                        #[test]
+                        fn [<$name _matches_periodic_function>]() {
+                            let wf = StaticWaveform::<f64, _, f64>::new(1000.0, $component);
+                            let boxed = Waveform::<f64, f64>::with_components(1000.0, vec![$($function),+]);
+
+                            assert_eq!(
+                                boxed.iter().take(5000).collect::<Vec<_>>(),
+                                wf.iter().take(5000).collect::<Vec<_>>()
+                            );
+                        }
+                    }
+                )*
+            };
+        }
+
+        test_matches_builder! {
+            sine: Sine::new(7., 2., 0.25) => [sine!(7., 2., 0.25)]
+            square: Square::new(7., 2., 0.25) => [square!(7., 2., 0.25)]
+            sawtooth: Sawtooth::new(7., 2., 0.25) => [sawtooth!(7., 2., 0.25)]
+            dc_bias: DcBias::new(-3.) => [dc_bias!(-3.)]
+            tuple: (Sine::new(7., 2., 0.), DcBias::new(1.), Square::new(3., 1., 0.)) => [sine!(7., 2.), dc_bias!(1.), square!(3.)]
+            array: [Sine::new(7., 1., 0.), Sine::new(11., 1., 0.)] => [sine!(7.), sine!(11.)]
+            periodic_function: sine!(7., 2.) * square!(1.) => [sine!(7., 2.) * square!(1.)]
+            mixed: (Sine::new(7., 1., 0.), sawtooth!(3.)) => [sine!(7.), sawtooth!(3.)]
+        }
+
+        #[test]
+        fn components_convert_into_periodic_functions() {
+            let functions: [PeriodicFunction<f64>; 4] = [
+                Sine::new(7., 2., 0.25).into(),
+                Square::new(7., 2., 0.25).into(),
+                Sawtooth::new(7., 2., 0.25).into(),
+                DcBias::new(-3.).into(),
+            ];
+            let expected: [PeriodicFunction<f64>; 4] = [
+                sine!(7., 2., 0.25),
+                square!(7., 2., 0.25),
+                sawtooth!(7., 2., 0.25),
+                dc_bias!(-3.),
+            ];
+
+            for (f, e) in functions.iter().zip(expected.iter()) {
+                assert_eq!(e.kind(), f.kind());
+            }
+        }
+
+        #[test]
+        fn band_limited_function_is_bound() {
+            let square =
+                || PeriodicFunction::band_limited_square(1000., 1., 0., BandLimit::PolyBlep);
+            let wf = StaticWaveform::<f64, _, f64>::new(8000.0, (square(),));
+            let boxed = Waveform::<f64, f64>::with_components(8000.0, vec![square()]);
+
+            assert_eq!(
+                boxed.iter().take(500).collect::<Vec<_>>(),
+                wf.iter().take(500).collect::<Vec<_>>()
+            );
+        }
+
+        #[test]
+        fn time_is_reduced_per_component() {
+            let wf =
+                StaticWaveform::<f32, _>::new(44100.0, (Sine::new(1000., 1., 0.), DcBias::new(1.)));
+            let boxed = Waveform::<f32>::with_components(44100.0, vec![sine!(1000.), dc_bias!(1.)]);
+
+            assert_eq!(
+                boxed.iter().nth(1_000_000_000),
+                wf.iter().nth(1_000_000_000)
+            );
+        }
+    }
+}
@@ -0,0 +1,472 @@
+//! Direct digital synthesis (DDS) emulation.
+
+use crate::noise::{draw_index, hash};
+use crate::{finite, Error, Kind, PeriodicFunction, Precision, Two};
+use num_traits::{Float, NumCast, ToPrimitive};
+
+/// Stream used to draw the phase dither.
+const DITHER_STREAM: u64 = 1 << 34;
+
+/// Number of distinct values of a `bits` wide word.
+#[inline]
+fn steps<P: Precision>(bits: u32) -> P {
+    NumCast::from(1u128 << bits).unwrap_or_else(P::nan)
+}
+
+/// Parameters of an emulated direct digital synthesis core, see [`PeriodicFunction::dds`].
+///
+/// Every clock cycle, the core adds a tuning word to an `accumulator_bits` wide phase accumulator, truncates the
+/// accumulated phase to its top `phase_bits`, and looks the sine of the truncated phase up in a table of
+/// `amplitude_bits` wide signed values.
+///
+/// # Examples
+///
+/// ```
+/// use wavegen::Dds;
+///
+/// let dds = Dds::new(32, 12, 14).with_dither(42);
+///
+/// assert_eq!(Some(42), dds.dither);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Dds {
+    /// Width of the phase accumulator, from 1 to 64 bits. The frequency resolution is `sample_rate / 2^accumulator_bits`.
+    pub accumulator_bits: u32,
+    /// Width of the truncated phase addressing the sine table, from 1 to `accumulator_bits` bits.
+    pub phase_bits: u32,
+    /// Width of the signed output values, from 2 to 32 bits.
+    pub amplitude_bits: u32,
+    /// Seed of the phase dither, if any.
+    ///
+    /// A uniformly distributed value, smaller than the least significant bit of the truncated phase, is added to the
+    /// accumulator before truncation. This spreads the truncation spurs into a noise floor.
+    pub dither: Option<u64>,
+}
+
+impl Dds {
+    /// New core with given widths, in bits, and no dither.
+    #[must_use]
+    pub const fn new(accumulator_bits: u32, phase_bits: u32, amplitude_bits: u32) -> Self {
+        Self {
+            accumulator_bits,
+            phase_bits,
+            amplitude_bits,
+            dither: None,
+        }
+    }
+
+    /// Enables the phase dither, drawn from given `seed`.
+    #[must_use]
+    pub const fn with_dither(self, seed: u64) -> Self {
+        Self {
+            dither: Some(seed),
+            ..self
+        }
+    }
+
+    /// Tuning word, that is the phase increment per clock cycle, programmed for `frequency` at `sample_rate`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wavegen::Dds;
+    ///
+    /// assert_eq!(1 << 28, Dds::new(32, 12, 14).tuning_word(3000.0, 48000.0));
+    /// ```
+    pub fn tuning_word<P: Precision>(&self, frequency: P, sample_rate: P) -> u64 {
+        self.word(frequency / sample_rate)
+    }
+
+    /// Frequency actually synthesised for `frequency` at `sample_rate`, that is `frequency` rounded to the frequency
+    /// resolution of the core.
+    ///
+    /// Comparing the output of the core against an ideal sine of this frequency isolates the truncation and
+    /// quantisation errors from the frequency error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wavegen::Dds;
+    ///
+    /// assert_eq!(1000.0, Dds::new(8, 8, 8).output_frequency(1010.0, 64000.0));
+    /// ```
+    pub fn output_frequency<P: Precision>(&self, frequency: P, sample_rate: P) -> P {
+        let word: P =
+            NumCast::from(self.tuning_word(frequency, sample_rate)).unwrap_or_else(P::nan);
+
+        word * sample_rate / steps(self.accumulator_bits)
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        if (1..=64).contains(&self.accumulator_bits)
+            && (1..=self.accumulator_bits).contains(&self.phase_bits)
+            && (2..=32).contains(&self.amplitude_bits)
+        {
+            Ok(())
+        } else {
+            Err(Error::InvalidBitWidth)
+        }
+    }
+
+    #[inline]
+    fn mask(&self) -> u64 {
+        u64::MAX >> (64 - self.accumulator_bits)
+    }
+
+    /// Accumulator word of `periods`, wrapped to a single period.
+    #[inline]
+    #[allow(clippy::cast_possible_truncation)]
+    fn word<P: Precision>(&self, periods: P) -> u64 {
+        let periods = periods.to_f64().unwrap_or(0.0);
+        let fraction = periods - Float::floor(periods);
+        let word = Float::round(fraction * steps::<f64>(self.accumulator_bits));
+
+        (ToPrimitive::to_u128(&word).unwrap_or(0) as u64) & self.mask()
+    }
+
+    /// Output of the core at clock cycle `n`, in `[-1, 1]` range.
+    #[inline]
+    fn sample<P: Precision>(&self, n: u64, tuning_word: u64, phase_word: u64) -> P {
+        let mask = self.mask();
+        let truncated_bits = self.accumulator_bits - self.phase_bits;
+
+        let mut accumulator = tuning_word.wrapping_mul(n).wrapping_add(phase_word) & mask;
+        if let (Some(seed), true) = (self.dither, truncated_bits > 0) {
+            let dither = hash(seed, DITHER_STREAM, n) >> (64 - truncated_bits);
+            accumulator = accumulator.wrapping_add(dither) & mask;
+        }
+
+        let phase: P = NumCast::from(accumulator >> truncated_bits).unwrap_or_else(P::zero);
+        let full_scale = steps::<P>(self.amplitude_bits - 1) - P::one();
+        let value = (P::two() * P::PI() * phase / steps(self.phase_bits)).sin();
+
+        (value * full_scale).round() / full_scale
+    }
+}
+
+impl<P: Precision> PeriodicFunction<P> {
+    /// Direct digital synthesis (DDS) sine builder, emulating the `dds` core.
+    ///
+    /// The core is clocked at the sample rate of the [`Waveform`] the function is added to. Until then, the function
+    /// is the ideal sine the core approximates. The output carries the frequency error of the tuning word, the spurs
+    /// of the phase truncation, and the noise of the amplitude quantisation, which can be studied against
+    /// [`PeriodicFunction::sine`] of [`Dds::output_frequency`].
+    ///
+    /// The core is clocked by the sample index, so it does not drift, however long it runs, in either precision.
+    /// Inside functions derived from it, such as sums or products, the clock cycle is recovered from the sample time
+    /// instead, so use `f64` precision there for runs longer than `2^24` samples.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if the widths of the `dds` core are out of range. See [`PeriodicFunction::try_dds`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wavegen::{sine, Dds, PeriodicFunction, Waveform};
+    ///
+    /// let dds = Dds::new(32, 8, 10);
+    /// let wf = Waveform::<f64, f64>::with_components(48000.0, vec![PeriodicFunction::dds(1000.0, 1.0, 0.0, dds)]);
+    /// let ideal = Waveform::<f64, f64>::with_components(48000.0, vec![sine!(dds.output_frequency(1000.0, 48000.0))]);
+    ///
+    /// assert!(wf.iter().zip(ideal.iter()).take(1000).all(|(x, y)| (x - y).abs() < 0.03));
+    /// ```
+    ///
+    /// [`Waveform`]: crate::Waveform
+    #[inline]
+    pub fn dds(
+        frequency: impl Into<P>,
+        amplitude: impl Into<P>,
+        phase: impl Into<P>,
+        dds: Dds,
+    ) -> Self {
+        match Self::try_dds(frequency, amplitude, phase, dds) {
+            Ok(f) => f,
+            Err(error) => panic!("{}", error),
+        }
+    }
+
+    /// Fallible version of [`PeriodicFunction::dds`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidBitWidth`] if the widths of the `dds` core are out of range, or another [`Error`] if
+    /// any of the other parameters is not a finite number.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wavegen::{Dds, Error, PeriodicFunction};
+    ///
+    /// assert!(PeriodicFunction::<f32>::try_dds(50.0, 1.0, 0.0, Dds::new(32, 12, 14)).is_ok());
+    /// assert_eq!(
+    ///     Some(Error::InvalidBitWidth),
+    ///     PeriodicFunction::<f32>::try_dds(50.0, 1.0, 0.0, Dds::new(32, 33, 14)).err()
+    /// );
+    /// ```
+    #[inline]
+    pub fn try_dds(
+        frequency: impl Into<P>,
+        amplitude: impl Into<P>,
+        phase: impl Into<P>,
+        dds: Dds,
+    ) -> Result<Self, Error> {
+        let frequency = finite(frequency.into(), Error::InvalidFrequency)?;
+        let amplitude = finite(amplitude.into(), Error::InvalidAmplitude)?;
+        let phase = finite(phase.into(), Error::InvalidPhase)?;
+        dds.validate()?;
+
+        let mut f = Self::sine(frequency, amplitude, phase)
+            .with_bind(move |sample_rate| {
+                let tuning_word = dds.tuning_word(frequency, sample_rate);
+                let phase_word = dds.word(phase);
+
+                move |t| {
+                    amplitude * dds.sample::<P>(draw_index(t, sample_rate), tuning_word, phase_word)
+                }
+            })
+            .with_clock(move |sample_rate| {
+                let tuning_word = dds.tuning_word(frequency, sample_rate);
+                let phase_word = dds.word(phase);
+
+                move |n| amplitude * dds.sample::<P>(n, tuning_word, phase_word)
+            });
+        // inside derived functions, the accumulator has to see every clock cycle, so the time must not be reduced modulo
+        // period; the spectrum is kept, so that the fundamental is still checked against the Nyquist frequency
+        f.frequency = None;
+
+        Ok(f.with_kind(Kind::Dds {
+            frequency,
+            amplitude,
+            phase,
+            dds,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{steps, Dds};
+    use crate::{sine, Error, PeriodicFunction, StaticWaveform, Waveform};
+    use alloc::{vec, vec::Vec};
+    use paste::paste;
+
+    const SAMPLE_RATE: f64 = 48000.0;
+
+    fn render(f: PeriodicFunction<f64>, n: usize) -> Vec<f64> {
+        Waveform::<f64, f64>::with_components(SAMPLE_RATE, vec![f])
+            .iter()
+            .take(n)
+            .collect()
+    }
+
+    /// Largest difference between the core and the ideal sine of its output frequency.
+    fn max_error(frequency: f64, phase: f64, dds: Dds) -> f64 {
+        let ideal = sine!(dds.output_frequency(frequency, SAMPLE_RATE), 1., phase);
+
+        render(PeriodicFunction::dds(frequency, 1.0, phase, dds), 20_000)
+            .iter()
+            .zip(render(ideal, 20_000))
+            .map(|(x, y)| (x - y).abs())
+            .fold(0.0, f64::max)
+    }
+
+    macro_rules! test_error_bound {
+        ($($name:ident: $dds:expr)*) => {
+            $(
+                paste! {
+                    #[test]
+                    fn [<$name _error_is_bounded>]() {
+                        let dds: Dds = $dds;
+                        let truncation = 2.0 * core::f64::consts::PI / steps::<f64>(dds.phase_bits);
+                        let quantisation = 0.5 / (steps::<f64>(dds.amplitude_bits - 1) - 1.0);
+                        let error = max_error(1234.5, 0.1, dds);
+
+                        assert!(error <= truncation + quantisation + 1e-9, "error {}", error);
+                    }
+                }
+            )*
+        };
+    }
+
+    test_error_bound! {
+        coarse_phase: Dds::new(32, 6, 24)
+        coarse_amplitude: Dds::new(32, 24, 6)
+        typical: Dds::new(32, 12, 14)
+        full_phase: Dds::new(48, 48, 32)
+        narrow_accumulator: Dds::new(12, 12, 16)
+        dithered: Dds::new(32, 10, 16).with_dither(7)
+    }
+
+    #[test]
+    fn wide_core_matches_ideal_sine() {
+        assert!(max_error(440.0, 0.25, Dds::new(64, 32, 32)) < 1e-8);
+    }
+
+    #[test]
+    fn output_matches_integer_model() {
+        let dds = Dds::new(8, 4, 4);
+        // 1310.0 Hz at 48 kHz is 6.99 accumulator steps per sample, so the tuning word is 7
+        let samples = render(PeriodicFunction::dds(1310.0, 2.0, 0.0, dds), 300);
+
+        for (n, sample) in (0u32..).zip(samples) {
+            let phase = (7 * n % 256) >> 4;
+            let value = (2.0 * core::f64::consts::PI * f64::from(phase) / 16.0).sin();
+
+            assert!((sample - 2.0 * (value * 7.0).round() / 7.0).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn output_repeats_with_accumulator_period() {
+        // tuning word 10 in an 8 bit accumulator repeats every 256 / gcd(10, 256) = 128 cycles
+        let samples = render(
+            PeriodicFunction::dds(10.0 / 256.0 * SAMPLE_RATE, 1.0, 0.0, Dds::new(8, 6, 10)),
+            512,
+        );
+
+        assert_eq!(samples[..128], samples[128..256]);
+        assert_ne!(samples[..64], samples[64..128]);
+    }
+
+    #[test]
+    fn output_does_not_drift() {
+        let dds = Dds::new(32, 12, 14);
+        let wf = Waveform::<f64, f64>::with_components(
+            SAMPLE_RATE,
+            vec![PeriodicFunction::dds(997.0, 1.0, 0.0, dds)],
+        );
+        let tuning_word = dds.tuning_word(997.0, SAMPLE_RATE);
+
+        let sample = wf.iter().nth(10_000_000).unwrap_or(f64::NAN);
+
+        assert!((sample - dds.sample::<f64>(10_000_000, tuning_word, 0)).abs() < 1e-12);
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn single_precision_output_does_not_drift() {
+        let dds = Dds::new(32, 12, 14);
+        let wf = Waveform::<f32, f32>::with_components(
+            48000.0,
+            vec![PeriodicFunction::dds(997.0, 1.0, 0.0, dds)],
+        );
+        let tuning_word = dds.tuning_word::<f32>(997.0, 48000.0);
+        let mut buffer = [0.0; 4];
+        let mut iter = wf.iter();
+
+        let sample = iter.nth(20_000_000).unwrap_or(f32::NAN);
+        iter.fill(&mut buffer);
+
+        assert_eq!(dds.sample::<f32>(20_000_000, tuning_word, 0), sample);
+        for (n, y) in (20_000_001..).zip(buffer) {
+            assert_eq!(dds.sample::<f32>(n, tuning_word, 0), y);
+        }
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn single_precision_static_output_does_not_drift() {
+        let dds = Dds::new(32, 12, 14);
+        let wf = StaticWaveform::<f32, _, f32>::new(
+            48000.0,
+            (PeriodicFunction::dds(997.0, 1.0, 0.0, dds),),
+        );
+        let tuning_word = dds.tuning_word::<f32>(997.0, 48000.0);
+        let mut buffer = [0.0; 4];
+        let mut iter = wf.iter();
+
+        let sample = iter.nth(20_000_000).unwrap_or(f32::NAN);
+        iter.fill(&mut buffer);
+
+        assert_eq!(dds.sample::<f32>(20_000_000, tuning_word, 0), sample);
+        for (n, y) in (20_000_001..).zip(buffer) {
+            assert_eq!(dds.sample::<f32>(n, tuning_word, 0), y);
+        }
+    }
+
+    #[test]
+    fn frequency_above_nyquist_is_rejected() {
+        let mut wf = Waveform::<f64, f64>::new(1000.0);
+
+        assert_eq!(
+            Err(Error::NyquistViolation { component: 0 }),
+            wf.try_add_component(PeriodicFunction::dds(600.0, 1.0, 0.0, Dds::new(32, 12, 14)))
+        );
+        assert!(wf
+            .try_add_component(PeriodicFunction::dds(400.0, 1.0, 0.0, Dds::new(32, 12, 14)))
+            .is_ok());
+    }
+
+    #[test]
+    fn dither_is_reproducible_and_spreads_truncation_error() {
+        let dds = Dds::new(32, 6, 24);
+        let f = || PeriodicFunction::dds(1000.0, 1.0, 0.0, dds.with_dither(3));
+
+        assert_eq!(render(f(), 1000), render(f(), 1000));
+        assert_ne!(
+            render(f(), 1000),
+            render(
+                PeriodicFunction::dds(1000.0, 1.0, 0.0, dds.with_dither(4)),
+                1000
+            )
+        );
+        assert_ne!(
+            render(f(), 1000),
+            render(PeriodicFunction::dds(1000.0, 1.0, 0.0, dds), 1000)
+        );
+    }
+
+    #[test]
+    fn unbound_function_is_ideal_sine() {
+        let f: PeriodicFunction<f64> = PeriodicFunction::dds(50.0, 2.0, 0.1, Dds::new(8, 4, 4));
+        let ideal: PeriodicFunction<f64> = sine!(50., 2., 0.1);
+
+        for t in [0.0, 0.001, 0.013, 0.5] {
+            assert!((f.sample(t) - ideal.sample(t)).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn frequency_is_quantised_to_resolution() {
+        let dds = Dds::new(10, 10, 16);
+
+        assert_eq!(0, dds.tuning_word(1.0, SAMPLE_RATE));
+        assert_eq!(1024 - 21, dds.tuning_word(-1000.0, SAMPLE_RATE));
+        assert!(
+            (dds.output_frequency(1000.0, SAMPLE_RATE) - 21.0 * SAMPLE_RATE / 1024.0).abs() < 1e-9
+        );
+        assert_eq!(
+            u64::MAX / 4 + 1,
+            Dds::new(64, 12, 14).tuning_word(12000.0, SAMPLE_RATE)
+        );
+    }
+
+    #[test]
+    fn errors_are_reported() {
+        for dds in [
+            Dds::new(0, 0, 8),
+            Dds::new(65, 12, 8),
+            Dds::new(32, 0, 8),
+            Dds::new(32, 33, 8),
+            Dds::new(32, 12, 1),
+            Dds::new(32, 12, 33),
+        ] {
+            assert_eq!(
+                Some(Error::InvalidBitWidth),
+                PeriodicFunction::<f64>::try_dds(1.0, 1.0, 0.0, dds).err()
+            );
+        }
+        assert_eq!(
+            Some(Error::InvalidFrequency),
+            PeriodicFunction::<f64>::try_dds(f64::NAN, 1.0, 0.0, Dds::new(32, 12, 14)).err()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "bit width")]
+    fn invalid_widths_panic() {
+        let _ = PeriodicFunction::<f64>::dds(1.0, 1.0, 0.0, Dds::new(32, 12, 40));
+    }
+}
@@ -0,0 +1,342 @@
+//! Finite-duration sampling of waveforms.
+
+use crate::{Precision, SampleType, Waveform, WaveformIterator};
+use alloc::vec::Vec;
+use core::iter::FusedIterator;
+use num_traits::NumCast;
+
+/// Duration of a finite [`Waveform`] iteration, either in seconds or in samples.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Duration<P> {
+    /// Duration in seconds, rounded to the nearest sample. Negative or NaN durations yield no samples.
+    Seconds(P),
+    /// Duration in samples.
+    Samples(usize),
+}
+
+impl<P: Precision> Duration<P> {
+    /// Number of samples this duration spans at given `sample_rate`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wavegen::Duration;
+    ///
+    /// assert_eq!(441, Duration::Seconds(0.01f32).samples(44100.0));
+    /// assert_eq!(5, Duration::<f32>::Samples(5).samples(44100.0));
+    /// ```
+    pub fn samples(&self, sample_rate: P) -> usize {
+        match *self {
+            Duration::Seconds(seconds) => (seconds * sample_rate).round().to_usize().unwrap_or(0),
+            Duration::Samples(samples) => samples,
+        }
+    }
+}
+
+impl<P: Precision> From<core::time::Duration> for Duration<P> {
+    fn from(duration: core::time::Duration) -> Self {
+        Duration::Seconds(NumCast::from(duration.as_secs_f64()).unwrap_or_else(P::zero))
+    }
+}
+
+/// A finite iterator over a [`Waveform`] samples, created by [`WaveformIterator::for_duration`] or
+/// [`Waveform::iter_for`].
+///
+/// Unlike the [`WaveformIterator`] it wraps, it does not end early if a sample turns out to be NaN, and the sample type
+/// cannot represent it. Such samples are yielded as zero instead, so the iterator always has the exact length.
+#[derive(Clone)]
+pub struct FiniteWaveformIterator<'a, T: SampleType, P: Precision> {
+    inner: WaveformIterator<'a, T, P>,
+    remaining: usize,
+}
+
+impl<'a, T: SampleType, P: Precision> WaveformIterator<'a, T, P> {
+    /// Limits this iterator to `duration`, counted from its current position.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wavegen::{Duration, Waveform, sine};
+    ///
+    /// let wf = Waveform::<f32>::with_components(100.0, vec![sine!(1.)]);
+    /// let mut iter = wf.iter();
+    /// iter.nth(9);
+    ///
+    /// assert_eq!(50, iter.for_duration(Duration::Seconds(0.5)).len());
+    /// ```
+    pub fn for_duration(
+        self,
+        duration: impl Into<Duration<P>>,
+    ) -> FiniteWaveformIterator<'a, T, P> {
+        let remaining = duration.into().samples(*self.inner.sample_rate());
+
+        FiniteWaveformIterator {
+            inner: self,
+            remaining,
+        }
+    }
+}
+
+/// Sample yielded in place of a NaN one.
+#[inline]
+fn zero<T: SampleType>() -> T {
+    NumCast::from(0).unwrap_or_else(T::min_value)
+}
+
+impl<T: SampleType, P: Precision> Iterator for FiniteWaveformIterator<'_, T, P> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+
+        Some(self.inner.next().unwrap_or_else(zero))
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        if n >= self.remaining {
+            if self.remaining > 0 {
+                // stateful components still have to see the skipped samples
+                self.inner.nth(self.remaining - 1);
+            }
+            self.remaining = 0;
+
+            return None;
+        }
+
+        self.remaining -= n;
+        if n > 0 {
+            self.inner.nth(n - 1);
+        }
+
+        self.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T: SampleType, P: Precision> ExactSizeIterator for FiniteWaveformIterator<'_, T, P> {}
+
+impl<T: SampleType, P: Precision> FusedIterator for FiniteWaveformIterator<'_, T, P> {}
+
+impl<T: SampleType, P: Precision> Waveform<T, P> {
+    /// Returns a finite iterator over this [`Waveform`] samples, lasting for `duration`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wavegen::{Duration, Waveform, sine};
+    ///
+    /// let wf = Waveform::<f32>::with_components(44100.0, vec![sine!(1.)]);
+    ///
+    /// assert_eq!(44100, wf.iter_for(Duration::Seconds(1.0)).len());
+    /// assert_eq!(100, wf.iter_for(Duration::Samples(100)).len());
+    /// assert_eq!(22050, wf.iter_for(core::time::Duration::from_millis(500)).len());
+    /// ```
+    pub fn iter_for(&self, duration: impl Into<Duration<P>>) -> FiniteWaveformIterator<'_, T, P> {
+        self.iter().for_duration(duration)
+    }
+
+    /// Renders `duration` of this [`Waveform`] into a [`Vec`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wavegen::{Duration, Waveform, sine};
+    ///
+    /// let wf = Waveform::<f32>::with_components(100.0, vec![sine!(1.)]);
+    ///
+    /// assert_eq!(250, wf.render(Duration::Seconds(2.5)).len());
+    /// ```
+    pub fn render(&self, duration: impl Into<Duration<P>>) -> Vec<T> {
+        self.iter_for(duration).collect()
+    }
+
+    /// Renders this [`Waveform`] into `buffer`, filling it from the start.
+    ///
+    /// Like [`Waveform::render`], any NaN sample the sample type cannot represent is written as zero. Returns the number
+    /// of samples written, which is always the length of `buffer`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wavegen::{Waveform, sine};
+    ///
+    /// let wf = Waveform::<i16>::with_components(100.0, vec![sine!(1., 1000.)]);
+    /// let mut buffer = [0i16; 64];
+    ///
+    /// assert_eq!(64, wf.render_into(&mut buffer));
+    /// assert_eq!(1000, buffer[25]);
+    /// ```
+    pub fn render_into(&self, buffer: &mut [T]) -> usize {
+        let mut iter = self.iter();
+        let mut written = 0;
+
+        while written < buffer.len() {
+            written += iter.fill(&mut buffer[written..]);
+            // the iterator stopped just past a NaN sample
+            if let Some(sample) = buffer.get_mut(written) {
+                *sample = zero();
+                written += 1;
+            }
+        }
+
+        written
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{sine, square, Duration, PeriodicFunction, StatefulFunction, Waveform};
+    use alloc::{vec, vec::Vec};
+    use paste::paste;
+
+    fn waveform() -> Waveform<f64, f64> {
+        let mut wf = Waveform::with_components(100.0, vec![sine!(1.), square!(3.)]);
+        wf.add_stateful_component(StatefulFunction::new(|_| {
+            let mut n = 0.0;
+            move |_| {
+                n += 1.0;
+                n
+            }
+        }));
+
+        wf
+    }
+
+    macro_rules! test_samples {
+        ($($name:ident: $duration:expr => $samples:expr)*) => {
+            $(
+                paste! {
+                    #[test]
+                    fn [<$name _duration_has_expected_length>]() {
+                        let wf = waveform();
+
+                        assert_eq!($samples, $duration.samples(100.0));
+                        assert_eq!($samples, wf.iter_for($duration).len());
+                        assert_eq!($samples, wf.iter_for($duration).count());
+                        assert_eq!($samples, wf.render($duration).len());
+                    }
+                }
+            )*
+        };
+    }
+
+    test_samples! {
+        seconds: Duration::Seconds(1.5) => 150
+        rounded_seconds: Duration::Seconds(0.014) => 1
+        negative_seconds: Duration::Seconds(-1.0) => 0
+        nan_seconds: Duration::Seconds(f64::NAN) => 0
+        samples: Duration::<f64>::Samples(42) => 42
+        zero_samples: Duration::<f64>::Samples(0) => 0
+        std_duration: Duration::<f64>::from(core::time::Duration::from_millis(250)) => 25
+    }
+
+    #[test]
+    fn render_matches_infinite_iterator() {
+        let wf = waveform();
+
+        assert_eq!(
+            wf.iter().take(300).collect::<Vec<_>>(),
+            wf.render(Duration::Samples(300))
+        );
+    }
+
+    #[test]
+    fn render_into_fills_buffer() {
+        let wf = waveform();
+        let mut buffer = [0.0; 300];
+
+        assert_eq!(300, wf.render_into(&mut buffer));
+        assert_eq!(wf.iter().take(300).collect::<Vec<_>>(), buffer.to_vec());
+    }
+
+    #[test]
+    fn render_into_writes_zero_for_nan() {
+        let wf = Waveform::<i16, f64>::with_components(
+            100.0,
+            vec![PeriodicFunction::custom(|t| {
+                if (0.1..0.2).contains(&t) {
+                    f64::NAN
+                } else {
+                    1.0
+                }
+            })],
+        );
+        let mut buffer = [-1; 30];
+
+        assert_eq!(30, wf.render_into(&mut buffer));
+        assert_eq!(wf.render(Duration::Samples(30)), buffer.to_vec());
+    }
+
+    #[test]
+    fn nan_samples_are_zero() {
+        let wf = Waveform::<i16, f64>::with_components(
+            100.0,
+            vec![PeriodicFunction::custom(|t| {
+                if (0.1..0.2).contains(&t) {
+                    f64::NAN
+                } else {
+                    1.0
+                }
+            })],
+        );
+        let samples = wf.render(Duration::Samples(30));
+
+        assert_eq!(30, samples.len());
+        assert_eq!([1; 10], samples[..10]);
+        assert_eq!([0; 10], samples[10..20]);
+        assert_eq!([1; 10], samples[20..]);
+    }
+
+    #[test]
+    fn size_hint_decreases() {
+        let wf = waveform();
+        let mut iter = wf.iter_for(Duration::Samples(3));
+
+        for remaining in (0..3).rev() {
+            assert!(iter.next().is_some());
+            assert_eq!(remaining, iter.len());
+        }
+        assert_eq!(None, iter.next());
+        assert_eq!(None, iter.next());
+    }
+
+    #[test]
+    fn nth_matches_infinite_iterator() {
+        let wf = waveform();
+        let mut finite = wf.iter_for(Duration::Samples(100));
+        let mut infinite = wf.iter();
+
+        assert_eq!(infinite.nth(1), finite.nth(1));
+        assert_eq!(infinite.nth(10), finite.nth(10));
+        assert_eq!(87, finite.len());
+        assert_eq!(infinite.nth(86), finite.nth(86));
+        assert_eq!(0, finite.len());
+        assert_eq!(None, finite.nth(1));
+    }
+
+    #[test]
+    fn nth_past_end_is_none() {
+        let wf = waveform();
+        let mut iter = wf.iter_for(Duration::Samples(10));
+
+        assert_eq!(None, iter.nth(10));
+        assert_eq!(0, iter.len());
+        assert_eq!(None, iter.next());
+    }
+
+    #[test]
+    fn for_duration_counts_from_current_position() {
+        let wf = waveform();
+        let mut iter = wf.iter();
+        iter.nth(49);
+        let rest = iter.for_duration(Duration::Samples(50)).collect::<Vec<_>>();
+
+        assert_eq!(wf.iter().skip(50).take(50).collect::<Vec<_>>(), rest);
+    }
+}
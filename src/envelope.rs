@@ -0,0 +1,299 @@
+//! Amplitude envelope builders.
+
+use crate::{Kind, PeriodicFunction, Precision};
+use alloc::boxed::Box;
+use core::cmp::Ordering;
+
+/// Defines when an envelope is triggered, and for how long its gate stays open.
+///
+/// Envelopes without a sustain stage, like [`PeriodicFunction::exponential_decay`] and
+/// [`PeriodicFunction::piecewise_linear`], only use the gate opening.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Trigger<P> {
+    /// Triggered once, with the gate opening at `on` and closing at `off` seconds.
+    ///
+    /// The envelope is zero before the gate opens.
+    Once {
+        /// Time the gate opens at.
+        on: P,
+        /// Time the gate closes at.
+        off: P,
+    },
+    /// Retriggered every `period` seconds, starting at zero, with the gate open for the first `gate` seconds of each
+    /// period.
+    ///
+    /// The envelope starts over on every period, even if it has not finished yet.
+    Loop {
+        /// Time between consecutive triggers.
+        period: P,
+        /// Time the gate stays open for.
+        gate: P,
+    },
+}
+
+impl<P: Precision> Trigger<P> {
+    /// Time since the gate opened, and for how long it stays open.
+    fn at(&self, t: P) -> (P, P) {
+        match *self {
+            Trigger::Once { on, off } => (t - on, off - on),
+            Trigger::Loop { period, gate } => (t - (t / period).floor() * period, gate),
+        }
+    }
+
+    /// Frequency the envelope repeats with, if looping.
+    fn frequency(&self) -> Option<P> {
+        match *self {
+            Trigger::Once { .. } => None,
+            Trigger::Loop { period, .. } => {
+                let frequency = period.recip();
+
+                if frequency.is_normal() {
+                    Some(frequency.abs())
+                } else {
+                    None
+                }
+            }
+        }
+    }
+}
+
+impl<P: Precision> PeriodicFunction<P> {
+    /// Builds an envelope function of `level`, called with the time since the gate opened and the gate length.
+    fn envelope<F>(trigger: Trigger<P>, level: F, kind: Kind<P>) -> Self
+    where
+        F: Fn(P, P) -> P + Send + Sync + 'static,
+    {
+        let mut f = Self::new(Box::new(move |t| {
+            let (t, gate) = trigger.at(t);
+
+            if t < P::zero() {
+                P::zero()
+            } else {
+                level(t, gate)
+            }
+        }));
+        f.frequency = trigger.frequency();
+
+        f.with_kind(kind)
+    }
+
+    /// ADSR envelope builder. See the [`macro`] for more info.
+    ///
+    /// [`macro`]: ../macro.adsr.html
+    #[inline]
+    pub fn adsr(
+        attack: impl Into<P>,
+        decay: impl Into<P>,
+        sustain: impl Into<P>,
+        release: impl Into<P>,
+        trigger: Trigger<P>,
+    ) -> Self {
+        let attack = attack.into();
+        let decay = decay.into();
+        let sustain = sustain.into();
+        let release = release.into();
+
+        let held = move |t: P| {
+            if t < attack {
+                t / attack
+            } else if t < attack + decay {
+                P::one() - (P::one() - sustain) * (t - attack) / decay
+            } else {
+                sustain
+            }
+        };
+
+        Self::envelope(
+            trigger,
+            move |t, gate| {
+                if t < gate {
+                    held(t)
+                } else if t < gate + release {
+                    held(gate.max(P::zero())) * (P::one() - (t - gate) / release)
+                } else {
+                    P::zero()
+                }
+            },
+            Kind::Adsr {
+                attack,
+                decay,
+                sustain,
+                release,
+                trigger,
+            },
+        )
+    }
+
+    /// AR envelope builder. See the [`macro`] for more info.
+    ///
+    /// [`macro`]: ../macro.ar.html
+    #[inline]
+    pub fn ar(attack: impl Into<P>, release: impl Into<P>, trigger: Trigger<P>) -> Self {
+        let attack = attack.into();
+        let release = release.into();
+
+        Self::adsr(attack, P::zero(), P::one(), release, trigger).with_kind(Kind::Ar {
+            attack,
+            release,
+            trigger,
+        })
+    }
+
+    /// Exponential decay envelope builder. See the [`macro`] for more info.
+    ///
+    /// [`macro`]: ../macro.exponential_decay.html
+    #[inline]
+    pub fn exponential_decay(time_constant: impl Into<P>, trigger: Trigger<P>) -> Self {
+        let time_constant = time_constant.into();
+
+        Self::envelope(
+            trigger,
+            move |t, _| (-t / time_constant).exp(),
+            Kind::ExponentialDecay {
+                time_constant,
+                trigger,
+            },
+        )
+    }
+
+    /// Piecewise-linear envelope builder. See the [`macro`] for more info.
+    ///
+    /// [`macro`]: ../macro.piecewise_linear.html
+    #[inline]
+    pub fn piecewise_linear(points: &[(P, P)], trigger: Trigger<P>) -> Self {
+        let mut points = points.to_vec();
+        points.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal));
+        let kind = Kind::PiecewiseLinear {
+            points: points.clone(),
+            trigger,
+        };
+
+        Self::envelope(
+            trigger,
+            move |t, _| {
+                let next = points.iter().position(|&(time, _)| time > t);
+
+                match next {
+                    Some(0) => points[0].1,
+                    Some(i) => {
+                        let (t0, l0) = points[i - 1];
+                        let (t1, l1) = points[i];
+
+                        l0 + (l1 - l0) * (t - t0) / (t1 - t0)
+                    }
+                    None => points.last().map_or_else(P::zero, |&(_, level)| level),
+                }
+            },
+            kind,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        adsr, ar, exponential_decay, piecewise_linear, sine, Kind, PeriodicFunction, Trigger,
+        Waveform,
+    };
+    use alloc::{vec, vec::Vec};
+    use float_cmp::approx_eq;
+    use paste::paste;
+
+    const ONCE: Trigger<f64> = Trigger::Once { on: 1.0, off: 2.0 };
+    const LOOP: Trigger<f64> = Trigger::Loop {
+        period: 2.0,
+        gate: 1.0,
+    };
+
+    macro_rules! test_envelope {
+        ($($name:ident: $f:expr => [$(($t:expr, $level:expr)),*])*) => {
+            $(
+                paste! {
+                    #[test]
+                    fn [<$name _has_expected_levels>]() {
+                        let f: PeriodicFunction<f64> = $f;
+
+                        $(
+                            assert!(approx_eq!(f64, $level, f.sample($t), epsilon = 1e-9), "f({}) = {}", $t, f.sample($t));
+                        )*
+                    }
+                }
+            )*
+        };
+    }
+
+    test_envelope! {
+        adsr_once: adsr!(0.1, 0.2, 0.5, 0.4, ONCE) => [(0.0, 0.0), (0.99, 0.0), (1.05, 0.5), (1.1, 1.0), (1.2, 0.75), (1.5, 0.5), (2.0, 0.5), (2.2, 0.25), (2.4, 0.0), (9.0, 0.0)]
+        adsr_released_during_attack: adsr!(0.1, 0.2, 0.5, 0.4, Trigger::Once { on: 1.0, off: 1.05 }) => [(1.05, 0.5), (1.25, 0.25), (1.45, 0.0)]
+        adsr_released_during_decay: adsr!(0.1, 0.2, 0.5, 0.4, Trigger::Once { on: 1.0, off: 1.2 }) => [(1.2, 0.75), (1.4, 0.375), (1.6, 0.0)]
+        adsr_loop: adsr!(0.1, 0.2, 0.5, 0.4, LOOP) => [(0.05, 0.5), (1.2, 0.25), (1.5, 0.0), (2.05, 0.5), (201.2, 0.25)]
+        adsr_without_attack_and_decay: adsr!(0., 0., 0.5, 0.4, ONCE) => [(0.5, 0.0), (1.0, 0.5), (1.9, 0.5)]
+        adsr_without_release: adsr!(0.1, 0.2, 0.5, 0., ONCE) => [(1.9, 0.5), (2.0, 0.0)]
+        ar_once: ar!(0.5, 1., ONCE) => [(0.5, 0.0), (1.25, 0.5), (1.5, 1.0), (2.0, 1.0), (2.5, 0.5), (3.0, 0.0)]
+        ar_loop: ar!(attack: 0.5, release: 1., period: 2., gate: 1.) => [(0.25, 0.5), (1.5, 0.5), (2.25, 0.5)]
+        exponential_decay_once: exponential_decay!(0.5, ONCE) => [(0.5, 0.0), (1.0, 1.0), (1.5, (-1.0f64).exp()), (3.0, (-4.0f64).exp())]
+        exponential_decay_from_zero: exponential_decay!(0.5) => [(0.0, 1.0), (0.5, (-1.0f64).exp())]
+        exponential_decay_loop: exponential_decay!(time_constant: 0.5, period: 2.) => [(0.5, (-1.0f64).exp()), (2.5, (-1.0f64).exp())]
+        piecewise_linear_once: piecewise_linear!([(0., 0.), (0.5, 1.), (1.5, 0.2)], ONCE) => [(0.5, 0.0), (1.25, 0.5), (2.0, 0.6), (2.5, 0.2), (7.0, 0.2)]
+        piecewise_linear_unsorted: piecewise_linear!([(1.5, 0.2), (0.5, 1.)]) => [(0.0, 1.0), (1.0, 0.6), (2.0, 0.2)]
+        piecewise_linear_loop: piecewise_linear!([(0., 1.), (1., 0.)], LOOP) => [(0.5, 0.5), (1.5, 0.0), (2.5, 0.5)]
+        piecewise_linear_empty: piecewise_linear!([], ONCE) => [(1.5, 0.0)]
+    }
+
+    #[test]
+    fn looping_envelope_is_periodic() {
+        let f: PeriodicFunction<f64> = adsr!(0.1, 0.2, 0.5, 0.4, LOOP);
+
+        assert_eq!(Some(0.5), f.frequency);
+        assert_eq!(None, adsr!(0.1, 0.2, 0.5, 0.4, ONCE).frequency);
+    }
+
+    #[test]
+    fn envelope_multiplies_function() {
+        let tone = || sine!(1000.);
+        let wf = Waveform::<f64, f64>::with_components(
+            8000.0,
+            vec![tone() * ar!(0.01, 0.01, Trigger::Once { on: 0.1, off: 0.2 })],
+        );
+        let samples = wf.iter().take(3000).collect::<Vec<_>>();
+
+        assert!(samples[..800].iter().all(|&y| y == 0.0));
+        assert!(approx_eq!(
+            f64,
+            tone().sample(0.15),
+            samples[1200],
+            epsilon = 1e-9
+        ));
+        assert!(samples[1681..].iter().all(|&y| y == 0.0));
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used, clippy::float_cmp)]
+    fn envelope_kinds_are_recorded_and_rebuilt() {
+        let functions: [PeriodicFunction<f64>; 4] = [
+            adsr!(0.1, 0.2, 0.5, 0.4, ONCE),
+            ar!(0.1, 0.4, LOOP),
+            exponential_decay!(0.5, ONCE),
+            piecewise_linear!([(0., 1.), (1., 0.)], LOOP),
+        ];
+
+        assert_eq!(
+            Kind::Ar {
+                attack: 0.1,
+                release: 0.4,
+                trigger: LOOP
+            },
+            *functions[1].kind()
+        );
+
+        for f in &functions {
+            let rebuilt = f.kind().build().unwrap();
+
+            assert_eq!(f.kind(), rebuilt.kind());
+            for t in [0.0, 0.05, 0.3, 1.1, 1.5, 2.2, 3.7] {
+                assert_eq!(f.sample(t), rebuilt.sample(t));
+            }
+        }
+    }
+}
@@ -0,0 +1,86 @@
+//! Error type returned by fallible constructors.
+
+use core::fmt;
+
+/// Errors returned by the fallible `try_*` constructors of [`Waveform`] and [`PeriodicFunction`].
+///
+/// [`Waveform`]: crate::Waveform
+/// [`PeriodicFunction`]: crate::PeriodicFunction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// Sample rate is not a finite, positive, non-zero number.
+    InvalidSampleRate,
+    /// Frequency is not a finite number.
+    InvalidFrequency,
+    /// Amplitude is not a finite number.
+    InvalidAmplitude,
+    /// Phase is not a finite number.
+    InvalidPhase,
+    /// Duration is not a finite, positive, non-zero number.
+    InvalidDuration,
+    /// Duty cycle is not a number within `[0, 1]` range.
+    InvalidDuty,
+    /// Symmetry is not a number within `[0, 1]` range.
+    InvalidSymmetry,
+    /// Frequency of a component exceeds the Nyquist frequency, that is half of the sample rate.
+    NyquistViolation {
+        /// Index of the offending component.
+        component: usize,
+    },
+    /// Component is built from a closure, and has no serialisable definition.
+    CustomComponent,
+    /// Stateful components cannot be evaluated at arbitrary time, as required by the operation.
+    StatefulComponent,
+    /// Modulator has no known frequency, so it cannot be integrated over its period.
+    AperiodicModulator,
+    /// Function has no known frequency, so it cannot be tabulated over its period.
+    AperiodicFunction,
+    /// Table has no values.
+    EmptyTable,
+    /// Bit width of an emulated DDS core is out of range.
+    InvalidBitWidth,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidSampleRate => {
+                write!(
+                    f,
+                    "sample rate has to be a finite, positive, non-zero number"
+                )
+            }
+            Error::InvalidFrequency => write!(f, "frequency has to be a finite number"),
+            Error::InvalidAmplitude => write!(f, "amplitude has to be a finite number"),
+            Error::InvalidPhase => write!(f, "phase has to be a finite number"),
+            Error::InvalidDuration => {
+                write!(f, "duration has to be a finite, positive, non-zero number")
+            }
+            Error::InvalidDuty => write!(f, "duty cycle has to be a number within [0, 1] range"),
+            Error::InvalidSymmetry => write!(f, "symmetry has to be a number within [0, 1] range"),
+            Error::NyquistViolation { component } => write!(
+                f,
+                "frequency of component {component} exceeds half of the sample rate"
+            ),
+            Error::CustomComponent => write!(
+                f,
+                "custom and stateful components cannot be serialised, only built-in ones"
+            ),
+            Error::StatefulComponent => write!(
+                f,
+                "stateful components cannot be evaluated at arbitrary time"
+            ),
+            Error::AperiodicModulator => write!(f, "modulator has to have a known frequency"),
+            Error::AperiodicFunction => write!(f, "function has to have a known frequency"),
+            Error::EmptyTable => write!(f, "table has to have at least one value"),
+            Error::InvalidBitWidth => write!(
+                f,
+                "bit widths of a DDS core have to be within supported ranges"
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
@@ -0,0 +1,772 @@
+//! Fixed-point calculation precision, for targets without a floating point unit.
+
+use crate::Error;
+use core::marker::PhantomData;
+use core::ops::{Add, Mul, Neg, Sub};
+
+macro_rules! fixed_type {
+    ($(#[$meta:meta])* $name:ident, $raw:ty, $wide:ty, $bits:expr) => {
+        $(#[$meta])*
+        #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        pub struct $name(pub $raw);
+
+        impl $name {
+            /// Largest value, just below one.
+            pub const MAX: Self = Self(<$raw>::MAX);
+            /// Smallest value, minus one.
+            pub const MIN: Self = Self(<$raw>::MIN);
+
+            /// Creates a value from its raw bits.
+            #[inline]
+            #[must_use]
+            pub const fn from_bits(bits: $raw) -> Self {
+                Self(bits)
+            }
+
+            /// Returns the raw bits of the value.
+            #[inline]
+            #[must_use]
+            pub const fn to_bits(self) -> $raw {
+                self.0
+            }
+        }
+
+        impl Add for $name {
+            type Output = Self;
+
+            #[inline]
+            fn add(self, rhs: Self) -> Self {
+                Self(self.0.saturating_add(rhs.0))
+            }
+        }
+
+        impl Sub for $name {
+            type Output = Self;
+
+            #[inline]
+            fn sub(self, rhs: Self) -> Self {
+                Self(self.0.saturating_sub(rhs.0))
+            }
+        }
+
+        impl Mul for $name {
+            type Output = Self;
+
+            #[inline]
+            fn mul(self, rhs: Self) -> Self {
+                let product = (<$wide>::from(self.0) * <$wide>::from(rhs.0) + (1 << ($bits - 2))) >> ($bits - 1);
+
+                Self(saturate!(product, $raw))
+            }
+        }
+
+        impl Neg for $name {
+            type Output = Self;
+
+            #[inline]
+            fn neg(self) -> Self {
+                Self(self.0.saturating_neg())
+            }
+        }
+
+        impl From<$name> for f64 {
+            fn from(x: $name) -> Self {
+                f64::from(x.0) / f64::from(1u32 << ($bits - 1))
+            }
+        }
+    };
+}
+
+macro_rules! saturate {
+    ($x:expr, $raw:ty) => {
+        match <$raw>::try_from($x) {
+            Ok(x) => x,
+            Err(_) if $x > 0 => <$raw>::MAX,
+            Err(_) => <$raw>::MIN,
+        }
+    };
+}
+
+fixed_type!(
+    /// Signed fixed-point number in Q15 format, with 15 fractional bits, covering `[-1, 1)` range.
+    ///
+    /// Arithmetic saturates instead of overflowing.
+    Q15,
+    i16,
+    i32,
+    16
+);
+
+fixed_type!(
+    /// Signed fixed-point number in Q31 format, with 31 fractional bits, covering `[-1, 1)` range.
+    ///
+    /// Arithmetic saturates instead of overflowing.
+    Q31,
+    i32,
+    i64,
+    32
+);
+
+impl From<Q15> for Q31 {
+    fn from(x: Q15) -> Self {
+        Q31(i32::from(x.0) << 16)
+    }
+}
+
+impl From<Q15> for i16 {
+    fn from(x: Q15) -> Self {
+        x.0
+    }
+}
+
+impl From<Q15> for i32 {
+    /// Scales the value to the full range of [`i32`].
+    fn from(x: Q15) -> Self {
+        i32::from(x.0) << 16
+    }
+}
+
+impl From<Q31> for i32 {
+    fn from(x: Q31) -> Self {
+        x.0
+    }
+}
+
+impl From<Q15> for f32 {
+    fn from(x: Q15) -> Self {
+        f32::from(x.0) / 32768.0
+    }
+}
+
+/// Frequency in Hz, in unsigned fixed-point Q16.16 format, with 16 integer and 16 fractional bits.
+///
+/// It covers `[0, 65536)` Hz range, in steps of `2^-16` Hz, so fractional and sub-Hz frequencies can be given without
+/// floating point.
+///
+/// # Examples
+///
+/// ```
+/// use wavegen::FixedFrequency;
+///
+/// assert_eq!(440.5, f64::from(FixedFrequency::from_bits(440 << 16 | 1 << 15)));
+/// assert_eq!(0.25, f64::from(FixedFrequency::from_bits(1 << 14)));
+/// assert_eq!(FixedFrequency::from_bits(1000 << 16), FixedFrequency::from_hz(1000));
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FixedFrequency(pub u32);
+
+impl FixedFrequency {
+    /// Creates a frequency of a whole number of Hz.
+    #[inline]
+    #[must_use]
+    pub fn from_hz(hz: u16) -> Self {
+        Self(u32::from(hz) << 16)
+    }
+
+    /// Creates a frequency from its raw bits.
+    #[inline]
+    #[must_use]
+    pub const fn from_bits(bits: u32) -> Self {
+        Self(bits)
+    }
+
+    /// Returns the raw bits of the frequency.
+    #[inline]
+    #[must_use]
+    pub const fn to_bits(self) -> u32 {
+        self.0
+    }
+}
+
+impl From<FixedFrequency> for f64 {
+    fn from(x: FixedFrequency) -> Self {
+        f64::from(x.0) / 65536.0
+    }
+}
+
+/// Defines fixed-point precision of [`FixedWaveform`] calculations.
+pub trait Fixed: Copy + Add<Output = Self> + Mul<Output = Self> + Send + Sync + 'static {
+    /// Zero value.
+    const ZERO: Self;
+
+    /// Converts a [`Q31`] value, rounding to the nearest one.
+    fn from_q31(x: Q31) -> Self;
+}
+
+impl Fixed for Q15 {
+    const ZERO: Self = Q15(0);
+
+    #[inline]
+    fn from_q31(x: Q31) -> Self {
+        let rounded = (i64::from(x.0) + (1 << 15)) >> 16;
+
+        Q15(saturate!(rounded, i16))
+    }
+}
+
+impl Fixed for Q31 {
+    const ZERO: Self = Q31(0);
+
+    #[inline]
+    fn from_q31(x: Q31) -> Self {
+        x
+    }
+}
+
+/// Quarter period of sine in Q31 format, in 256 steps.
+#[allow(clippy::unreadable_literal)]
+const QUARTER_SINE: [i32; 257] = [
+    0, 13176712, 26352928, 39528151, 52701887, 65873638, 79042909, 92209205, 105372028, 118530885,
+    131685278, 144834714, 157978697, 171116733, 184248325, 197372981, 210490206, 223599506,
+    236700388, 249792358, 262874923, 275947592, 289009871, 302061269, 315101295, 328129457,
+    341145265, 354148230, 367137861, 380113669, 393075166, 406021865, 418953276, 431868915,
+    444768294, 457650927, 470516330, 483364019, 496193509, 509004318, 521795963, 534567963,
+    547319836, 560051104, 572761285, 585449903, 598116479, 610760536, 623381598, 635979190,
+    648552838, 661102068, 673626408, 686125387, 698598533, 711045377, 723465451, 735858287,
+    748223418, 760560380, 772868706, 785147934, 797397602, 809617249, 821806413, 833964638,
+    846091463, 858186435, 870249095, 882278992, 894275671, 906238681, 918167572, 930061894,
+    941921200, 953745043, 965532978, 977284562, 988999351, 1000676905, 1012316784, 1023918550,
+    1035481766, 1047005996, 1058490808, 1069935768, 1081340445, 1092704411, 1104027237, 1115308496,
+    1126547765, 1137744621, 1148898640, 1160009405, 1171076495, 1182099496, 1193077991, 1204011567,
+    1214899813, 1225742318, 1236538675, 1247288478, 1257991320, 1268646800, 1279254516, 1289814068,
+    1300325060, 1310787095, 1321199781, 1331562723, 1341875533, 1352137822, 1362349204, 1372509294,
+    1382617710, 1392674072, 1402678000, 1412629117, 1422527051, 1432371426, 1442161874, 1451898025,
+    1461579514, 1471205974, 1480777044, 1490292364, 1499751576, 1509154322, 1518500250, 1527789007,
+    1537020244, 1546193612, 1555308768, 1564365367, 1573363068, 1582301533, 1591180426, 1599999411,
+    1608758157, 1617456335, 1626093616, 1634669676, 1643184191, 1651636841, 1660027308, 1668355276,
+    1676620432, 1684822463, 1692961062, 1701035922, 1709046739, 1716993211, 1724875040, 1732691928,
+    1740443581, 1748129707, 1755750017, 1763304224, 1770792044, 1778213194, 1785567396, 1792854372,
+    1800073849, 1807225553, 1814309216, 1821324572, 1828271356, 1835149306, 1841958164, 1848697674,
+    1855367581, 1861967634, 1868497586, 1874957189, 1881346202, 1887664383, 1893911494, 1900087301,
+    1906191570, 1912224073, 1918184581, 1924072871, 1929888720, 1935631910, 1941302225, 1946899451,
+    1952423377, 1957873796, 1963250501, 1968553292, 1973781967, 1978936331, 1984016189, 1989021350,
+    1993951625, 1998806829, 2003586779, 2008291295, 2012920201, 2017473321, 2021950484, 2026351522,
+    2030676269, 2034924562, 2039096241, 2043191150, 2047209133, 2051150040, 2055013723, 2058800036,
+    2062508835, 2066139983, 2069693342, 2073168777, 2076566160, 2079885360, 2083126254, 2086288720,
+    2089372638, 2092377892, 2095304370, 2098151960, 2100920556, 2103610054, 2106220352, 2108751352,
+    2111202959, 2113575080, 2115867626, 2118080511, 2120213651, 2122266967, 2124240380, 2126133817,
+    2127947206, 2129680480, 2131333572, 2132906420, 2134398966, 2135811153, 2137142927, 2138394240,
+    2139565043, 2140655293, 2141664948, 2142593971, 2143442326, 2144209982, 2144896910, 2145503083,
+    2146028480, 2146473080, 2146836866, 2147119825, 2147321946, 2147443222, 2147483647,
+];
+
+/// Sine of `phase`, given as a fraction of the period scaled by `2^32`, linearly interpolated from [`QUARTER_SINE`].
+#[inline]
+fn sine(phase: u32) -> Q31 {
+    const QUARTER: u32 = 1 << 30;
+    const FRACTION_BITS: u32 = 22;
+
+    let quadrant = phase >> 30;
+    let x = phase & (QUARTER - 1);
+    let x = if quadrant & 1 == 1 { QUARTER - x } else { x };
+    let index = (x >> FRACTION_BITS) as usize;
+    let fraction = i64::from(x & ((1 << FRACTION_BITS) - 1));
+    let a = i64::from(QUARTER_SINE[index]);
+    let b = i64::from(QUARTER_SINE[(index + 1).min(256)]);
+    // cannot overflow, as the interpolated value lies between two table values
+    #[allow(clippy::cast_possible_truncation)]
+    let y = (a + (((b - a) * fraction) >> FRACTION_BITS)) as i32;
+
+    if quadrant >= 2 {
+        Q31(-y)
+    } else {
+        Q31(y)
+    }
+}
+
+/// Phase increment per sample, scaled by `2^32`, of `frequency` at `sample_rate`.
+///
+/// The phase wraps around, so frequencies above the sample rate are taken modulo the sample rate.
+#[inline]
+#[allow(clippy::cast_possible_truncation)]
+fn tuning_word(frequency: FixedFrequency, sample_rate: u32) -> u32 {
+    let sample_rate = u64::from(sample_rate.max(1));
+
+    // the frequency has 16 fractional bits already
+    (((u64::from(frequency.0) << 16) + sample_rate / 2) / sample_rate) as u32
+}
+
+/// A component of a [`FixedWaveform`], calculated in fixed-point precision `Q`.
+///
+/// The phase of periodic components is kept in an integer phase accumulator, so the samples neither drift, nor depend
+/// on floating point calculations.
+pub trait FixedComponent<Q: Fixed> {
+    /// Returns the value of the sample at `index`.
+    fn sample_at(&self, index: u64) -> Q;
+
+    /// Adapts the component to the `sample_rate` of the [`FixedWaveform`] it is added to.
+    #[must_use]
+    fn bound(self, sample_rate: u32) -> Self;
+}
+
+macro_rules! fixed_component {
+    ($(#[$meta:meta])* $name:ident, |$self:ident, $phase:ident| $sample:expr) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub struct $name<Q = Q15> {
+            frequency: FixedFrequency,
+            amplitude: Q,
+            phase: u32,
+            tuning_word: u32,
+        }
+
+        impl<Q: Fixed> $name<Q> {
+            /// Initializes new component of `frequency`, `amplitude`, and `phase` given as a fraction of the period
+            /// scaled by `2^32`, so that `1 << 31` is a shift by half a period.
+            ///
+            /// Once bound to a sample rate, the frequency is rounded to a multiple of `sample_rate / 2^32`, the
+            /// resolution of the 32-bit phase accumulator.
+            #[inline]
+            pub fn new(frequency: FixedFrequency, amplitude: Q, phase: u32) -> Self {
+                Self {
+                    frequency,
+                    amplitude,
+                    phase,
+                    tuning_word: 0,
+                }
+            }
+
+            /// Frequency, in Hz.
+            pub fn frequency(&self) -> FixedFrequency {
+                self.frequency
+            }
+
+            /// Amplitude.
+            pub fn amplitude(&self) -> Q {
+                self.amplitude
+            }
+
+            /// Phase, as a fraction of the period scaled by `2^32`.
+            pub fn phase(&self) -> u32 {
+                self.phase
+            }
+
+            /// Phase of the sample at `index`.
+            #[inline]
+            // the phase accumulator wraps around every 2^32 samples anyway
+            #[allow(clippy::cast_possible_truncation)]
+            fn phase_at(&self, index: u64) -> u32 {
+                self.tuning_word
+                    .wrapping_mul(index as u32)
+                    .wrapping_add(self.phase)
+            }
+        }
+
+        impl<Q: Fixed> FixedComponent<Q> for $name<Q> {
+            #[inline]
+            fn sample_at(&$self, index: u64) -> Q {
+                let $phase = $self.phase_at(index);
+
+                $sample
+            }
+
+            #[inline]
+            fn bound(mut self, sample_rate: u32) -> Self {
+                self.tuning_word = tuning_word(self.frequency, sample_rate);
+
+                self
+            }
+        }
+    };
+}
+
+fixed_component!(
+    /// Sine component in fixed-point precision, calculated from a table with linear interpolation.
+    ///
+    /// The sine is within `5e-6` of the exact one, before being multiplied by the amplitude and rounded to `Q`.
+    FixedSine,
+    |self, phase| Q::from_q31(sine(phase)) * self.amplitude
+);
+
+fixed_component!(
+    /// Square component in fixed-point precision.
+    FixedSquare,
+    |self, phase| {
+        if phase < 1 << 31 {
+            Q::from_q31(Q31::MAX) * self.amplitude
+        } else {
+            Q::from_q31(Q31::MIN) * self.amplitude
+        }
+    }
+);
+
+fixed_component!(
+    /// Sawtooth component in fixed-point precision, rising from minus to plus amplitude.
+    FixedSawtooth,
+    |self, phase| {
+        // 2 * (phase / 2^32) - 1, in Q31
+        #[allow(clippy::cast_possible_wrap)]
+        let ramp = Q31(phase.wrapping_sub(1 << 31) as i32);
+
+        Q::from_q31(ramp) * self.amplitude
+    }
+);
+
+/// DC bias component in fixed-point precision.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixedDcBias<Q = Q15> {
+    /// Constant value of the component.
+    pub bias: Q,
+}
+
+impl<Q: Fixed> FixedDcBias<Q> {
+    /// Initializes new component with given bias.
+    #[inline]
+    pub fn new(bias: Q) -> Self {
+        Self { bias }
+    }
+}
+
+impl<Q: Fixed> FixedComponent<Q> for FixedDcBias<Q> {
+    #[inline]
+    fn sample_at(&self, _: u64) -> Q {
+        self.bias
+    }
+
+    #[inline]
+    fn bound(self, _: u32) -> Self {
+        self
+    }
+}
+
+impl<Q: Fixed, C: FixedComponent<Q>, const N: usize> FixedComponent<Q> for [C; N] {
+    #[inline]
+    fn sample_at(&self, index: u64) -> Q {
+        self.iter().fold(Q::ZERO, |sum, c| sum + c.sample_at(index))
+    }
+
+    #[inline]
+    fn bound(self, sample_rate: u32) -> Self {
+        self.map(|c| c.bound(sample_rate))
+    }
+}
+
+macro_rules! tuple_fixed_component {
+    ($($c:ident: $i:tt),+) => {
+        impl<Q: Fixed, $($c: FixedComponent<Q>),+> FixedComponent<Q> for ($($c,)+) {
+            #[inline]
+            fn sample_at(&self, index: u64) -> Q {
+                Q::ZERO $(+ self.$i.sample_at(index))+
+            }
+
+            #[inline]
+            fn bound(self, sample_rate: u32) -> Self {
+                ($(self.$i.bound(sample_rate),)+)
+            }
+        }
+    };
+}
+
+tuple_fixed_component!(A: 0);
+tuple_fixed_component!(A: 0, B: 1);
+tuple_fixed_component!(A: 0, B: 1, C: 2);
+tuple_fixed_component!(A: 0, B: 1, C: 2, D: 3);
+tuple_fixed_component!(A: 0, B: 1, C: 2, D: 3, E: 4);
+tuple_fixed_component!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5);
+tuple_fixed_component!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6);
+tuple_fixed_component!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6, H: 7);
+
+/// A waveform calculated in fixed-point precision `Q`, with an integer sample rate, generic over its components.
+///
+/// It is the counterpart of [`StaticWaveform`](crate::StaticWaveform) for targets without a floating point unit:
+/// sampling it takes integer arithmetic only. Sums of components saturate instead of overflowing.
+///
+/// # Examples
+///
+/// ```
+/// use wavegen::{FixedDcBias, FixedFrequency, FixedSine, FixedWaveform, Q15};
+///
+/// let sine = FixedSine::new(FixedFrequency::from_hz(1000), Q15(16384), 0);
+/// let wf = FixedWaveform::<i16, _>::new(8000, (sine, FixedDcBias::new(Q15(-100))));
+/// let samples: Vec<i16> = wf.iter().take(8).collect();
+///
+/// assert_eq!(vec![-100, 11485, 16284, 11485, -100, -11685, -16484, -11685], samples);
+/// ```
+#[derive(Debug, Clone)]
+pub struct FixedWaveform<T, C, Q: Fixed = Q15> {
+    sample_rate: u32,
+    components: C,
+    _phantom: PhantomData<(T, Q)>,
+}
+
+impl<T: From<Q>, C: FixedComponent<Q>, Q: Fixed> FixedWaveform<T, C, Q> {
+    /// Initializes new [`FixedWaveform`] with given components.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if `sample_rate` is zero.
+    pub fn new(sample_rate: u32, components: C) -> Self {
+        match Self::try_new(sample_rate, components) {
+            Ok(wf) => wf,
+            Err(error) => panic!("{}", error),
+        }
+    }
+
+    /// Fallible version of [`FixedWaveform::new`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidSampleRate`] if `sample_rate` is zero.
+    pub fn try_new(sample_rate: u32, components: C) -> Result<Self, Error> {
+        if sample_rate == 0 {
+            return Err(Error::InvalidSampleRate);
+        }
+
+        Ok(FixedWaveform {
+            sample_rate,
+            components: components.bound(sample_rate),
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Gets sample rate of this [`FixedWaveform`].
+    pub fn sample_rate(&self) -> &u32 {
+        &self.sample_rate
+    }
+
+    /// Returns the components this [`FixedWaveform`] consists of.
+    pub fn components(&self) -> &C {
+        &self.components
+    }
+
+    /// Returns an iterator over this [`FixedWaveform`] samples.
+    pub fn iter(&self) -> FixedWaveformIterator<'_, T, C, Q> {
+        FixedWaveformIterator {
+            inner: self,
+            index: 0,
+        }
+    }
+}
+
+impl<'a, T: From<Q>, C: FixedComponent<Q>, Q: Fixed> IntoIterator for &'a FixedWaveform<T, C, Q> {
+    type Item = T;
+
+    type IntoIter = FixedWaveformIterator<'a, T, C, Q>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// An infinite iterator that allows to sample a [`FixedWaveform`].
+#[derive(Debug)]
+pub struct FixedWaveformIterator<'a, T, C, Q: Fixed> {
+    inner: &'a FixedWaveform<T, C, Q>,
+    index: u64,
+}
+
+impl<T, C, Q: Fixed> Clone for FixedWaveformIterator<'_, T, C, Q> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner,
+            index: self.index,
+        }
+    }
+}
+
+impl<T: From<Q>, C: FixedComponent<Q>, Q: Fixed> FixedWaveformIterator<'_, T, C, Q> {
+    /// Fills `buffer` with the next samples, advancing the iterator by its length.
+    ///
+    /// Returns the number of samples written, which is always the length of `buffer`, as fixed-point samples cannot be
+    /// NaN.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wavegen::{FixedFrequency, FixedSine, FixedWaveform, Q15};
+    ///
+    /// let wf = FixedWaveform::<i16, _>::new(44100, FixedSine::new(FixedFrequency::from_hz(440), Q15::MAX, 0));
+    /// let mut buffer = [0; 512];
+    ///
+    /// assert_eq!(512, wf.iter().fill(&mut buffer));
+    /// assert!(wf.iter().zip(buffer.iter()).all(|(y, &b)| y == b));
+    /// ```
+    pub fn fill(&mut self, buffer: &mut [T]) -> usize {
+        for y in buffer.iter_mut() {
+            *y = self.inner.components.sample_at(self.index).into();
+            self.index = self.index.wrapping_add(1);
+        }
+
+        buffer.len()
+    }
+}
+
+impl<T: From<Q>, C: FixedComponent<Q>, Q: Fixed> Iterator for FixedWaveformIterator<'_, T, C, Q> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let sample = self.inner.components.sample_at(self.index);
+        self.index = self.index.wrapping_add(1);
+
+        Some(sample.into())
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.index = self.index.wrapping_add(n as u64);
+
+        self.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (usize::MAX, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::f64::consts::PI;
+    use paste::paste;
+
+    macro_rules! test_arithmetic {
+        ($($name:ident: $a:expr, $op:tt, $b:expr => $expected:expr)*) => {
+            $(
+                paste! {
+                    #[test]
+                    fn [<$name _is_saturated>]() {
+                        assert_eq!($expected, $a $op $b);
+                    }
+                }
+            )*
+        };
+    }
+
+    test_arithmetic! {
+        q15_add: Q15(16384), +, Q15(8192) => Q15(24576)
+        q15_add_overflow: Q15(30000), +, Q15(30000) => Q15::MAX
+        q15_sub_overflow: Q15(-30000), -, Q15(30000) => Q15::MIN
+        q15_mul: Q15(16384), *, Q15(16384) => Q15(8192)
+        q15_mul_negative: Q15(-16384), *, Q15(16384) => Q15(-8192)
+        q15_mul_minus_one: Q15::MIN, *, Q15::MIN => Q15::MAX
+        q31_add_overflow: Q31::MAX, +, Q31(1) => Q31::MAX
+        q31_mul: Q31(1 << 30), *, Q31(-(1 << 30)) => Q31(-(1 << 29))
+        q31_mul_minus_one: Q31::MIN, *, Q31::MIN => Q31::MAX
+    }
+
+    #[test]
+    fn negation_saturates() {
+        assert_eq!(Q15::MAX, -Q15::MIN);
+        assert_eq!(Q31(-5), -Q31(5));
+    }
+
+    #[test]
+    fn conversions_keep_value() {
+        assert_eq!(Q31(1 << 30), Q31::from(Q15(1 << 14)));
+        assert_eq!(Q15(1 << 14), Q15::from_q31(Q31(1 << 30)));
+        assert_eq!(Q15::MAX, Q15::from_q31(Q31::MAX));
+        assert!((f64::from(Q15(-16384)) + 0.5).abs() < f64::EPSILON);
+        assert!((f32::from(Q15(16384)) - 0.5).abs() < f32::EPSILON);
+        assert_eq!(i32::MIN, i32::from(Q15::MIN));
+    }
+
+    #[test]
+    fn table_sine_is_accurate() {
+        for i in 0..=4096u32 {
+            let phase = i.wrapping_mul(1_048_573);
+            let exact = (2.0 * PI * f64::from(phase) / 4_294_967_296.0).sin();
+
+            assert!(
+                (f64::from(sine(phase)) - exact).abs() < 5e-6,
+                "phase {phase}"
+            );
+        }
+    }
+
+    #[test]
+    fn sine_matches_floating_point_sine() {
+        let wf = FixedWaveform::<Q15, _>::new(
+            44100,
+            FixedSine::new(FixedFrequency::from_hz(440), Q15::MAX, 1 << 30),
+        );
+
+        for (i, y) in (0..10_000u32).zip(wf.iter()) {
+            let exact = (2.0 * PI * 440.0 * f64::from(i) / 44100.0 + PI / 2.0).sin();
+
+            // rounding of the sine and the amplitude, within 3 least significant bits
+            assert!((f64::from(y) - exact).abs() < 1e-4, "sample {i}");
+        }
+    }
+
+    #[test]
+    fn square_and_sawtooth_follow_phase() {
+        let square = FixedWaveform::<Q31, _, Q31>::new(
+            8,
+            FixedSquare::new(FixedFrequency::from_hz(1), Q31::MAX, 0),
+        );
+        let sawtooth = FixedWaveform::<i16, _>::new(
+            8,
+            FixedSawtooth::new(FixedFrequency::from_hz(1), Q15::MAX, 1 << 31),
+        );
+
+        assert!(square.iter().take(4).all(|y| y > Q31(0)));
+        assert!(square.iter().skip(4).take(4).all(|y| y < Q31(0)));
+        assert_eq!(
+            [0, 8192, 16384, 24575, -32767, -24575, -16383, -8192],
+            [0; 8].map({
+                let mut iter = sawtooth.iter();
+                move |_: i16| iter.next().unwrap_or_default()
+            })
+        );
+    }
+
+    #[test]
+    fn fractional_frequency_matches_floating_point_sine() {
+        // 440.5 Hz and 0.25 Hz
+        for bits in [440 << 16 | 1 << 15, 1 << 14] {
+            let frequency = FixedFrequency::from_bits(bits);
+            let wf =
+                FixedWaveform::<Q31, _, Q31>::new(48000, FixedSine::new(frequency, Q31::MAX, 0));
+
+            for (i, y) in (0..48000u32).zip(wf.iter()).step_by(997) {
+                let exact = (2.0 * PI * f64::from(frequency) * f64::from(i) / 48000.0).sin();
+
+                // the tuning word drifts by at most half of its resolution per sample
+                assert!((f64::from(y) - exact).abs() < 1e-4, "{bits}: sample {i}");
+            }
+        }
+    }
+
+    #[test]
+    fn components_are_summed_with_saturation() {
+        let wf = FixedWaveform::<i16, _>::new(
+            100,
+            [FixedDcBias::new(Q15(20000)), FixedDcBias::new(Q15(20000))],
+        );
+
+        assert_eq!(Some(i16::MAX), wf.iter().next());
+    }
+
+    #[test]
+    fn phase_does_not_drift() {
+        let wf = FixedWaveform::<i32, _, Q31>::new(
+            65536,
+            (FixedSine::new(FixedFrequency::from_hz(1000), Q31::MAX, 0),),
+        );
+
+        // the tuning word of 1000 Hz at 65536 Hz is exact, 1000 periods take 65536 samples
+        assert_eq!(wf.iter().nth(3), wf.iter().nth(65536 * 1_000_000 + 3));
+    }
+
+    #[test]
+    fn fill_matches_iterator() {
+        let wf = FixedWaveform::<i16, _>::new(
+            8000,
+            FixedSquare::new(FixedFrequency::from_hz(440), Q15(1000), 0),
+        );
+        let mut iter = wf.iter();
+        let mut buffer = [0; 300];
+
+        assert_eq!(100, iter.fill(&mut buffer[..100]));
+        assert_eq!(200, iter.fill(&mut buffer[100..]));
+        assert!(wf.iter().zip(buffer.iter()).all(|(y, &b)| y == b));
+    }
+
+    #[test]
+    fn zero_sample_rate_is_rejected() {
+        assert_eq!(
+            Some(Error::InvalidSampleRate),
+            FixedWaveform::<i16, _>::try_new(0, FixedDcBias::new(Q15(1))).err()
+        );
+    }
+}
@@ -0,0 +1,391 @@
+//! Fourier series builders.
+
+use crate::band_limited::harmonics;
+use crate::nyquist::Shape;
+use crate::{finite, Error, Kind, PeriodicFunction, Precision, Two};
+use alloc::{boxed::Box, vec::Vec};
+use num_traits::NumCast;
+
+/// Sorts the coefficients by harmonic, merging the ones of the same harmonic.
+fn merged<P: Precision>(coefficients: &[(u32, P, P)]) -> Vec<(u32, P, P)> {
+    let mut sorted = coefficients.to_vec();
+    sorted.sort_by_key(|&(n, _, _)| n);
+
+    let mut merged: Vec<(u32, P, P)> = Vec::with_capacity(sorted.len());
+    for (n, cosine, sine) in sorted {
+        match merged.last_mut() {
+            Some(last) if last.0 == n => {
+                last.1 = last.1 + cosine;
+                last.2 = last.2 + sine;
+            }
+            _ => merged.push((n, cosine, sine)),
+        }
+    }
+
+    merged
+}
+
+/// Sums `cosine * cos(2 * PI * n * x) + sine * sin(2 * PI * n * x)` over `coefficients` sorted by `n`.
+#[inline]
+fn series<P: Precision>(x: P, coefficients: &[(u32, P, P)]) -> P {
+    let (sin_1, cos_1) = (P::two() * P::PI() * x).sin_cos();
+    let (mut sin_n, mut cos_n) = (P::zero(), P::one());
+    let mut previous = 0;
+    let mut sum = P::zero();
+
+    for &(n, cosine, sine) in coefficients {
+        if n == previous + 1 {
+            // angle addition, to avoid calling sin and cos for consecutive harmonics
+            let next_sin = sin_n * cos_1 + cos_n * sin_1;
+            cos_n = cos_n * cos_1 - sin_n * sin_1;
+            sin_n = next_sin;
+        } else if n != previous {
+            let n: P = NumCast::from(n).unwrap_or_else(P::nan);
+            (sin_n, cos_n) = (P::two() * P::PI() * (x * n).fract()).sin_cos();
+        }
+        previous = n;
+
+        sum = sum + cosine * cos_n + sine * sin_n;
+    }
+
+    sum
+}
+
+impl<P: Precision> PeriodicFunction<P> {
+    /// Fourier series builder, summing `amplitude * sin(2 * PI * (harmonic * fundamental * t + phase))` over the
+    /// `(harmonic, amplitude, phase)` triples of `harmonics`.
+    ///
+    /// Each term is the same as [`PeriodicFunction::sine`] of `harmonic * fundamental` frequency. Harmonic `0` adds a
+    /// constant. Once added to a [`Waveform`], harmonics at or above the Nyquist frequency are dropped.
+    ///
+    /// The terms are kept in the cosine and sine coefficient form, see [`PeriodicFunction::fourier_coefficients`].
+    ///
+    /// # Examples
+    ///
+    /// Square wave of amplitude 1, truncated to its first 50 odd harmonics:
+    ///
+    /// ```
+    /// use core::f32::consts::PI;
+    /// use wavegen::PeriodicFunction;
+    ///
+    /// let harmonics: Vec<_> = (1..100).step_by(2).map(|n| (n, 4.0 / (PI * n as f32), 0.0)).collect();
+    /// let square = PeriodicFunction::<f32>::fourier(50.0, &harmonics);
+    ///
+    /// assert!((square.sample(0.005) - 1.0).abs() < 0.02);
+    /// ```
+    ///
+    /// [`Waveform`]: crate::Waveform
+    #[inline]
+    pub fn fourier(fundamental: impl Into<P>, harmonics: &[(u32, P, P)]) -> Self {
+        let coefficients: Vec<_> = harmonics
+            .iter()
+            .map(|&(n, amplitude, phase)| {
+                let (sin, cos) = (P::two() * P::PI() * phase).sin_cos();
+
+                (n, amplitude * sin, amplitude * cos)
+            })
+            .collect();
+
+        Self::fourier_coefficients(fundamental, &coefficients)
+    }
+
+    /// Fallible version of [`PeriodicFunction::fourier`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error`] if the fundamental frequency, or any of the amplitudes or phases, is not a finite number.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wavegen::{Error, PeriodicFunction};
+    ///
+    /// assert!(PeriodicFunction::<f32>::try_fourier(50.0, &[(1, 1.0, 0.0), (3, 0.3, 0.5)]).is_ok());
+    /// assert_eq!(
+    ///     Some(Error::InvalidPhase),
+    ///     PeriodicFunction::<f32>::try_fourier(50.0, &[(1, 1.0, f32::NAN)]).err()
+    /// );
+    /// ```
+    #[inline]
+    pub fn try_fourier(
+        fundamental: impl Into<P>,
+        harmonics: &[(u32, P, P)],
+    ) -> Result<Self, Error> {
+        let fundamental = finite(fundamental.into(), Error::InvalidFrequency)?;
+        for &(_, amplitude, phase) in harmonics {
+            finite(amplitude, Error::InvalidAmplitude)?;
+            finite(phase, Error::InvalidPhase)?;
+        }
+
+        Ok(Self::fourier(fundamental, harmonics))
+    }
+
+    /// Fourier series builder, summing `cosine * cos(2 * PI * harmonic * fundamental * t) + sine * sin(2 * PI *
+    /// harmonic * fundamental * t)` over the `(harmonic, cosine, sine)` triples of `coefficients`.
+    ///
+    /// Coefficients of the same harmonic are added together. Once added to a [`Waveform`], harmonics at or above the
+    /// Nyquist frequency are dropped.
+    ///
+    /// Evaluating the series costs a single `sin_cos` call for every run of consecutive harmonics, so both dense and
+    /// sparse series evaluate efficiently.
+    ///
+    /// # Examples
+    ///
+    /// Sawtooth wave of amplitude 1, truncated to its first 100 harmonics:
+    ///
+    /// ```
+    /// use core::f32::consts::PI;
+    /// use wavegen::PeriodicFunction;
+    ///
+    /// let coefficients: Vec<_> = (1..=100).map(|n| (n, 0.0, -2.0 / (PI * n as f32))).collect();
+    /// let sawtooth = PeriodicFunction::<f32>::fourier_coefficients(50.0, &coefficients);
+    ///
+    /// assert!((sawtooth.sample(0.005) + 0.5).abs() < 0.01);
+    /// ```
+    ///
+    /// [`Waveform`]: crate::Waveform
+    #[inline]
+    pub fn fourier_coefficients(fundamental: impl Into<P>, coefficients: &[(u32, P, P)]) -> Self {
+        let fundamental = fundamental.into();
+        let coefficients = merged(coefficients);
+        let kind = Kind::Fourier {
+            fundamental,
+            coefficients: coefficients.clone(),
+        };
+        let all = coefficients.clone();
+
+        Self::new(Box::new(move |t| series(t * fundamental, &all)))
+            .with_bind(move |sample_rate| {
+                let limit = harmonics(fundamental, sample_rate);
+                let below_nyquist: Vec<_> = coefficients
+                    .iter()
+                    .copied()
+                    .filter(|&(n, _, _)| limit >= n.into())
+                    .collect();
+
+                move |t| series(t * fundamental, &below_nyquist)
+            })
+            .periodic(fundamental, Shape::Tone)
+            .with_kind(kind)
+    }
+
+    /// Fallible version of [`PeriodicFunction::fourier_coefficients`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidFrequency`] if the fundamental frequency is not a finite number, or
+    /// [`Error::InvalidAmplitude`] if any of the coefficients is not.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wavegen::{Error, PeriodicFunction};
+    ///
+    /// assert_eq!(
+    ///     Some(Error::InvalidAmplitude),
+    ///     PeriodicFunction::<f32>::try_fourier_coefficients(50.0, &[(1, f32::INFINITY, 0.0)]).err()
+    /// );
+    /// ```
+    #[inline]
+    pub fn try_fourier_coefficients(
+        fundamental: impl Into<P>,
+        coefficients: &[(u32, P, P)],
+    ) -> Result<Self, Error> {
+        let fundamental = finite(fundamental.into(), Error::InvalidFrequency)?;
+        for &(_, cosine, sine) in coefficients {
+            finite(cosine, Error::InvalidAmplitude)?;
+            finite(sine, Error::InvalidAmplitude)?;
+        }
+
+        Ok(Self::fourier_coefficients(fundamental, coefficients))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::merged;
+    use crate::{sawtooth, sine, square, BandLimit, Error, PeriodicFunction, Waveform};
+    use alloc::{vec, vec::Vec};
+    use core::f64::consts::PI;
+    use paste::paste;
+
+    const TIMES: [f64; 6] = [0.0, 0.0013, 0.025, 0.4, 0.71, 3.3];
+
+    fn float(x: impl Into<f64>) -> f64 {
+        x.into()
+    }
+
+    /// `(harmonic, amplitude, phase)` triples of the square wave, up to `harmonics`.
+    fn square_series(amplitude: f64, harmonics: u32) -> Vec<(u32, f64, f64)> {
+        (1..=harmonics)
+            .step_by(2)
+            .map(|n| (n, 4.0 * amplitude / (PI * float(n)), 0.0))
+            .collect()
+    }
+
+    /// `(harmonic, cosine, sine)` triples of the sawtooth wave, up to `harmonics`.
+    fn sawtooth_series(amplitude: f64, harmonics: u32) -> Vec<(u32, f64, f64)> {
+        (1..=harmonics)
+            .map(|n| (n, 0.0, -2.0 * amplitude / (PI * float(n))))
+            .collect()
+    }
+
+    macro_rules! test_single_harmonic {
+        ($($name:ident: $harmonic:expr, $amplitude:expr, $phase:expr)*) => {
+            $(
+                paste! {
+                    #[test]
+                    fn [<$name _is_sine_of_harmonic_frequency>]() {
+                        let f = PeriodicFunction::<f64>::fourier(5.0, &[($harmonic, $amplitude, $phase)]);
+                        let expected: PeriodicFunction<f64> = sine!(5.0 * float($harmonic), $amplitude, $phase);
+
+                        for t in TIMES {
+                            assert!((f.sample(t) - expected.sample(t)).abs() < 1e-9, "f({t})");
+                        }
+                    }
+                }
+            )*
+        };
+    }
+
+    test_single_harmonic! {
+        fundamental: 1u32, 1.0, 0.0
+        shifted_fundamental: 1u32, 2.0, 0.3
+        third: 3u32, 0.5, 0.25
+        high: 1000u32, 1.0, 0.7
+        negative_phase: 7u32, 3.0, -0.4
+    }
+
+    #[test]
+    fn coefficients_are_cosine_and_sine_terms() {
+        let f = PeriodicFunction::<f64>::fourier_coefficients(
+            2.0,
+            &[(0, 0.5, 0.0), (1, 1.0, 2.0), (4, -0.5, 0.0), (5, 0.0, 0.25)],
+        );
+
+        for t in TIMES {
+            let x = 2.0 * PI * 2.0 * t;
+            let expected =
+                0.5 + (x.cos() + 2.0 * x.sin()) - 0.5 * (4.0 * x).cos() + 0.25 * (5.0 * x).sin();
+
+            assert!((f.sample(t) - expected).abs() < 1e-9, "f({t})");
+        }
+    }
+
+    #[test]
+    fn harmonics_are_sorted_and_merged() {
+        assert_eq!(
+            vec![(0, 1.0, 0.0), (2, 1.5, 3.0), (3, 0.0, 1.0)],
+            merged(&[(3, 0.0, 1.0), (2, 1.0, 1.0), (0, 1.0, 0.0), (2, 0.5, 2.0)])
+        );
+    }
+
+    #[test]
+    fn truncated_series_approach_square_and_sawtooth() {
+        let square_series = PeriodicFunction::fourier(10.0, &square_series(2.0, 4001));
+        let sawtooth_series =
+            PeriodicFunction::fourier_coefficients(10.0, &sawtooth_series(2.0, 4000));
+        let square: PeriodicFunction<f64> = square!(10., 2.);
+        let sawtooth: PeriodicFunction<f64> = sawtooth!(10., 2.);
+
+        // away from the discontinuities, where the Gibbs phenomenon keeps the error from vanishing
+        for t in (1..100)
+            .map(|i| float(i) / 1000.0)
+            .filter(|t| ((t * 20.0) % 1.0 - 0.5).abs() < 0.4)
+        {
+            assert!(
+                (square_series.sample(t) - square.sample(t)).abs() < 1e-2,
+                "square({t})"
+            );
+        }
+        for t in (1..100)
+            .map(|i| float(i) / 1000.0)
+            .filter(|t| ((t * 10.0) % 1.0 - 0.5).abs() < 0.4)
+        {
+            assert!(
+                (sawtooth_series.sample(t) - sawtooth.sample(t)).abs() < 1e-2,
+                "sawtooth({t})"
+            );
+        }
+    }
+
+    #[test]
+    fn series_bound_to_waveform_matches_additive_band_limited_functions() {
+        let series = Waveform::<f64, f64>::with_components(
+            8000.0,
+            vec![
+                PeriodicFunction::fourier(110.0, &square_series(1.0, 1000)),
+                PeriodicFunction::fourier_coefficients(220.0, &sawtooth_series(0.5, 1000)),
+            ],
+        );
+        let additive = Waveform::<f64, f64>::with_components(
+            8000.0,
+            vec![
+                PeriodicFunction::band_limited_square(110.0, 1.0, 0.0, BandLimit::Additive),
+                PeriodicFunction::band_limited_sawtooth(220.0, 0.5, 0.0, BandLimit::Additive),
+            ],
+        );
+
+        assert!(series
+            .iter()
+            .zip(additive.iter())
+            .take(1000)
+            .all(|(x, y)| (x - y).abs() < 1e-9));
+    }
+
+    #[test]
+    fn harmonics_above_nyquist_are_dropped_when_bound() {
+        let f = || {
+            PeriodicFunction::fourier(
+                100.0,
+                &[(0, 1.0, 0.25), (1, 1.0, 0.0), (5, 1.0, 0.0), (30, 1.0, 0.0)],
+            )
+        };
+        let wf = Waveform::<f64, f64>::with_components(1000.0, vec![f()]);
+        let expected =
+            Waveform::<f64, f64>::with_components(1000.0, vec![sine!(100.), sine!(0., 1., 0.25)]);
+
+        assert!(wf
+            .iter()
+            .zip(expected.iter())
+            .take(100)
+            .all(|(x, y)| (x - y).abs() < 1e-9));
+        // the unbound function still has all the harmonics
+        assert!((f().sample(0.0004) - f().bound(1000.0).sample(0.0004)).abs() > 0.1);
+    }
+
+    #[test]
+    fn fundamental_above_nyquist_is_reported() {
+        let wf = Waveform::<f64, f64>::with_components(
+            1000.0,
+            vec![PeriodicFunction::fourier(600.0, &square_series(1.0, 9))],
+        );
+
+        assert_eq!(Some(600.0), wf.components()[0].frequency);
+        assert!(!wf.validate(0.01).is_ok());
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn empty_series_is_zero() {
+        let f = PeriodicFunction::<f64>::fourier(10.0, &[]);
+
+        assert!(TIMES.iter().all(|&t| f.sample(t) == 0.0));
+    }
+
+    #[test]
+    fn errors_are_reported() {
+        assert_eq!(
+            Some(Error::InvalidFrequency),
+            PeriodicFunction::<f64>::try_fourier(f64::NAN, &[(1, 1.0, 0.0)]).err()
+        );
+        assert_eq!(
+            Some(Error::InvalidAmplitude),
+            PeriodicFunction::<f64>::try_fourier(1.0, &[(1, f64::INFINITY, 0.0)]).err()
+        );
+        assert_eq!(
+            Some(Error::InvalidAmplitude),
+            PeriodicFunction::<f64>::try_fourier_coefficients(1.0, &[(1, 0.0, f64::NAN)]).err()
+        );
+    }
+}
@@ -0,0 +1,740 @@
+//! Typed description of built-in periodic functions.
+
+use crate::{
+    AfterSweep, BandLimit, Dds, Error, Interpolation, PeriodicFunction, Placement, Precision,
+    Sweep, Trigger,
+};
+use alloc::{boxed::Box, vec::Vec};
+
+/// Color of a noise function, as built by the [`PeriodicFunction`] noise builders.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum NoiseColor {
+    /// See [`PeriodicFunction::white_noise`].
+    White,
+    /// See [`PeriodicFunction::gaussian_noise`].
+    Gaussian,
+    /// See [`PeriodicFunction::pink_noise`].
+    Pink,
+    /// See [`PeriodicFunction::brown_noise`].
+    Brown,
+    /// See [`PeriodicFunction::blue_noise`].
+    Blue,
+    /// See [`PeriodicFunction::violet_noise`].
+    Violet,
+}
+
+/// Kind of a [`PeriodicFunction`], together with the parameters it was built with.
+///
+/// Every built-in builder records its kind, which can be read back with [`PeriodicFunction::kind`]. Functions built
+/// from closures, with [`PeriodicFunction::new`] or [`PeriodicFunction::custom`], are of [`Kind::Custom`] kind.
+///
+/// Parameters are stored after normalization done by the builder, so for example a clamped `duty` is reported clamped.
+///
+/// # Examples
+///
+/// ```
+/// use wavegen::{sine, Kind, PeriodicFunction};
+///
+/// let f: PeriodicFunction<f32> = sine!(50., 10.);
+///
+/// assert_eq!(&Kind::Sine { frequency: 50., amplitude: 10., phase: 0. }, f.kind());
+/// assert_eq!(Some(50.), f.kind().frequency());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
+pub enum Kind<P> {
+    /// Function built from a closure.
+    Custom,
+    /// See [`PeriodicFunction::dc_bias`].
+    DcBias {
+        /// Constant value.
+        bias: P,
+    },
+    /// See [`PeriodicFunction::sawtooth`].
+    Sawtooth {
+        /// Frequency in Hz.
+        frequency: P,
+        /// Amplitude.
+        amplitude: P,
+        /// Phase, in periods.
+        phase: P,
+    },
+    /// See [`PeriodicFunction::sine`].
+    Sine {
+        /// Frequency in Hz.
+        frequency: P,
+        /// Amplitude.
+        amplitude: P,
+        /// Phase, in periods.
+        phase: P,
+    },
+    /// See [`PeriodicFunction::square`].
+    Square {
+        /// Frequency in Hz.
+        frequency: P,
+        /// Amplitude.
+        amplitude: P,
+        /// Phase, in seconds.
+        phase: P,
+    },
+    /// See [`PeriodicFunction::triangle`].
+    Triangle {
+        /// Frequency in Hz.
+        frequency: P,
+        /// Amplitude.
+        amplitude: P,
+        /// Phase, in periods.
+        phase: P,
+    },
+    /// See [`PeriodicFunction::ramp`].
+    Ramp {
+        /// Frequency in Hz.
+        frequency: P,
+        /// Amplitude.
+        amplitude: P,
+        /// Phase, in periods.
+        phase: P,
+        /// Fraction of the period spent rising.
+        symmetry: P,
+    },
+    /// See [`PeriodicFunction::pulse`].
+    Pulse {
+        /// Frequency in Hz.
+        frequency: P,
+        /// Amplitude.
+        amplitude: P,
+        /// Phase, in periods.
+        phase: P,
+        /// Fraction of the period spent high.
+        duty: P,
+    },
+    /// See [`PeriodicFunction::unipolar_pulse`].
+    UnipolarPulse {
+        /// Frequency in Hz.
+        frequency: P,
+        /// Amplitude.
+        amplitude: P,
+        /// Phase, in periods.
+        phase: P,
+        /// Fraction of the period spent high.
+        duty: P,
+    },
+    /// See [`PeriodicFunction::band_limited_sawtooth`].
+    BandLimitedSawtooth {
+        /// Frequency in Hz.
+        frequency: P,
+        /// Amplitude.
+        amplitude: P,
+        /// Phase, in periods.
+        phase: P,
+        /// Band-limiting method.
+        method: BandLimit,
+    },
+    /// See [`PeriodicFunction::band_limited_square`].
+    BandLimitedSquare {
+        /// Frequency in Hz.
+        frequency: P,
+        /// Amplitude.
+        amplitude: P,
+        /// Phase, in seconds.
+        phase: P,
+        /// Band-limiting method.
+        method: BandLimit,
+    },
+    /// See [`PeriodicFunction::band_limited_pulse`].
+    BandLimitedPulse {
+        /// Frequency in Hz.
+        frequency: P,
+        /// Amplitude.
+        amplitude: P,
+        /// Phase, in periods.
+        phase: P,
+        /// Fraction of the period spent high.
+        duty: P,
+        /// Band-limiting method.
+        method: BandLimit,
+    },
+    /// See [`PeriodicFunction::band_limited_unipolar_pulse`].
+    BandLimitedUnipolarPulse {
+        /// Frequency in Hz.
+        frequency: P,
+        /// Amplitude.
+        amplitude: P,
+        /// Phase, in periods.
+        phase: P,
+        /// Fraction of the period spent high.
+        duty: P,
+        /// Band-limiting method.
+        method: BandLimit,
+    },
+    /// See [`PeriodicFunction::chirp`].
+    Chirp {
+        /// Frequency at the start of the sweep, in Hz.
+        start_frequency: P,
+        /// Frequency at the end of the sweep, in Hz.
+        end_frequency: P,
+        /// Duration of the sweep, in seconds.
+        duration: P,
+        /// Amplitude.
+        amplitude: P,
+        /// Phase, in periods.
+        phase: P,
+        /// Sweep law.
+        sweep: Sweep,
+        /// Behavior after the sweep.
+        after: AfterSweep,
+    },
+    /// See [`PeriodicFunction::dds`].
+    Dds {
+        /// Frequency in Hz.
+        frequency: P,
+        /// Amplitude.
+        amplitude: P,
+        /// Phase, in periods.
+        phase: P,
+        /// Emulated core.
+        dds: Dds,
+    },
+    /// See the noise builders of [`PeriodicFunction`].
+    Noise {
+        /// Noise color.
+        color: NoiseColor,
+        /// Number of values drawn per second.
+        rate: P,
+        /// Amplitude, or standard deviation for [`NoiseColor::Gaussian`].
+        amplitude: P,
+        /// Seed.
+        seed: u64,
+    },
+    /// Sum of functions, see [`PeriodicFunction`]'s `Add` and `Sub` implementations.
+    Sum(Vec<Kind<P>>),
+    /// Product of functions, see [`PeriodicFunction`]'s `Mul` implementation.
+    Product(Vec<Kind<P>>),
+    /// Negated function, see [`PeriodicFunction`]'s `Neg` implementation.
+    Neg(Box<Kind<P>>),
+    /// See [`PeriodicFunction::compose`].
+    Compose {
+        /// Function applied last.
+        outer: Box<Kind<P>>,
+        /// Function applied first.
+        inner: Box<Kind<P>>,
+    },
+    /// See [`PeriodicFunction::abs`].
+    Abs(Box<Kind<P>>),
+    /// See [`PeriodicFunction::clamp`].
+    Clamp {
+        /// Clamped function.
+        function: Box<Kind<P>>,
+        /// Lower bound.
+        min: P,
+        /// Upper bound.
+        max: P,
+    },
+    /// See [`PeriodicFunction::powf`].
+    Powf {
+        /// Base function.
+        function: Box<Kind<P>>,
+        /// Exponent.
+        exponent: P,
+    },
+    /// See [`PeriodicFunction::am`].
+    Am {
+        /// Modulated function.
+        carrier: Box<Kind<P>>,
+        /// Modulating function.
+        modulator: Box<Kind<P>>,
+        /// Modulation depth.
+        depth: P,
+    },
+    /// See [`PeriodicFunction::fm`].
+    Fm {
+        /// Frequency of the unmodulated carrier.
+        carrier_frequency: P,
+        /// Modulating function.
+        modulator: Box<Kind<P>>,
+        /// Frequency deviation.
+        deviation: P,
+    },
+    /// See [`PeriodicFunction::pm`].
+    Pm {
+        /// Frequency of the carrier.
+        carrier_frequency: P,
+        /// Modulating function.
+        modulator: Box<Kind<P>>,
+        /// Phase deviation, in radians.
+        deviation: P,
+    },
+    /// See [`PeriodicFunction::adsr`].
+    Adsr {
+        /// Attack time.
+        attack: P,
+        /// Decay time.
+        decay: P,
+        /// Sustain level.
+        sustain: P,
+        /// Release time.
+        release: P,
+        /// Trigger.
+        trigger: Trigger<P>,
+    },
+    /// See [`PeriodicFunction::ar`].
+    Ar {
+        /// Attack time.
+        attack: P,
+        /// Release time.
+        release: P,
+        /// Trigger.
+        trigger: Trigger<P>,
+    },
+    /// See [`PeriodicFunction::exponential_decay`].
+    ExponentialDecay {
+        /// Time the level decays by the factor of e in.
+        time_constant: P,
+        /// Trigger.
+        trigger: Trigger<P>,
+    },
+    /// See [`PeriodicFunction::piecewise_linear`].
+    PiecewiseLinear {
+        /// Points, as time since trigger and level, sorted by time.
+        points: Vec<(P, P)>,
+        /// Trigger.
+        trigger: Trigger<P>,
+    },
+    /// See [`Sequence`](crate::Sequence).
+    Sequence {
+        /// Sequenced functions, with their placement.
+        segments: Vec<(Kind<P>, Placement<P>)>,
+        /// Total duration.
+        duration: P,
+        /// Whether the sequence is looped.
+        looping: bool,
+    },
+    /// See [`PeriodicFunction::fourier`] and [`PeriodicFunction::fourier_coefficients`].
+    Fourier {
+        /// Fundamental frequency in Hz.
+        fundamental: P,
+        /// Harmonic number, cosine and sine coefficient of every term, sorted by harmonic number.
+        coefficients: Vec<(u32, P, P)>,
+    },
+    /// See [`PeriodicFunction::wavetable`].
+    Wavetable {
+        /// Frequency in Hz.
+        frequency: P,
+        /// Amplitude.
+        amplitude: P,
+        /// Phase, in periods.
+        phase: P,
+        /// Values over one period.
+        table: Vec<P>,
+        /// Interpolation between the values.
+        interpolation: Interpolation,
+    },
+    /// See [`PeriodicFunction::tabulated`].
+    Tabulated {
+        /// Tabulated function.
+        function: Box<Kind<P>>,
+        /// Number of values in the table.
+        size: usize,
+        /// Interpolation between the values.
+        interpolation: Interpolation,
+    },
+}
+
+impl<P: Precision> Kind<P> {
+    /// Returns the frequency of the function, if it has a constant one.
+    pub fn frequency(&self) -> Option<P> {
+        match *self {
+            Kind::Sawtooth { frequency, .. }
+            | Kind::Sine { frequency, .. }
+            | Kind::Square { frequency, .. }
+            | Kind::Triangle { frequency, .. }
+            | Kind::Ramp { frequency, .. }
+            | Kind::Pulse { frequency, .. }
+            | Kind::UnipolarPulse { frequency, .. }
+            | Kind::BandLimitedSawtooth { frequency, .. }
+            | Kind::BandLimitedSquare { frequency, .. }
+            | Kind::BandLimitedPulse { frequency, .. }
+            | Kind::BandLimitedUnipolarPulse { frequency, .. }
+            | Kind::Dds { frequency, .. }
+            | Kind::Wavetable { frequency, .. } => Some(frequency),
+            Kind::Fourier { fundamental, .. } => Some(fundamental),
+            _ => None,
+        }
+    }
+
+    /// Returns the amplitude of the function, if it has one.
+    ///
+    /// This is the bias for [`Kind::DcBias`], and the standard deviation for gaussian [`Kind::Noise`].
+    pub fn amplitude(&self) -> Option<P> {
+        match *self {
+            Kind::DcBias { bias } => Some(bias),
+            Kind::Sawtooth { amplitude, .. }
+            | Kind::Sine { amplitude, .. }
+            | Kind::Square { amplitude, .. }
+            | Kind::Triangle { amplitude, .. }
+            | Kind::Ramp { amplitude, .. }
+            | Kind::Pulse { amplitude, .. }
+            | Kind::UnipolarPulse { amplitude, .. }
+            | Kind::BandLimitedSawtooth { amplitude, .. }
+            | Kind::BandLimitedSquare { amplitude, .. }
+            | Kind::BandLimitedPulse { amplitude, .. }
+            | Kind::BandLimitedUnipolarPulse { amplitude, .. }
+            | Kind::Chirp { amplitude, .. }
+            | Kind::Dds { amplitude, .. }
+            | Kind::Wavetable { amplitude, .. }
+            | Kind::Noise { amplitude, .. } => Some(amplitude),
+            _ => None,
+        }
+    }
+
+    /// Returns the phase of the function, if it has one.
+    ///
+    /// Note that the phase of [`Kind::Square`] and [`Kind::BandLimitedSquare`] is expressed in seconds, while all the
+    /// others are expressed in periods.
+    pub fn phase(&self) -> Option<P> {
+        match *self {
+            Kind::Sawtooth { phase, .. }
+            | Kind::Sine { phase, .. }
+            | Kind::Square { phase, .. }
+            | Kind::Triangle { phase, .. }
+            | Kind::Ramp { phase, .. }
+            | Kind::Pulse { phase, .. }
+            | Kind::UnipolarPulse { phase, .. }
+            | Kind::BandLimitedSawtooth { phase, .. }
+            | Kind::BandLimitedSquare { phase, .. }
+            | Kind::BandLimitedPulse { phase, .. }
+            | Kind::BandLimitedUnipolarPulse { phase, .. }
+            | Kind::Chirp { phase, .. }
+            | Kind::Dds { phase, .. }
+            | Kind::Wavetable { phase, .. } => Some(phase),
+            _ => None,
+        }
+    }
+
+    /// Builds a new [`PeriodicFunction`] of this kind, or returns [`None`] for [`Kind::Custom`] and for parameters
+    /// rejected by the builders. See [`Kind::try_build`].
+    ///
+    /// This allows editing the parameters of an existing function.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wavegen::{sine, Kind, PeriodicFunction};
+    ///
+    /// let f: PeriodicFunction<f32> = sine!(50.);
+    /// let mut kind = f.kind().clone();
+    /// if let Kind::Sine { frequency, .. } = &mut kind {
+    ///     *frequency = 100.;
+    /// }
+    ///
+    /// assert_eq!(Some(100.), kind.build().unwrap().kind().frequency());
+    /// ```
+    pub fn build(&self) -> Option<PeriodicFunction<P>> {
+        self.try_build().ok()
+    }
+
+    /// Fallible version of [`Kind::build`], validating the parameters with the `try_*` builders.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::CustomComponent`] for [`Kind::Custom`], or the [`Error`] of the builder rejecting the
+    /// parameters of this kind, or of any of the kinds it is made of.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wavegen::{Error, Kind};
+    ///
+    /// let kind = Kind::<f32>::Pulse { frequency: 10., amplitude: 1., phase: 0., duty: 2. };
+    ///
+    /// assert_eq!(Some(Error::InvalidDuty), kind.try_build().err());
+    /// assert_eq!(Some(Error::CustomComponent), Kind::<f32>::Custom.try_build().err());
+    /// ```
+    #[allow(clippy::too_many_lines)]
+    pub fn try_build(&self) -> Result<PeriodicFunction<P>, Error> {
+        let f = match *self {
+            Kind::Custom => return Err(Error::CustomComponent),
+            Kind::DcBias { bias } => PeriodicFunction::try_dc_bias(bias)?,
+            Kind::Sawtooth {
+                frequency,
+                amplitude,
+                phase,
+            } => PeriodicFunction::try_sawtooth(frequency, amplitude, phase)?,
+            Kind::Sine {
+                frequency,
+                amplitude,
+                phase,
+            } => PeriodicFunction::try_sine(frequency, amplitude, phase)?,
+            Kind::Square {
+                frequency,
+                amplitude,
+                phase,
+            } => PeriodicFunction::try_square(frequency, amplitude, phase)?,
+            Kind::Triangle {
+                frequency,
+                amplitude,
+                phase,
+            } => PeriodicFunction::try_triangle(frequency, amplitude, phase)?,
+            Kind::Ramp {
+                frequency,
+                amplitude,
+                phase,
+                symmetry,
+            } => PeriodicFunction::try_ramp(frequency, amplitude, phase, symmetry)?,
+            Kind::Pulse {
+                frequency,
+                amplitude,
+                phase,
+                duty,
+            } => PeriodicFunction::try_pulse(frequency, amplitude, phase, duty)?,
+            Kind::UnipolarPulse {
+                frequency,
+                amplitude,
+                phase,
+                duty,
+            } => PeriodicFunction::try_unipolar_pulse(frequency, amplitude, phase, duty)?,
+            Kind::BandLimitedSawtooth {
+                frequency,
+                amplitude,
+                phase,
+                method,
+            } => PeriodicFunction::band_limited_sawtooth(frequency, amplitude, phase, method),
+            Kind::BandLimitedSquare {
+                frequency,
+                amplitude,
+                phase,
+                method,
+            } => PeriodicFunction::band_limited_square(frequency, amplitude, phase, method),
+            Kind::BandLimitedPulse {
+                frequency,
+                amplitude,
+                phase,
+                duty,
+                method,
+            } => PeriodicFunction::band_limited_pulse(frequency, amplitude, phase, duty, method),
+            Kind::BandLimitedUnipolarPulse {
+                frequency,
+                amplitude,
+                phase,
+                duty,
+                method,
+            } => PeriodicFunction::band_limited_unipolar_pulse(
+                frequency, amplitude, phase, duty, method,
+            ),
+            Kind::Chirp {
+                start_frequency,
+                end_frequency,
+                duration,
+                amplitude,
+                phase,
+                sweep,
+                after,
+            } => PeriodicFunction::try_chirp(
+                start_frequency,
+                end_frequency,
+                duration,
+                amplitude,
+                phase,
+                sweep,
+                after,
+            )?,
+            Kind::Dds {
+                frequency,
+                amplitude,
+                phase,
+                dds,
+            } => PeriodicFunction::try_dds(frequency, amplitude, phase, dds)?,
+            Kind::Noise {
+                color,
+                rate,
+                amplitude,
+                seed,
+            } => match color {
+                NoiseColor::White => PeriodicFunction::white_noise(rate, amplitude, seed),
+                NoiseColor::Gaussian => PeriodicFunction::gaussian_noise(rate, amplitude, seed),
+                NoiseColor::Pink => PeriodicFunction::pink_noise(rate, amplitude, seed),
+                NoiseColor::Brown => PeriodicFunction::brown_noise(rate, amplitude, seed),
+                NoiseColor::Blue => PeriodicFunction::blue_noise(rate, amplitude, seed),
+                NoiseColor::Violet => PeriodicFunction::violet_noise(rate, amplitude, seed),
+            },
+            Kind::Sum(ref kinds) => PeriodicFunction::sum_of(kinds)?,
+            Kind::Product(ref kinds) => PeriodicFunction::product_of(kinds)?,
+            Kind::Neg(ref kind) => -kind.try_build()?,
+            Kind::Compose {
+                ref outer,
+                ref inner,
+            } => outer.try_build()?.compose(inner.try_build()?),
+            Kind::Abs(ref kind) => kind.try_build()?.abs(),
+            Kind::Clamp {
+                ref function,
+                min,
+                max,
+            } => function.try_build()?.clamp(min, max),
+            Kind::Powf {
+                ref function,
+                exponent,
+            } => function.try_build()?.powf(exponent),
+            Kind::Am {
+                ref carrier,
+                ref modulator,
+                depth,
+            } => PeriodicFunction::am(carrier.try_build()?, modulator.try_build()?, depth),
+            Kind::Fm {
+                carrier_frequency,
+                ref modulator,
+                deviation,
+            } => PeriodicFunction::fm(carrier_frequency, modulator.try_build()?, deviation)?,
+            Kind::Pm {
+                carrier_frequency,
+                ref modulator,
+                deviation,
+            } => PeriodicFunction::pm(carrier_frequency, modulator.try_build()?, deviation)?,
+            Kind::Adsr {
+                attack,
+                decay,
+                sustain,
+                release,
+                trigger,
+            } => PeriodicFunction::adsr(attack, decay, sustain, release, trigger),
+            Kind::Ar {
+                attack,
+                release,
+                trigger,
+            } => PeriodicFunction::ar(attack, release, trigger),
+            Kind::ExponentialDecay {
+                time_constant,
+                trigger,
+            } => PeriodicFunction::exponential_decay(time_constant, trigger),
+            Kind::PiecewiseLinear {
+                ref points,
+                trigger,
+            } => PeriodicFunction::piecewise_linear(points, trigger),
+            Kind::Sequence {
+                ref segments,
+                duration,
+                looping,
+            } => PeriodicFunction::sequence_of(segments, duration, looping)?,
+            Kind::Fourier {
+                fundamental,
+                ref coefficients,
+            } => PeriodicFunction::try_fourier_coefficients(fundamental, coefficients)?,
+            Kind::Wavetable {
+                frequency,
+                amplitude,
+                phase,
+                ref table,
+                interpolation,
+            } => PeriodicFunction::try_wavetable(
+                frequency,
+                amplitude,
+                phase,
+                table.clone(),
+                interpolation,
+            )?,
+            Kind::Tabulated {
+                ref function,
+                size,
+                interpolation,
+            } => function.try_build()?.try_tabulated(size, interpolation)?,
+        };
+
+        Ok(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{chirp, dc_bias, pulse, ramp, sawtooth, sine, square, triangle, unipolar_pulse};
+    use alloc::{format, vec};
+    use float_cmp::approx_eq;
+    use paste::paste;
+
+    macro_rules! test_kind {
+        ($($name:ident: $f:expr => $kind:expr)*) => {
+            $(
+                paste! {
+                    #[test]
+                    fn [<$name _has_kind>]() {
+                        let f: PeriodicFunction<f64> = $f;
+
+                        assert_eq!(&$kind, f.kind());
+                    }
+
+                    #[test]
+                    #[allow(clippy::unwrap_used)]
+                    fn [<$name _rebuilt_from_kind_is_same>]() {
+                        let f: PeriodicFunction<f64> = $f;
+                        let rebuilt = f.kind().build().unwrap();
+
+                        assert_eq!(f.kind(), rebuilt.kind());
+                        for t in [0.0, 0.013, 0.25, 0.71, 3.3] {
+                            assert!(approx_eq!(f64, f.sample(t), rebuilt.sample(t)));
+                        }
+                    }
+                }
+            )*
+        };
+    }
+
+    test_kind! {
+        dc_bias: dc_bias!(3.) => Kind::DcBias { bias: 3.0 }
+        sawtooth: sawtooth!(2., 3., 0.1) => Kind::Sawtooth { frequency: 2.0, amplitude: 3.0, phase: 0.1 }
+        sine: sine!(2., 3., 0.1) => Kind::Sine { frequency: 2.0, amplitude: 3.0, phase: 0.1 }
+        square: square!(2., 3., 0.1) => Kind::Square { frequency: 2.0, amplitude: 3.0, phase: 0.1 }
+        triangle: triangle!(2., 3., 0.1) => Kind::Triangle { frequency: 2.0, amplitude: 3.0, phase: 0.1 }
+        ramp: ramp!(2., 3., 0.1, 0.3) => Kind::Ramp { frequency: 2.0, amplitude: 3.0, phase: 0.1, symmetry: 0.3 }
+        pulse_with_clamped_duty: pulse!(2., 3., 0.1, 1.5) => Kind::Pulse { frequency: 2.0, amplitude: 3.0, phase: 0.1, duty: 1.0 }
+        unipolar_pulse: unipolar_pulse!(2., 3., 0.1, 0.3) => Kind::UnipolarPulse { frequency: 2.0, amplitude: 3.0, phase: 0.1, duty: 0.3 }
+        band_limited_sawtooth: PeriodicFunction::band_limited_sawtooth(2., 3., 0.1, BandLimit::PolyBlep) => Kind::BandLimitedSawtooth { frequency: 2.0, amplitude: 3.0, phase: 0.1, method: BandLimit::PolyBlep }
+        band_limited_square: PeriodicFunction::band_limited_square(2., 3., 0.1, BandLimit::Additive) => Kind::BandLimitedSquare { frequency: 2.0, amplitude: 3.0, phase: 0.1, method: BandLimit::Additive }
+        band_limited_pulse: PeriodicFunction::band_limited_pulse(2., 3., 0.1, 0.3, BandLimit::PolyBlep) => Kind::BandLimitedPulse { frequency: 2.0, amplitude: 3.0, phase: 0.1, duty: 0.3, method: BandLimit::PolyBlep }
+        band_limited_unipolar_pulse: PeriodicFunction::band_limited_unipolar_pulse(2., 3., 0.1, 0.3, BandLimit::Additive) => Kind::BandLimitedUnipolarPulse { frequency: 2.0, amplitude: 3.0, phase: 0.1, duty: 0.3, method: BandLimit::Additive }
+        chirp: chirp!(10., 20., 1.) => Kind::Chirp { start_frequency: 10.0, end_frequency: 20.0, duration: 1.0, amplitude: 1.0, phase: 0.0, sweep: Sweep::Linear, after: AfterSweep::Hold }
+        dds: PeriodicFunction::dds(2., 3., 0.1, Dds::new(32, 12, 14).with_dither(5)) => Kind::Dds { frequency: 2.0, amplitude: 3.0, phase: 0.1, dds: Dds::new(32, 12, 14).with_dither(5) }
+        wavetable: PeriodicFunction::wavetable(2., 3., 0.1, vec![0.0, 1.0, -0.5], Interpolation::Cubic) => Kind::Wavetable { frequency: 2.0, amplitude: 3.0, phase: 0.1, table: vec![0.0, 1.0, -0.5], interpolation: Interpolation::Cubic }
+        fourier: PeriodicFunction::fourier_coefficients(2., &[(3, 0.5, 0.25), (0, 1.0, 0.0), (1, 0.0, 2.0)]) => Kind::Fourier { fundamental: 2.0, coefficients: vec![(0, 1.0, 0.0), (1, 0.0, 2.0), (3, 0.5, 0.25)] }
+        white_noise: PeriodicFunction::white_noise(100., 2., 7) => Kind::Noise { color: NoiseColor::White, rate: 100.0, amplitude: 2.0, seed: 7 }
+        gaussian_noise: PeriodicFunction::gaussian_noise(100., 2., 7) => Kind::Noise { color: NoiseColor::Gaussian, rate: 100.0, amplitude: 2.0, seed: 7 }
+        pink_noise: PeriodicFunction::pink_noise(100., 2., 7) => Kind::Noise { color: NoiseColor::Pink, rate: 100.0, amplitude: 2.0, seed: 7 }
+        brown_noise: PeriodicFunction::brown_noise(100., 2., 7) => Kind::Noise { color: NoiseColor::Brown, rate: 100.0, amplitude: 2.0, seed: 7 }
+        blue_noise: PeriodicFunction::blue_noise(100., 2., 7) => Kind::Noise { color: NoiseColor::Blue, rate: 100.0, amplitude: 2.0, seed: 7 }
+        violet_noise: PeriodicFunction::violet_noise(100., 2., 7) => Kind::Noise { color: NoiseColor::Violet, rate: 100.0, amplitude: 2.0, seed: 7 }
+    }
+
+    #[test]
+    fn custom_function_has_custom_kind() {
+        let f = PeriodicFunction::custom(|t: f64| t * 2.0);
+
+        assert_eq!(&Kind::Custom, f.kind());
+        assert!(f.kind().build().is_none());
+        assert_eq!(None, f.kind().frequency());
+        assert_eq!(None, f.kind().amplitude());
+        assert_eq!(None, f.kind().phase());
+    }
+
+    #[test]
+    fn getters_return_parameters() {
+        let kind = Kind::Pulse {
+            frequency: 1.0,
+            amplitude: 2.0,
+            phase: 3.0,
+            duty: 0.5,
+        };
+
+        assert_eq!(Some(1.0), kind.frequency());
+        assert_eq!(Some(2.0), kind.amplitude());
+        assert_eq!(Some(3.0), kind.phase());
+    }
+
+    #[test]
+    fn debug_shows_kind() {
+        let wf = crate::Waveform::<f32>::with_components(100.0, vec![sine!(10.), dc_bias!(1.)]);
+        let debug = format!("{wf:?}");
+
+        assert!(debug.contains("Sine { frequency: 10.0, amplitude: 1.0, phase: 0.0 }"));
+        assert!(debug.contains("DcBias { bias: 1.0 }"));
+    }
+}
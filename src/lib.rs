@@ -3,7 +3,7 @@
 //! # Quickstart
 //!
 //! ```
-//! use wavegen::{wf, sine, dc_bias, sawtooth};
+//! use wavegen::{wf, sine, dc_bias, sawtooth, Duration};
 //!
 //! // Define a Waveform with 200Hz sampling rate and three function components,
 //! // choosing f32 as the output type:
@@ -11,6 +11,10 @@
 //!
 //! // Use Waveform as an infinite iterator:
 //! let two_seconds_of_samples: Vec<f32> = waveform.iter().take(400).collect();
+//!
+//! // Or render a finite duration of it:
+//! let same_two_seconds: Vec<f32> = waveform.render(Duration::Seconds(2.));
+//! assert_eq!(two_seconds_of_samples, same_two_seconds);
 //! ```
 //!
 //! Look into macros section for a complete list of defined periodic functions and their constructors.
@@ -37,6 +41,45 @@
 //!
 //! Refer to Macros section for more info.
 //!
+//! # Arithmetic
+//!
+//! [`PeriodicFunction`]s can be added, subtracted, multiplied and negated, as well as composed and transformed with
+//! [`PeriodicFunction::abs`], [`PeriodicFunction::clamp`] and [`PeriodicFunction::powf`]. A whole [`Waveform`] can be
+//! turned into a [`PeriodicFunction`], to be nested in another one:
+//!
+//! ```
+//! use core::convert::TryFrom;
+//! use wavegen::{wf, dc_bias, sine, square, PeriodicFunction};
+//!
+//! // 1 Hz sine, keyed on and off by a 0.1 Hz square
+//! let burst: PeriodicFunction<f32> = sine!(1.) * (square!(0.1) + dc_bias!(1.)).clamp(0., 1.);
+//! let nested = PeriodicFunction::try_from(wf!(f32, 100., sine!(2.), square!(3.))).unwrap();
+//! let waveform = wf!(f32, 100., burst, nested);
+//! ```
+//!
+//! # Envelopes
+//!
+//! Envelope macros, like [`adsr!`], [`ar!`], [`exponential_decay!`] and [`piecewise_linear!`], build [`PeriodicFunction`]s
+//! to multiply other functions with. A [`Trigger`] defines whether the envelope plays once, or loops:
+//!
+//! ```
+//! use wavegen::{wf, adsr, sine};
+//!
+//! // 440 Hz note, played for 0.5 second every second
+//! let waveform = wf!(f32, 44100., sine!(440.) * adsr!(attack: 0.01, decay: 0.1, sustain: 0.7, release: 0.2, period: 1., gate: 0.5));
+//! ```
+//!
+//! # Text expressions
+//!
+//! [`Waveform`]s can also be parsed from text, for example entered by the user. Function names and arguments follow
+//! the macros, see [`Waveform::parse`] for details:
+//!
+//! ```
+//! use wavegen::Waveform;
+//!
+//! let waveform = Waveform::<f32>::parse(200., "sine(50, 10) + sawtooth(frequency: 20) - dc(5)").unwrap();
+//! ```
+//!
 //! # Custom periodic functions
 //! Supported, of course. Just define your custom function as `Box<Fn(f64) -> f64>` and use it with [`Waveform`].
 //!
@@ -46,6 +89,113 @@
 //! let waveform = wf!(f64, 100.0, PeriodicFunction::custom(|x| x % 2.0));
 //! ```
 //!
+//! Built-in functions remember how they were built, which can be read back as a [`Kind`]:
+//!
+//! ```
+//! use wavegen::{wf, sine, Kind, PeriodicFunction};
+//!
+//! let waveform = wf!(f64, 100.0, sine!(10., 2.), PeriodicFunction::custom(|x| x % 2.0));
+//!
+//! assert_eq!(Some(10.0), waveform.components()[0].kind().frequency());
+//! assert_eq!(&Kind::Custom, waveform.components()[1].kind());
+//! ```
+//!
+//! # Serialisation
+//!
+//! With the `serde` feature enabled, [`Waveform`]s and [`PeriodicFunction`]s made of built-in components implement
+//! `Serialize` and `Deserialize`, storing the sample rate, precision and the [`Kind`] of every component. Custom and
+//! stateful components cannot be described, so serialising them fails with [`Error::CustomComponent`]. The precision
+//! is stored by its `PrecisionTag`, which is only implemented for [`f32`] and [`f64`].
+//!
+//! # Stateful components
+//!
+//! Filters, random walks and other components that depend on previous samples can be expressed as a [`StatefulFunction`].
+//! Its state is created separately for every iterator, so iterators over the same [`Waveform`] stay independent:
+//!
+//! ```
+//! use wavegen::{Waveform, StatefulFunction};
+//!
+//! let mut wf = Waveform::<f32>::new(100.0);
+//! wf.add_stateful_component(StatefulFunction::new(|sample_rate: f32| {
+//!     let mut y = 0.0;
+//!     move |_| {
+//!         y += 1.0 / sample_rate;
+//!         y
+//!     }
+//! }));
+//!
+//! assert_eq!(wf.iter().take(10).collect::<Vec<_>>(), wf.iter().take(10).collect::<Vec<_>>());
+//! ```
+//!
+//! # Noise
+//!
+//! Seeded noise generators of different colors (white, pink, brown, blue, violet and gaussian white) are available as
+//! [`PeriodicFunction`] builders. Noise is drawn at a given rate, and is fully reproducible for a given seed:
+//!
+//! ```
+//! use wavegen::{wf, sine, PeriodicFunction};
+//!
+//! let noisy_sine = wf!(f32, 44100., sine!(440.), PeriodicFunction::pink_noise(44100., 0.1, 1234));
+//! ```
+//!
+//! # Direct digital synthesis
+//!
+//! [`PeriodicFunction::dds`] emulates the output of a hardware DDS core, clocked at the sample rate of the [`Waveform`].
+//! The phase accumulator, phase truncation, amplitude quantisation and phase dither of the core are set by [`Dds`]:
+//!
+//! ```
+//! use wavegen::{Dds, PeriodicFunction, Waveform};
+//!
+//! let dds = Dds::new(32, 12, 14).with_dither(1234);
+//! let wf = Waveform::<f64, f64>::with_components(100e6, vec![PeriodicFunction::dds(10.7e6, 1.0, 0.0, dds)]);
+//! ```
+//!
+//! # Static dispatch
+//!
+//! Every [`PeriodicFunction`] is boxed, so sampling a [`Waveform`] costs an indirect call per component. When the
+//! components are known at compile time, a [`StaticWaveform`] over a tuple or an array of [`Component`]s, like
+//! [`Sine`], [`Square`], [`Sawtooth`] and [`DcBias`], lets the compiler inline all of them:
+//!
+//! ```
+//! use wavegen::{DcBias, Sine, StaticWaveform};
+//!
+//! let wf = StaticWaveform::<f32, _>::new(44100., (Sine::new(440., 1., 0.), Sine::new(880., 0.5, 0.), DcBias::new(0.1)));
+//! let samples: Vec<f32> = wf.iter().take(100).collect();
+//! ```
+//!
+//! [`PeriodicFunction`] implements [`Component`] as well, so boxed functions can be mixed in where needed.
+//!
+//! [`StaticWaveform`] does not allocate. With the `alloc` feature disabled, it is the only waveform available, along
+//! with the [`Oscillator`] enum for arrays of mixed components, and the crate needs no global allocator at all.
+//!
+//! # Fourier series
+//!
+//! Timbres known by their harmonics can be built with [`PeriodicFunction::fourier`], from the harmonic number,
+//! amplitude and phase of every term, or with [`PeriodicFunction::fourier_coefficients`], from the cosine and sine
+//! coefficients. The harmonics above the Nyquist frequency of the [`Waveform`] are dropped:
+//!
+//! ```
+//! use wavegen::{PeriodicFunction, Waveform};
+//!
+//! let mains = PeriodicFunction::<f32>::fourier(50., &[(1, 1.0, 0.0), (3, 0.05, 0.1), (5, 0.03, 0.4)]);
+//! let wf = Waveform::<f32>::with_components(1000., vec![mains]);
+//! ```
+//!
+//! # Wavetables
+//!
+//! Any [`PeriodicFunction`] with a known frequency can be replaced with a table of its values over one period, which
+//! is cheaper to sample than the exact sine, especially under `libm`. The error of the lookup depends on the table
+//! size and the [`Interpolation`] between its values:
+//!
+//! ```
+//! use wavegen::{sine, Interpolation, Waveform};
+//!
+//! let wf = Waveform::<f32>::with_components(44100., vec![sine!(440.).tabulated(1024, Interpolation::Linear)]);
+//! ```
+//!
+//! Arbitrary single-cycle waveforms can be played with [`PeriodicFunction::wavetable`], from tables rendered by
+//! [`Waveform::period_table`], or read from WAV files with `read_wav_table` when the `hound` feature is enabled.
+//!
 //! # Overflows
 //!
 //! As [`Waveform`] can be composed of multiple components, it is possible for it to overflow during samples collection.
@@ -79,6 +229,36 @@
 //! let double_precision_waveform = wavegen::Waveform::<f64, f64>::new(1e100);
 //! ```
 //!
+//! [`WaveformIterator`] keeps track of an integer sample counter, and the time of each sample is derived from it,
+//! instead of being accumulated. Before being passed to the built-in periodic functions, the time is additionally
+//! reduced modulo the function's period, so the phase does not drift even after billions of samples at single precision:
+//!
+//! ```
+//! use wavegen::{Waveform, sine};
+//!
+//! let wf = Waveform::<f32>::with_components(44100.0, vec![sine!(1000.)]);
+//! let sample = wf.iter().nth(1_000_000_000).unwrap();
+//! let expected = (2.0 * core::f64::consts::PI * (1e9 * 1000.0 / 44100.0f64).fract()).sin();
+//!
+//! assert!((f64::from(sample) - expected).abs() < 1e-3);
+//! ```
+//!
+//! # Fixed-point precision
+//!
+//! On targets without a floating point unit, [`FixedWaveform`] calculates the samples in [`Q15`] or [`Q31`]
+//! fixed-point precision, with integer phase accumulators and a table-based sine, so no floating point code runs at
+//! all:
+//!
+//! ```
+//! use wavegen::{FixedFrequency, FixedSine, FixedSquare, FixedWaveform, Q15};
+//!
+//! let sine = FixedSine::new(FixedFrequency::from_hz(440), Q15(16384), 0);
+//! // 0.5 Hz, as frequencies are given in Q16.16 format
+//! let square = FixedSquare::new(FixedFrequency::from_bits(1 << 15), Q15(1000), 0);
+//! let wf = FixedWaveform::<i16, _, Q15>::new(8000, (sine, square));
+//! let samples: Vec<i16> = wf.iter().take(100).collect();
+//! ```
+//!
 //! # Iterator infinity
 //!
 //! [`WaveformIterator`] is a *mostly* infinite iterator, with one exception:
@@ -121,6 +301,30 @@
 //! ```
 //!
 //! As it is often a case, it is you, the programmer, who's left in charge of making sure the input data makes sense.
+//!
+//! If you'd rather have it checked, use the fallible constructors, which reject components with frequency above half
+//! of the sample rate:
+//!
+//! ```
+//! use wavegen::{Error, Waveform, sine};
+//!
+//! let wf = Waveform::<f32>::try_with_components(100.0, vec![sine!(80.)]);
+//!
+//! assert_eq!(Some(Error::NyquistViolation { component: 0 }), wf.err());
+//! ```
+//!
+//! The fallible constructors only look at the fundamental frequencies. To also check the harmonics of square, sawtooth
+//! and other non-sinusoidal functions, use [`Waveform::validate`], which returns an [`AliasingReport`]:
+//!
+//! ```
+//! use wavegen::{Waveform, sawtooth};
+//!
+//! let wf = Waveform::<f32>::with_components(44100.0, vec![sawtooth!(5000.)]);
+//! let report = wf.validate(0.05);
+//!
+//! assert!(!report.is_ok());
+//! assert_eq!(5, report.aliases[0].harmonic);
+//! ```
 
 #![no_std]
 #![deny(missing_docs)]
@@ -133,12 +337,93 @@ extern crate std;
 #[cfg(all(not(feature = "libm"), not(feature = "std")))]
 compile_error!("at least one of \"libm\", \"std\" features has to be enabled");
 
+#[cfg(feature = "alloc")]
 extern crate alloc;
 
+#[cfg(feature = "alloc")]
+mod band_limited;
+#[cfg(feature = "alloc")]
+mod chirp;
+mod component;
+#[cfg(feature = "alloc")]
+mod dds;
+#[cfg(feature = "alloc")]
+mod duration;
+#[cfg(feature = "alloc")]
+mod envelope;
+mod error;
+mod fixed;
+#[cfg(feature = "alloc")]
+mod fourier;
+#[cfg(feature = "alloc")]
+mod kind;
+#[cfg(feature = "alloc")]
 mod macros;
-
+#[cfg(feature = "alloc")]
+mod modulation;
+#[cfg(feature = "alloc")]
+mod noise;
+#[cfg(feature = "alloc")]
+mod nyquist;
+#[cfg(feature = "alloc")]
+mod ops;
+#[cfg(feature = "alloc")]
+mod parse;
+#[cfg(feature = "alloc")]
+mod sequence;
+#[cfg(feature = "serde")]
+mod serialize;
+#[cfg(feature = "alloc")]
+mod stateful;
+#[cfg(feature = "alloc")]
+mod wavetable;
+
+#[cfg(feature = "alloc")]
+pub use band_limited::BandLimit;
+#[cfg(feature = "alloc")]
+pub use chirp::{AfterSweep, Sweep};
+pub use component::{
+    Component, DcBias, Oscillator, Sawtooth, Sine, Square, StaticWaveform, StaticWaveformIterator,
+};
+#[cfg(feature = "alloc")]
+pub use dds::Dds;
+#[cfg(feature = "alloc")]
+pub use duration::{Duration, FiniteWaveformIterator};
+#[cfg(feature = "alloc")]
+pub use envelope::Trigger;
+pub use error::Error;
+pub use fixed::{
+    Fixed, FixedComponent, FixedDcBias, FixedFrequency, FixedSawtooth, FixedSine, FixedSquare,
+    FixedWaveform, FixedWaveformIterator, Q15, Q31,
+};
+#[cfg(feature = "alloc")]
+pub use kind::{Kind, NoiseColor};
+#[cfg(feature = "alloc")]
+pub use nyquist::{Alias, AliasingReport};
+#[cfg(feature = "alloc")]
+pub use parse::{ParseError, ParseErrorKind};
+#[cfg(feature = "alloc")]
+pub use sequence::{Placement, Sequence};
+#[cfg(feature = "serde")]
+pub use serialize::PrecisionTag;
+#[cfg(feature = "alloc")]
+pub use stateful::StatefulFunction;
+#[cfg(feature = "hound")]
+pub use wavetable::read_wav_table;
+#[cfg(feature = "alloc")]
+pub use wavetable::Interpolation;
+
+#[cfg(feature = "alloc")]
+use nyquist::{Shape, Spectrum};
+#[cfg(feature = "alloc")]
+use stateful::State;
+
+#[cfg(feature = "alloc")]
 use alloc::{boxed::Box, vec, vec::Vec};
+#[cfg(feature = "alloc")]
+use core::fmt;
 use core::iter::Sum;
+#[cfg(feature = "alloc")]
 use core::marker::PhantomData;
 use core::ops::Add;
 use num_traits::{Bounded, Float, FloatConst, NumCast, One};
@@ -168,12 +453,28 @@ pub trait SampleType: NumCast + Bounded {}
 impl<T> SampleType for T where T: NumCast + Bounded {}
 
 /// Struct representing a waveform, consisting of output numeric type, sampling rate and a vector of [`PeriodicFunction`]s.
+///
+/// A waveform can also contain [`StatefulFunction`]s, which are summed together with the periodic components.
+#[cfg(feature = "alloc")]
 pub struct Waveform<T: SampleType, P: Precision = f32> {
     sample_rate: P,
     components: Vec<PeriodicFunction<P>>,
+    stateful_components: Vec<StatefulFunction<P>>,
     _phantom: PhantomData<T>,
 }
 
+#[cfg(feature = "alloc")]
+impl<T: SampleType, P: Precision + fmt::Debug> fmt::Debug for Waveform<T, P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Waveform")
+            .field("sample_rate", &self.sample_rate)
+            .field("components", &self.components)
+            .field("stateful_components", &self.stateful_components)
+            .finish()
+    }
+}
+
+#[cfg(feature = "alloc")]
 impl<T: SampleType, P: Precision> Waveform<T, P> {
     /// Initializes new empty [`Waveform`]
     ///
@@ -197,6 +498,7 @@ impl<T: SampleType, P: Precision> Waveform<T, P> {
         Waveform {
             sample_rate,
             components: vec![],
+            stateful_components: vec![],
             _phantom: PhantomData,
         }
     }
@@ -223,11 +525,108 @@ impl<T: SampleType, P: Precision> Waveform<T, P> {
 
         Waveform {
             sample_rate,
-            components,
+            components: components
+                .into_iter()
+                .map(|x| x.bound(sample_rate))
+                .collect(),
+            stateful_components: vec![],
             _phantom: PhantomData,
         }
     }
 
+    /// Fallible version of [`Waveform::new`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidSampleRate`] if `sample_rate` is not a finite, positive, non-zero number.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wavegen::{Error, Waveform};
+    ///
+    /// assert!(Waveform::<f32>::try_new(44100.0).is_ok());
+    /// assert_eq!(Some(Error::InvalidSampleRate), Waveform::<f32>::try_new(f32::NAN).err());
+    /// ```
+    pub fn try_new(sample_rate: impl Into<P>) -> Result<Self, Error> {
+        Self::try_with_components(sample_rate, vec![])
+    }
+
+    /// Fallible version of [`Waveform::with_components`].
+    ///
+    /// Unlike its panicking counterpart, it also enforces the Nyquist-Shannon rule on the components of known frequency.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidSampleRate`] if `sample_rate` is not a finite, positive, non-zero number, or
+    /// [`Error::NyquistViolation`] if frequency of any of the components exceeds half of the `sample_rate`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wavegen::{Error, Waveform, sine};
+    ///
+    /// assert!(Waveform::<f32>::try_with_components(100.0, vec![sine!(10.)]).is_ok());
+    /// assert_eq!(
+    ///     Some(Error::NyquistViolation { component: 1 }),
+    ///     Waveform::<f32>::try_with_components(100.0, vec![sine!(10.), sine!(80.)]).err()
+    /// );
+    /// ```
+    pub fn try_with_components(
+        sample_rate: impl Into<P>,
+        components: Vec<PeriodicFunction<P>>,
+    ) -> Result<Self, Error> {
+        let sample_rate = sample_rate.into();
+        if !Self::is_sane(sample_rate) {
+            return Err(Error::InvalidSampleRate);
+        }
+
+        let mut wf = Self::new(sample_rate);
+        for component in components {
+            wf.try_add_component(component)?;
+        }
+
+        Ok(wf)
+    }
+
+    /// Fallible version of [`Waveform::add_component`], enforcing the Nyquist-Shannon rule on the added component.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::NyquistViolation`] if frequency of the component, or the highest frequency a chirp sweeps
+    /// through, exceeds half of the sample rate.
+    /// The component is not added in that case.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wavegen::{Waveform, sine};
+    ///
+    /// let mut wf = Waveform::<f32>::new(100.0);
+    ///
+    /// assert!(wf.try_add_component(sine!(50.)).is_ok());
+    /// assert!(wf.try_add_component(sine!(51.)).is_err());
+    /// assert_eq!(1, wf.components().len());
+    /// ```
+    pub fn try_add_component(&mut self, component: PeriodicFunction<P>) -> Result<(), Error> {
+        let nyquist = self.sample_rate / P::two();
+        let exceeds = |f: P| f.abs() > nyquist;
+        if component.frequency.map_or(false, exceeds)
+            || component
+                .spectrum
+                .as_ref()
+                .map_or(false, |s| exceeds(s.fundamental))
+        {
+            return Err(Error::NyquistViolation {
+                component: self.components.len(),
+            });
+        }
+
+        self.add_component(component);
+
+        Ok(())
+    }
+
     /// Ads a new component to existing [`Waveform`].
     ///
     /// # Examples
@@ -242,7 +641,30 @@ impl<T: SampleType, P: Precision> Waveform<T, P> {
     /// assert_eq!(2, wf.components().len());
     /// ```
     pub fn add_component(&mut self, component: PeriodicFunction<P>) {
-        self.components.push(component);
+        self.components.push(component.bound(self.sample_rate));
+    }
+
+    /// Adds a new stateful component to existing [`Waveform`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wavegen::{Waveform, StatefulFunction};
+    ///
+    /// let mut wf = Waveform::<f32>::new(100.0);
+    /// wf.add_stateful_component(StatefulFunction::new(|_| {
+    ///     let mut n = 0.0;
+    ///     move |_| {
+    ///         n += 1.0;
+    ///         n
+    ///     }
+    /// }));
+    ///
+    /// assert_eq!(vec![1.0, 2.0, 3.0], wf.iter().take(3).collect::<Vec<_>>());
+    /// assert_eq!(1, wf.stateful_components().len());
+    /// ```
+    pub fn add_stateful_component(&mut self, component: StatefulFunction<P>) {
+        self.stateful_components.push(component);
     }
 
     /// Gets sample rate of this [`Waveform`].
@@ -275,8 +697,15 @@ impl<T: SampleType, P: Precision> Waveform<T, P> {
         &self.components
     }
 
+    /// Returns list of stateful components this [`Waveform`] consists of.
+    pub fn stateful_components(&self) -> &Vec<StatefulFunction<P>> {
+        &self.stateful_components
+    }
+
     /// Returns an iterator over this [`Waveform`] samples.
     ///
+    /// Each iterator holds its own state of the [`StatefulFunction`] components, initialized at its creation.
+    ///
     /// # Examples
     ///
     /// ```
@@ -285,10 +714,15 @@ impl<T: SampleType, P: Precision> Waveform<T, P> {
     /// let wf = Waveform::<f32>::with_components(42.0, vec![sine!(1.)]);
     /// let samples = wf.iter().take(100).collect::<Vec<_>>();
     /// ```
-    pub fn iter(&self) -> WaveformIterator<T, P> {
+    pub fn iter(&self) -> WaveformIterator<'_, T, P> {
         WaveformIterator::<T, P> {
             inner: self,
-            time: P::zero(),
+            index: 0,
+            states: self
+                .stateful_components
+                .iter()
+                .map(|x| x.init(self.sample_rate))
+                .collect(),
         }
     }
 
@@ -297,75 +731,218 @@ impl<T: SampleType, P: Precision> Waveform<T, P> {
         assert!(x.is_normal());
         assert!(x.is_sign_positive());
     }
+
+    #[inline]
+    fn is_sane(x: P) -> bool {
+        x.is_normal() && x.is_sign_positive()
+    }
 }
 
+#[cfg(feature = "alloc")]
 impl<'a, T: SampleType, P: Precision> IntoIterator for &'a Waveform<T, P> {
     type Item = T;
 
     type IntoIter = WaveformIterator<'a, T, P>;
 
     fn into_iter(self) -> Self::IntoIter {
-        WaveformIterator {
-            inner: self,
-            time: P::zero(),
-        }
+        self.iter()
     }
 }
 
 /// An iterator that allows to sample a [`Waveform`].
-#[derive(Clone, Copy)]
+///
+/// Cloning the iterator also clones the state of its [`StatefulFunction`] components, so the clone continues independently
+/// from the same point.
+#[derive(Clone)]
+#[cfg(feature = "alloc")]
 pub struct WaveformIterator<'a, T: SampleType, P: Precision> {
     inner: &'a Waveform<T, P>,
-    time: P,
+    index: u64,
+    states: Vec<Box<dyn State<P>>>,
 }
 
-impl<'a, T: SampleType, P: Precision> WaveformIterator<'a, T, P> {
-    fn into_target_type_sanitized(sample: P) -> Option<T> {
-        let result = NumCast::from(sample);
-
-        result.or_else(|| {
-            if sample > P::zero() {
-                Some(T::max_value())
-            } else if sample < P::zero() {
-                Some(T::min_value())
-            } else {
-                None
-            }
-        })
-    }
+/// Converts `sample` into the sample type, saturating on overflow. Returns [`None`] if the sample is NaN and the sample
+/// type cannot represent it.
+fn into_target_type_sanitized<T: SampleType, P: Precision>(sample: P) -> Option<T> {
+    let result = NumCast::from(sample);
 
-    fn increment_time(&mut self, n: usize) -> Result<(), ()> {
-        let new_time = self.time + (P::from(n).ok_or(())? * (P::one() / self.inner.sample_rate));
-        if new_time.is_finite() {
-            self.time = new_time;
+    result.or_else(|| {
+        if sample > P::zero() {
+            Some(T::max_value())
+        } else if sample < P::zero() {
+            Some(T::min_value())
         } else {
-            self.time = (P::one() / self.inner.sample_rate) - (P::max_value() - self.time);
+            None
         }
+    })
+}
 
-        Ok(())
+/// Number of samples in one period of `frequency`, if any, at given `sample_rate`.
+#[inline]
+fn samples_per_period<P: Precision>(sample_rate: f64, frequency: Option<P>) -> Option<f64> {
+    frequency
+        .and_then(|f| f.to_f64())
+        .map(|frequency| sample_rate / frequency)
+}
+
+/// Time of the sample at `index`, reduced modulo period of `samples_per_period`, if any.
+///
+/// The reduction is done in double precision on the sample index, so that the time passed to the periodic functions
+/// stays small and exact, no matter how many samples were taken before.
+#[inline]
+#[allow(clippy::cast_precision_loss)]
+fn reduced_time<P: Precision>(index: u64, sample_rate: f64, samples_per_period: Option<f64>) -> P {
+    let index = index as f64;
+    let samples = match samples_per_period {
+        Some(samples_per_period) => {
+            index - Float::floor(index / samples_per_period) * samples_per_period
+        }
+        None => index,
+    };
+
+    P::from(samples / sample_rate).unwrap_or_else(P::nan)
+}
+
+#[cfg(feature = "alloc")]
+impl<T: SampleType, P: Precision> WaveformIterator<'_, T, P> {
+    /// Time of the current sample, reduced modulo period of the given `frequency`, if any.
+    fn time(&self, frequency: Option<P>) -> P {
+        let mut time = [P::zero()];
+        self.times(frequency, &mut time);
+
+        time[0]
     }
 
-    fn raw_sample(&self) -> P {
-        self.inner
+    /// Times of the consecutive samples starting with the current one, reduced modulo period of the given
+    /// `frequency`, if any.
+    fn times(&self, frequency: Option<P>, times: &mut [P]) {
+        let sample_rate = self.inner.sample_rate.to_f64().unwrap_or(f64::NAN);
+        let samples_per_period = samples_per_period(sample_rate, frequency);
+
+        for (i, time) in times.iter_mut().enumerate() {
+            let index = self.index.wrapping_add(i as u64);
+
+            *time = reduced_time(index, sample_rate, samples_per_period);
+        }
+    }
+
+    fn raw_sample(&mut self) -> P {
+        let stateless: P = self
+            .inner
             .components
             .iter()
-            .map(|x| x.sample(self.time))
-            .sum()
+            .map(|x| match &x.clock {
+                Some(clock) => clock(self.index),
+                None => x.sample(self.time(x.frequency)),
+            })
+            .sum();
+        let time = self.time(None);
+        let stateful: P = self.states.iter_mut().map(|x| x(time)).sum();
+
+        stateless + stateful
     }
 }
 
-impl<'a, T: SampleType, P: Precision> Iterator for WaveformIterator<'a, T, P> {
+#[cfg(feature = "alloc")]
+impl<T: SampleType, P: Precision> WaveformIterator<'_, T, P> {
+    /// Fills `buffer` with the next samples, advancing the iterator by its length.
+    ///
+    /// Unlike calling [`Iterator::next`] for every sample, this evaluates the stateless components a block of samples at
+    /// a time, working out the time of each block of samples once per component. The built-in sine, square, sawtooth
+    /// and DC bias functions are then sampled by [`Component::add_samples`] in a plain loop over the block, which the
+    /// compiler can inline and vectorise for `f32` and `f64`, rather than by a boxed call per sample. The samples are the
+    /// same as the ones yielded by the iterator.
+    ///
+    /// Returns the number of samples written, which is less than the length of `buffer` only if a sample turned out to
+    /// be NaN, and the sample type cannot represent it. The rest of `buffer` is left untouched in that case, and the
+    /// iterator is advanced just past the NaN sample, as if it was yielded by [`Iterator::next`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wavegen::{Waveform, sine};
+    ///
+    /// let wf = Waveform::<f32>::with_components(44100.0, vec![sine!(440.)]);
+    /// let mut iter = wf.iter();
+    /// let mut buffer = [0.0; 512];
+    ///
+    /// // e.g. in an audio callback
+    /// assert_eq!(512, iter.fill(&mut buffer));
+    /// assert_eq!(wf.iter().take(512).collect::<Vec<_>>(), buffer);
+    /// ```
+    pub fn fill(&mut self, buffer: &mut [T]) -> usize {
+        let mut written = 0;
+
+        for chunk in buffer.chunks_mut(BLOCK) {
+            let len = chunk.len();
+            let mut times = [P::zero(); BLOCK];
+            let mut stateless = [P::zero(); BLOCK];
+            let times = &mut times[..len];
+            let stateless = &mut stateless[..len];
+
+            for component in &self.inner.components {
+                if let Some(clock) = &component.clock {
+                    for (i, y) in stateless.iter_mut().enumerate() {
+                        *y = *y + clock(self.index.wrapping_add(i as u64));
+                    }
+                    continue;
+                }
+
+                self.times(component.frequency, times);
+                match &component.oscillator {
+                    Some(oscillator) => oscillator.add_samples(times, stateless),
+                    None => {
+                        for (y, &t) in stateless.iter_mut().zip(times.iter()) {
+                            *y = *y + component.sample(t);
+                        }
+                    }
+                }
+            }
+
+            // stateful components are evaluated sample by sample, so that they stop right after a NaN one
+            self.times(None, times);
+            for (y, (&a, &t)) in chunk.iter_mut().zip(stateless.iter().zip(times.iter())) {
+                let b: P = self.states.iter_mut().map(|state| state(t)).sum();
+                self.index = self.index.wrapping_add(1);
+
+                match into_target_type_sanitized(a + b) {
+                    Some(sample) => {
+                        *y = sample;
+                        written += 1;
+                    }
+                    None => return written,
+                }
+            }
+        }
+
+        written
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: SampleType, P: Precision> Iterator for WaveformIterator<'_, T, P> {
     type Item = T;
 
     fn next(&mut self) -> Option<Self::Item> {
         let sample = self.raw_sample();
-        self.increment_time(1).ok()?;
+        self.index = self.index.wrapping_add(1);
 
-        Self::into_target_type_sanitized(sample)
+        into_target_type_sanitized(sample)
     }
 
     fn nth(&mut self, n: usize) -> Option<Self::Item> {
-        self.increment_time(n).ok()?;
+        if self.states.is_empty() {
+            self.index = self.index.wrapping_add(n as u64);
+        } else {
+            // stateful components have to see every skipped sample
+            for _ in 0..n {
+                let time = self.time(None);
+                self.states.iter_mut().for_each(|x| {
+                    x(time);
+                });
+                self.index = self.index.wrapping_add(1);
+            }
+        }
 
         self.next()
     }
@@ -375,11 +952,53 @@ impl<'a, T: SampleType, P: Precision> Iterator for WaveformIterator<'a, T, P> {
     }
 }
 
+/// Number of samples [`WaveformIterator::fill`] evaluates at a time.
+#[cfg(feature = "alloc")]
+const BLOCK: usize = 64;
+
+#[inline]
+#[cfg(feature = "alloc")]
+fn finite<P: Precision>(x: P, error: Error) -> Result<P, Error> {
+    if x.is_finite() {
+        Ok(x)
+    } else {
+        Err(error)
+    }
+}
+
+#[inline]
+#[cfg(feature = "alloc")]
+fn fraction<P: Precision>(x: P, error: Error) -> Result<P, Error> {
+    if x >= P::zero() && x <= P::one() {
+        Ok(x)
+    } else {
+        Err(error)
+    }
+}
+
 /// Wrapper struct for a periodic function (in most cases a `f32 -> f32` or `f64 -> f64` map).
+#[cfg(feature = "alloc")]
 pub struct PeriodicFunction<P: Precision = f32> {
     inner: Box<dyn Fn(P) -> P + Send + Sync>,
+    frequency: Option<P>,
+    spectrum: Option<Spectrum<P>>,
+    bind: Option<Box<Bind<P>>>,
+    clock: Option<Box<Clock<P>>>,
+    bind_clock: Option<Box<BindClock<P>>>,
+    oscillator: Option<Oscillator<P>>,
+    kind: Kind<P>,
 }
 
+#[cfg(feature = "alloc")]
+type Bind<P> = dyn Fn(P) -> Box<dyn Fn(P) -> P + Send + Sync> + Send + Sync;
+
+#[cfg(feature = "alloc")]
+type Clock<P> = dyn Fn(u64) -> P + Send + Sync;
+
+#[cfg(feature = "alloc")]
+type BindClock<P> = dyn Fn(P) -> Box<Clock<P>> + Send + Sync;
+
+#[cfg(feature = "alloc")]
 impl<P: Precision + 'static> PeriodicFunction<P> {
     /// Initializes new [`PeriodicFunction`] with function defined by `f` parameter.
     ///
@@ -390,7 +1009,97 @@ impl<P: Precision + 'static> PeriodicFunction<P> {
     /// ```
     #[must_use]
     pub fn new(f: Box<dyn Fn(P) -> P + Send + Sync>) -> Self {
-        Self { inner: f }
+        Self {
+            inner: f,
+            frequency: None,
+            spectrum: None,
+            bind: None,
+            clock: None,
+            bind_clock: None,
+            oscillator: None,
+            kind: Kind::Custom,
+        }
+    }
+
+    /// Records the kind and parameters the function was built with.
+    #[inline]
+    fn with_kind(mut self, kind: Kind<P>) -> Self {
+        self.kind = kind;
+
+        self
+    }
+
+    /// Makes the function depend on the sample rate of the [`Waveform`] it is added to.
+    ///
+    /// Until then, the function behaves as it was built. When added to a [`Waveform`], the inner function is replaced
+    /// by the one returned by `bind` for the [`Waveform`]'s sample rate.
+    #[inline]
+    fn with_bind<B, F>(mut self, bind: B) -> Self
+    where
+        B: Fn(P) -> F + Send + Sync + 'static,
+        F: Fn(P) -> P + Send + Sync + 'static,
+    {
+        self.bind = Some(Box::new(move |sample_rate| Box::new(bind(sample_rate))));
+        self.oscillator = None;
+
+        self
+    }
+
+    /// Makes the function clocked by the sample index, once added to a [`Waveform`].
+    ///
+    /// When added to a [`Waveform`], the function is sampled by the function of the sample index returned by `bind` for
+    /// the [`Waveform`]'s sample rate, rather than by the sample time. Until then, and inside the functions derived from
+    /// it, the function is sampled by time as usual.
+    #[inline]
+    fn with_clock<B, F>(mut self, bind: B) -> Self
+    where
+        B: Fn(P) -> F + Send + Sync + 'static,
+        F: Fn(u64) -> P + Send + Sync + 'static,
+    {
+        self.bind_clock = Some(Box::new(move |sample_rate| Box::new(bind(sample_rate))));
+        self.oscillator = None;
+
+        self
+    }
+
+    /// Lets [`WaveformIterator::fill`] sample the function a block at a time, by the statically dispatched
+    /// `oscillator` it wraps.
+    #[inline]
+    fn with_oscillator(mut self, oscillator: impl Into<Oscillator<P>>) -> Self {
+        self.oscillator = Some(oscillator.into());
+
+        self
+    }
+
+    #[inline]
+    fn bound(mut self, sample_rate: P) -> Self {
+        if let Some(bind) = &self.bind {
+            self.inner = bind(sample_rate);
+        }
+        if let Some(bind) = &self.bind_clock {
+            self.clock = Some(bind(sample_rate));
+        }
+
+        self
+    }
+
+    /// Marks the function as periodic with given frequency and spectrum shape.
+    ///
+    /// This lets [`WaveformIterator`] reduce the time modulo period, and [`Waveform::validate`] check the harmonics.
+    #[inline]
+    fn periodic(mut self, frequency: P, shape: Shape<P>) -> Self {
+        let frequency = frequency.abs();
+        self.frequency = if frequency.is_normal() {
+            Some(frequency)
+        } else {
+            None
+        };
+        self.spectrum = Some(Spectrum {
+            fundamental: frequency,
+            shape,
+        });
+
+        self
     }
 
     /// Helper for defining custom functions. Same as `PeriodicFunction::new` but with implicit Boxing.
@@ -412,7 +1121,11 @@ impl<P: Precision + 'static> PeriodicFunction<P> {
     pub fn dc_bias(bias: impl Into<P>) -> Self {
         let bias = bias.into();
 
-        Self::new(Box::new(move |_| bias))
+        let dc_bias = DcBias::new(bias);
+
+        Self::new(Box::new(move |t| dc_bias.sample(t)))
+            .with_oscillator(dc_bias)
+            .with_kind(Kind::DcBias { bias })
     }
 
     /// Sawtooth function builder. See the [`macro`] for more info.
@@ -424,9 +1137,16 @@ impl<P: Precision + 'static> PeriodicFunction<P> {
         let amplitude = amplitude.into();
         let phase = phase.into();
 
-        Self::new(Box::new(move |t| {
-            P::two() * amplitude * (t * frequency + phase).fract() - amplitude
-        }))
+        let sawtooth = Sawtooth::new(frequency, amplitude, phase);
+
+        Self::new(Box::new(move |t| sawtooth.sample(t)))
+            .with_oscillator(sawtooth)
+            .periodic(frequency, Shape::Sawtooth)
+            .with_kind(Kind::Sawtooth {
+                frequency,
+                amplitude,
+                phase,
+            })
     }
 
     /// Sine function builder. See the [`macro`] for more info.
@@ -438,12 +1158,16 @@ impl<P: Precision + 'static> PeriodicFunction<P> {
         let amplitude = amplitude.into();
         let phase = phase.into();
 
-        Self::new(Box::new(move |t| {
-            let radians = (P::two() * P::PI() * frequency * t) + (phase * P::two() * P::PI());
-            let sine = radians.sin();
+        let sine = Sine::new(frequency, amplitude, phase);
 
-            sine * amplitude
-        }))
+        Self::new(Box::new(move |t| sine.sample(t)))
+            .with_oscillator(sine)
+            .periodic(frequency, Shape::Tone)
+            .with_kind(Kind::Sine {
+                frequency,
+                amplitude,
+                phase,
+            })
     }
 
     /// Square function builder. See the [`macro`] for more info.
@@ -455,11 +1179,316 @@ impl<P: Precision + 'static> PeriodicFunction<P> {
         let amplitude = amplitude.into();
         let phase = phase.into();
 
+        let square = Square::new(frequency, amplitude, phase);
+
+        Self::new(Box::new(move |t| square.sample(t)))
+            .with_oscillator(square)
+            .periodic(frequency, Shape::Square)
+            .with_kind(Kind::Square {
+                frequency,
+                amplitude,
+                phase,
+            })
+    }
+
+    /// Bipolar pulse function builder. See the [`macro`] for more info.
+    ///
+    /// The function swings between `amplitude` and `-amplitude`, staying high for `duty` fraction of the period.
+    /// `duty` is clamped to `[0, 1]` range. Duty of `0.5` gives a square wave.
+    ///
+    /// [`macro`]: ../macro.pulse.html
+    #[inline]
+    pub fn pulse(
+        frequency: impl Into<P>,
+        amplitude: impl Into<P>,
+        phase: impl Into<P>,
+        duty: impl Into<P>,
+    ) -> Self {
+        let frequency = frequency.into();
+        let amplitude = amplitude.into();
+        let phase = phase.into();
+        let duty = duty.into().max(P::zero()).min(P::one());
+        let high = Self::pulse_gate(frequency, phase, duty);
+
+        Self::new(Box::new(
+            move |t| {
+                if high(t) {
+                    amplitude
+                } else {
+                    amplitude.neg()
+                }
+            },
+        ))
+        .periodic(frequency, Shape::Pulse(duty))
+        .with_kind(Kind::Pulse {
+            frequency,
+            amplitude,
+            phase,
+            duty,
+        })
+    }
+
+    /// Unipolar pulse function builder. See the [`macro`] for more info.
+    ///
+    /// The function swings between `amplitude` and `0`, staying high for `duty` fraction of the period.
+    /// `duty` is clamped to `[0, 1]` range.
+    ///
+    /// [`macro`]: ../macro.unipolar_pulse.html
+    #[inline]
+    pub fn unipolar_pulse(
+        frequency: impl Into<P>,
+        amplitude: impl Into<P>,
+        phase: impl Into<P>,
+        duty: impl Into<P>,
+    ) -> Self {
+        let frequency = frequency.into();
+        let amplitude = amplitude.into();
+        let phase = phase.into();
+        let duty = duty.into().max(P::zero()).min(P::one());
+        let high = Self::pulse_gate(frequency, phase, duty);
+
+        Self::new(Box::new(
+            move |t| if high(t) { amplitude } else { P::zero() },
+        ))
+        .periodic(frequency, Shape::UnipolarPulse(duty))
+        .with_kind(Kind::UnipolarPulse {
+            frequency,
+            amplitude,
+            phase,
+            duty,
+        })
+    }
+
+    #[inline]
+    fn pulse_gate(frequency: P, phase: P, duty: P) -> impl Fn(P) -> bool {
+        move |t| {
+            let x = (t * frequency + phase).fract();
+            let x = if x < P::zero() { x + P::one() } else { x };
+
+            x < duty
+        }
+    }
+
+    /// Triangle function builder. See the [`macro`] for more info.
+    ///
+    /// This is a special case of [`ramp`](Self::ramp) with symmetry of `0.5`.
+    ///
+    /// [`macro`]: ../macro.triangle.html
+    #[inline]
+    pub fn triangle(frequency: impl Into<P>, amplitude: impl Into<P>, phase: impl Into<P>) -> Self {
+        let frequency = frequency.into();
+        let amplitude = amplitude.into();
+        let phase = phase.into();
+        let half = P::one() / P::two();
+
+        Self::ramp(frequency, amplitude, phase, half).with_kind(Kind::Triangle {
+            frequency,
+            amplitude,
+            phase,
+        })
+    }
+
+    /// Variable-symmetry ramp function builder. See the [`macro`] for more info.
+    ///
+    /// `symmetry` is the fraction of the period spent rising, and is clamped to `[0, 1]` range.
+    /// Value of `0` gives a falling sawtooth, `0.5` a triangle and `1` a rising sawtooth.
+    ///
+    /// [`macro`]: ../macro.ramp.html
+    #[inline]
+    pub fn ramp(
+        frequency: impl Into<P>,
+        amplitude: impl Into<P>,
+        phase: impl Into<P>,
+        symmetry: impl Into<P>,
+    ) -> Self {
+        let frequency = frequency.into();
+        let amplitude = amplitude.into();
+        let phase = phase.into();
+        let symmetry = symmetry.into().max(P::zero()).min(P::one());
+
         Self::new(Box::new(move |t| {
-            let power = (P::two() * (t - phase) * frequency).floor();
+            let x = (t * frequency + phase).fract();
+            let x = if x < P::zero() { x + P::one() } else { x };
 
-            amplitude * (P::one().neg()).powf(power)
+            if x < symmetry {
+                P::two() * amplitude * x / symmetry - amplitude
+            } else {
+                amplitude - P::two() * amplitude * (x - symmetry) / (P::one() - symmetry)
+            }
         }))
+        .periodic(frequency, Shape::Ramp(symmetry))
+        .with_kind(Kind::Ramp {
+            frequency,
+            amplitude,
+            phase,
+            symmetry,
+        })
+    }
+
+    /// Fallible version of [`PeriodicFunction::dc_bias`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidAmplitude`] if `bias` is not a finite number.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wavegen::{Error, PeriodicFunction};
+    ///
+    /// assert!(PeriodicFunction::<f32>::try_dc_bias(1.0).is_ok());
+    /// assert_eq!(Some(Error::InvalidAmplitude), PeriodicFunction::<f32>::try_dc_bias(f32::INFINITY).err());
+    /// ```
+    #[inline]
+    pub fn try_dc_bias(bias: impl Into<P>) -> Result<Self, Error> {
+        let bias = finite(bias.into(), Error::InvalidAmplitude)?;
+
+        Ok(Self::dc_bias(bias))
+    }
+
+    /// Fallible version of [`PeriodicFunction::sawtooth`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error`] if any of the parameters is not a finite number.
+    #[inline]
+    pub fn try_sawtooth(
+        frequency: impl Into<P>,
+        amplitude: impl Into<P>,
+        phase: impl Into<P>,
+    ) -> Result<Self, Error> {
+        let frequency = finite(frequency.into(), Error::InvalidFrequency)?;
+        let amplitude = finite(amplitude.into(), Error::InvalidAmplitude)?;
+        let phase = finite(phase.into(), Error::InvalidPhase)?;
+
+        Ok(Self::sawtooth(frequency, amplitude, phase))
+    }
+
+    /// Fallible version of [`PeriodicFunction::sine`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error`] if any of the parameters is not a finite number.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wavegen::{Error, PeriodicFunction};
+    ///
+    /// assert!(PeriodicFunction::<f32>::try_sine(50.0, 1.0, 0.0).is_ok());
+    /// assert_eq!(Some(Error::InvalidFrequency), PeriodicFunction::<f32>::try_sine(f32::NAN, 1.0, 0.0).err());
+    /// assert_eq!(Some(Error::InvalidAmplitude), PeriodicFunction::<f32>::try_sine(50.0, f32::INFINITY, 0.0).err());
+    /// ```
+    #[inline]
+    pub fn try_sine(
+        frequency: impl Into<P>,
+        amplitude: impl Into<P>,
+        phase: impl Into<P>,
+    ) -> Result<Self, Error> {
+        let frequency = finite(frequency.into(), Error::InvalidFrequency)?;
+        let amplitude = finite(amplitude.into(), Error::InvalidAmplitude)?;
+        let phase = finite(phase.into(), Error::InvalidPhase)?;
+
+        Ok(Self::sine(frequency, amplitude, phase))
+    }
+
+    /// Fallible version of [`PeriodicFunction::square`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error`] if any of the parameters is not a finite number.
+    #[inline]
+    pub fn try_square(
+        frequency: impl Into<P>,
+        amplitude: impl Into<P>,
+        phase: impl Into<P>,
+    ) -> Result<Self, Error> {
+        let frequency = finite(frequency.into(), Error::InvalidFrequency)?;
+        let amplitude = finite(amplitude.into(), Error::InvalidAmplitude)?;
+        let phase = finite(phase.into(), Error::InvalidPhase)?;
+
+        Ok(Self::square(frequency, amplitude, phase))
+    }
+
+    /// Fallible version of [`PeriodicFunction::pulse`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidDuty`] if `duty` is not within `[0, 1]` range, or another [`Error`] if any of the other
+    /// parameters is not a finite number.
+    #[inline]
+    pub fn try_pulse(
+        frequency: impl Into<P>,
+        amplitude: impl Into<P>,
+        phase: impl Into<P>,
+        duty: impl Into<P>,
+    ) -> Result<Self, Error> {
+        let frequency = finite(frequency.into(), Error::InvalidFrequency)?;
+        let amplitude = finite(amplitude.into(), Error::InvalidAmplitude)?;
+        let phase = finite(phase.into(), Error::InvalidPhase)?;
+        let duty = fraction(duty.into(), Error::InvalidDuty)?;
+
+        Ok(Self::pulse(frequency, amplitude, phase, duty))
+    }
+
+    /// Fallible version of [`PeriodicFunction::unipolar_pulse`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidDuty`] if `duty` is not within `[0, 1]` range, or another [`Error`] if any of the other
+    /// parameters is not a finite number.
+    #[inline]
+    pub fn try_unipolar_pulse(
+        frequency: impl Into<P>,
+        amplitude: impl Into<P>,
+        phase: impl Into<P>,
+        duty: impl Into<P>,
+    ) -> Result<Self, Error> {
+        let frequency = finite(frequency.into(), Error::InvalidFrequency)?;
+        let amplitude = finite(amplitude.into(), Error::InvalidAmplitude)?;
+        let phase = finite(phase.into(), Error::InvalidPhase)?;
+        let duty = fraction(duty.into(), Error::InvalidDuty)?;
+
+        Ok(Self::unipolar_pulse(frequency, amplitude, phase, duty))
+    }
+
+    /// Fallible version of [`PeriodicFunction::triangle`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error`] if any of the parameters is not a finite number.
+    #[inline]
+    pub fn try_triangle(
+        frequency: impl Into<P>,
+        amplitude: impl Into<P>,
+        phase: impl Into<P>,
+    ) -> Result<Self, Error> {
+        let frequency = finite(frequency.into(), Error::InvalidFrequency)?;
+        let amplitude = finite(amplitude.into(), Error::InvalidAmplitude)?;
+        let phase = finite(phase.into(), Error::InvalidPhase)?;
+
+        Ok(Self::triangle(frequency, amplitude, phase))
+    }
+
+    /// Fallible version of [`PeriodicFunction::ramp`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidSymmetry`] if `symmetry` is not within `[0, 1]` range, or another [`Error`] if any of the
+    /// other parameters is not a finite number.
+    #[inline]
+    pub fn try_ramp(
+        frequency: impl Into<P>,
+        amplitude: impl Into<P>,
+        phase: impl Into<P>,
+        symmetry: impl Into<P>,
+    ) -> Result<Self, Error> {
+        let frequency = finite(frequency.into(), Error::InvalidFrequency)?;
+        let amplitude = finite(amplitude.into(), Error::InvalidAmplitude)?;
+        let phase = finite(phase.into(), Error::InvalidPhase)?;
+        let symmetry = fraction(symmetry.into(), Error::InvalidSymmetry)?;
+
+        Ok(Self::ramp(frequency, amplitude, phase, symmetry))
     }
 
     /// Gets the inner function.
@@ -471,12 +1500,26 @@ impl<P: Precision + 'static> PeriodicFunction<P> {
     pub fn sample(&self, t: P) -> P {
         self.inner()(t)
     }
+
+    /// Gets the kind of the function, with the parameters it was built with.
+    pub fn kind(&self) -> &Kind<P> {
+        &self.kind
+    }
 }
 
-#[cfg(test)]
+#[cfg(feature = "alloc")]
+impl<P: Precision + fmt::Debug> fmt::Debug for PeriodicFunction<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PeriodicFunction")
+            .field("kind", &self.kind)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
 mod tests {
     use super::*;
-    use crate::{dc_bias, sawtooth, sine, square};
+    use crate::{chirp, dc_bias, pulse, ramp, sawtooth, sine, square, triangle, unipolar_pulse};
     use alloc::{vec, vec::Vec};
     use float_cmp::approx_eq;
     use paste::paste;
@@ -542,6 +1585,37 @@ mod tests {
         sine: sine!(1.)
         // sawtooth: sawtooth!(1) // does not pass currently, see https://github.com/spitfire05/wavegen-rs/issues/17
         square: square!(1.)
+        triangle: triangle!(1.)
+        symmetric_ramp: ramp!(1., 1., 0., 0.5)
+        half_duty_pulse: pulse!(frequency: 1., duty: 0.5)
+    }
+
+    macro_rules! test_pulse_mean {
+        ($($name:ident: $func:expr => $mean:expr)*) => {
+            $(
+                paste! {
+                    #[test]
+                    fn [<$name _has_expected_mean>]() {
+                        let wf = Waveform::<f64, f64>::with_components(1000.0, vec![$func]);
+
+                        let mean = wf.iter().take(1000).sum::<f64>() / 1000.0;
+
+                        assert!(approx_eq!(f64, mean, $mean, epsilon = 1e-2));
+                    }
+                }
+            )*
+        };
+    }
+
+    test_pulse_mean! {
+        pulse_10_percent: pulse!(10., 1., 0., 0.1) => -0.8
+        pulse_25_percent: pulse!(frequency: 10., amplitude: 2., duty: 0.25) => -1.0
+        pulse_75_percent_phase_shifted: pulse!(10., 1., 0.3, 0.75) => 0.5
+        pulse_zero_duty: pulse!(10., 1., 0., 0.) => -1.0
+        pulse_full_duty: pulse!(10., 1., 0., 1.) => 1.0
+        unipolar_pulse_10_percent: unipolar_pulse!(10., 1., 0., 0.1) => 0.1
+        unipolar_pulse_25_percent: unipolar_pulse!(frequency: 10., amplitude: 4., duty: 0.25) => 1.0
+        unipolar_pulse_half_duty: unipolar_pulse!(frequency: 10., amplitude: 1., phase: 0.5, duty: 0.5) => 0.5
     }
 
     #[test]
@@ -564,6 +1638,58 @@ mod tests {
         assert_eq!(Some(0f64), iter.skip(usize::MAX).next());
     }
 
+    const DRIFT_TEST_SAMPLES: usize = 1_000_000_000;
+
+    macro_rules! test_no_drift {
+        ($($name:ident: $func:expr => $analytic:expr)*) => {
+            $(
+                paste! {
+                    #[test]
+                    #[allow(clippy::cast_precision_loss)]
+                    fn [<$name _does_not_drift>]() {
+                        let wf = Waveform::<f64, f32>::with_components(44100.0, vec![$func]);
+                        let analytic: fn(f64) -> f64 = $analytic;
+                        let mut iter = wf.iter().skip(DRIFT_TEST_SAMPLES);
+
+                        for i in DRIFT_TEST_SAMPLES..DRIFT_TEST_SAMPLES + 100 {
+                            let cycles = (i as f64 * 997.0 / 44100.0).fract();
+                            let expected = analytic(cycles);
+
+                            assert!(
+                                approx_eq!(f64, iter.next().unwrap_or(f64::NAN), expected, epsilon = 1e-3),
+                                "sample {} differs from {}",
+                                i,
+                                expected
+                            );
+                        }
+                    }
+                }
+            )*
+        };
+    }
+
+    test_no_drift! {
+        sine: sine!(997.) => |x| (2.0 * core::f64::consts::PI * x).sin()
+        sawtooth: sawtooth!(997.) => |x| 2.0 * x - 1.0
+        square: square!(997.) => |x| if x < 0.5 { 1.0 } else { -1.0 }
+        triangle: triangle!(997.) => |x| if x < 0.5 { 4.0 * x - 1.0 } else { 3.0 - 4.0 * x }
+        pulse: pulse!(997., 1., 0., 0.3) => |x| if x < 0.3 { 1.0 } else { -1.0 }
+    }
+
+    #[test]
+    #[allow(clippy::cast_precision_loss)]
+    fn time_of_non_periodic_functions_is_exact() {
+        let wf = Waveform::<f32>::with_components(44100.0, vec![PeriodicFunction::custom(|t| t)]);
+        let t = wf.iter().nth(DRIFT_TEST_SAMPLES).unwrap_or(f32::NAN);
+
+        assert!(approx_eq!(
+            f32,
+            t,
+            DRIFT_TEST_SAMPLES as f32 / 44100.0,
+            ulps = 1
+        ));
+    }
+
     #[test]
     fn oversaturated_amplitude_clips_to_max() {
         let wf = Waveform::<u8>::with_components(100.0, vec![dc_bias!(300.)]);
@@ -631,6 +1757,8 @@ mod tests {
         test_size_hint!();
         test_size_hint!(sine!(50.));
         test_size_hint!(sine!(1.), sawtooth!(2.), square!(3.), dc_bias!(4.));
+        test_size_hint!(triangle!(5.), ramp!(6., 1., 0., 0.3));
+        test_size_hint!(pulse!(7., 1., 0., 0.1), unipolar_pulse!(8., 1., 0., 0.9));
     }
 
     #[test]
@@ -646,6 +1774,268 @@ mod tests {
         }
     }
 
+    fn fill_waveform<T: SampleType>() -> Waveform<T, f64> {
+        let mut wf = Waveform::<T, f64>::with_components(
+            44100.0,
+            vec![
+                sine!(440., 100.),
+                square!(1000., 50.),
+                sawtooth!(300., 20.),
+                dc_bias!(0.5),
+                // bound to the sample rate, so not sampled by the block path of the naive square
+                PeriodicFunction::band_limited_square(2000., 30., 0., BandLimit::PolyBlep),
+            ],
+        );
+        wf.add_stateful_component(random_walk(3));
+        wf.add_stateful_component(random_walk(5));
+
+        wf
+    }
+
+    macro_rules! test_fill {
+        ($($name:ident: $sample_type:ty)*) => {
+            $(
+                paste! {
+                    #[test]
+                    fn [<fill_matches_iterator_for_ $name>]() {
+                        let wf = fill_waveform::<$sample_type>();
+                        let mut iter = wf.iter();
+                        let mut buffer = vec![<$sample_type>::default(); 1000];
+
+                        iter.nth(10);
+                        assert_eq!(1000, iter.fill(&mut buffer));
+                        assert_eq!(wf.iter().skip(11).take(1000).collect::<Vec<_>>(), buffer);
+                        assert_eq!(wf.iter().nth(1011), iter.next());
+                    }
+                }
+            )*
+        };
+    }
+
+    test_fill! {
+        f32: f32
+        f64: f64
+        i16: i16
+        u8: u8
+    }
+
+    #[test]
+    fn fill_with_empty_buffer_does_nothing() {
+        let wf = fill_waveform::<f64>();
+        let mut iter = wf.iter();
+
+        assert_eq!(0, iter.fill(&mut []));
+        assert_eq!(wf.iter().next(), iter.next());
+    }
+
+    #[test]
+    fn fill_stops_at_nan() {
+        let wf = Waveform::<i16, f64>::with_components(
+            100.0,
+            vec![PeriodicFunction::custom(|t| {
+                if t < 0.7 {
+                    1.0
+                } else {
+                    f64::NAN
+                }
+            })],
+        );
+        let mut iter = wf.iter();
+        let mut buffer = [-1; 100];
+
+        assert_eq!(70, iter.fill(&mut buffer));
+        assert_eq!([1, -1], buffer[69..71]);
+        assert_eq!(None, iter.next());
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn fill_advances_just_past_nan() {
+        let mut wf = Waveform::<i16, f64>::with_components(
+            100.0,
+            vec![PeriodicFunction::custom(|t| {
+                if (0.03..0.04).contains(&t) {
+                    f64::NAN
+                } else {
+                    0.0
+                }
+            })],
+        );
+        wf.add_stateful_component(StatefulFunction::new(|_| {
+            let mut n = 0.0;
+            move |_| {
+                n += 1.0;
+                n
+            }
+        }));
+        let mut iter = wf.iter();
+        let mut buffer = [0; 10];
+
+        assert_eq!(3, iter.fill(&mut buffer));
+        assert_eq!([1, 2, 3], buffer[..3]);
+        // the stateful component has seen the NaN sample only
+        assert_eq!(5, iter.next().unwrap());
+        assert_eq!(wf.iter().nth(5), iter.next());
+    }
+
+    fn random_walk(seed: u64) -> StatefulFunction<f64> {
+        StatefulFunction::new(move |_| {
+            let mut state = seed;
+            let mut position = 0.0;
+
+            move |_| {
+                // xorshift64
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                position += if state & 1 == 0 { 1.0 } else { -1.0 };
+
+                position
+            }
+        })
+    }
+
+    #[test]
+    fn stateful_iterators_are_independent() {
+        let mut wf = Waveform::<f64, f64>::with_components(100.0, vec![sine!(1.)]);
+        wf.add_stateful_component(random_walk(42));
+
+        let mut i1 = wf.iter();
+        let first = i1.by_ref().take(100).collect::<Vec<_>>();
+        let second = wf.iter().take(100).collect::<Vec<_>>();
+
+        assert_eq!(first, second);
+        assert_ne!(first, i1.take(100).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn stateful_iterator_clone_continues_independently() {
+        let mut wf = Waveform::<f64, f64>::new(100.0);
+        wf.add_stateful_component(random_walk(7));
+
+        let mut i1 = wf.iter();
+        i1.by_ref().take(10).for_each(drop);
+        let i2 = i1.clone();
+
+        assert_eq!(
+            i1.take(50).collect::<Vec<_>>(),
+            i2.take(50).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn stateful_component_gets_sample_rate_and_time() {
+        let mut wf = Waveform::<f64, f64>::new(50.0);
+        wf.add_stateful_component(StatefulFunction::new(|sample_rate: f64| {
+            move |t: f64| t * sample_rate
+        }));
+
+        let samples = wf.iter().take(5).collect::<Vec<_>>();
+
+        for (i, s) in (0u32..).zip(samples) {
+            assert!(approx_eq!(f64, s, i.into(), epsilon = 1e-9));
+        }
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used, clippy::float_cmp)]
+    fn stateful_nth_and_next_give_same_results() {
+        let mut wf = Waveform::<f64, f64>::with_components(1000.0, vec![sine!(3.)]);
+        wf.add_stateful_component(random_walk(1));
+        let mut i1 = wf.iter();
+        let mut i2 = wf.iter();
+
+        for n in 0..50 {
+            for _ in 0..n {
+                i1.next();
+            }
+            assert_eq!(i1.next().unwrap(), i2.nth(n).unwrap());
+        }
+    }
+
+    macro_rules! test_try_new_fails {
+        ($($name:ident: $sample_rate:expr)*) => {
+            $(
+                paste! {
+                    #[test]
+                    fn [<waveform_try_new_fails_on_ $name>]() {
+                        assert_eq!(Some(Error::InvalidSampleRate), Waveform::<f64>::try_new($sample_rate).err());
+                        assert_eq!(
+                            Some(Error::InvalidSampleRate),
+                            Waveform::<f64>::try_with_components($sample_rate, vec![]).err()
+                        );
+                    }
+                }
+            )*
+        };
+    }
+
+    test_try_new_fails! {
+        nan: f32::NAN
+        negative: -1f32
+        zero: 0.0
+        infinity: f32::INFINITY
+        negative_infinity: f32::NEG_INFINITY
+    }
+
+    macro_rules! test_try_builders_fail {
+        ($($name:ident: $builder:ident($($arg:expr),*) => $error:expr)*) => {
+            $(
+                paste! {
+                    #[test]
+                    fn [<try_ $name _fails>]() {
+                        assert_eq!(Some($error), PeriodicFunction::<f64>::[<try_ $builder>]($($arg),*).err());
+                    }
+                }
+            )*
+        };
+    }
+
+    test_try_builders_fail! {
+        dc_bias_with_nan: dc_bias(f64::NAN) => Error::InvalidAmplitude
+        sine_with_infinite_frequency: sine(f64::INFINITY, 1.0, 0.0) => Error::InvalidFrequency
+        sine_with_nan_phase: sine(1.0, 1.0, f64::NAN) => Error::InvalidPhase
+        square_with_nan_amplitude: square(1.0, f64::NAN, 0.0) => Error::InvalidAmplitude
+        sawtooth_with_nan_frequency: sawtooth(f64::NAN, 1.0, 0.0) => Error::InvalidFrequency
+        triangle_with_infinite_amplitude: triangle(1.0, f64::NEG_INFINITY, 0.0) => Error::InvalidAmplitude
+        ramp_with_infinite_phase: ramp(1.0, 1.0, f64::INFINITY, 0.5) => Error::InvalidPhase
+        pulse_with_nan_frequency: pulse(f64::NAN, 1.0, 0.0, 0.5) => Error::InvalidFrequency
+        unipolar_pulse_with_nan_amplitude: unipolar_pulse(1.0, f64::NAN, 0.0, 0.5) => Error::InvalidAmplitude
+        pulse_with_nan_duty: pulse(1.0, 1.0, 0.0, f64::NAN) => Error::InvalidDuty
+        pulse_with_negative_duty: pulse(1.0, 1.0, 0.0, -0.1) => Error::InvalidDuty
+        unipolar_pulse_with_duty_above_one: unipolar_pulse(1.0, 1.0, 0.0, 1.5) => Error::InvalidDuty
+        ramp_with_nan_symmetry: ramp(1.0, 1.0, 0.0, f64::NAN) => Error::InvalidSymmetry
+        ramp_with_infinite_symmetry: ramp(1.0, 1.0, 0.0, f64::INFINITY) => Error::InvalidSymmetry
+    }
+
+    #[test]
+    fn try_with_components_accepts_nyquist_frequency() {
+        let wf = Waveform::<f64>::try_with_components(
+            100.0,
+            vec![sine!(50.), square!(-50.), dc_bias!(1000.)],
+        );
+
+        assert!(wf.is_ok());
+    }
+
+    #[test]
+    fn try_with_components_rejects_frequency_above_nyquist() {
+        let wf = Waveform::<f64>::try_with_components(100.0, vec![sine!(50.), sawtooth!(-50.1)]);
+
+        assert_eq!(Some(Error::NyquistViolation { component: 1 }), wf.err());
+    }
+
+    #[test]
+    fn try_add_component_rejects_chirp_sweeping_above_nyquist() {
+        let mut wf = Waveform::<f64>::new(1000.0);
+
+        assert_eq!(
+            Some(Error::NyquistViolation { component: 0 }),
+            wf.try_add_component(chirp!(10., 5000., 1.)).err()
+        );
+        assert!(wf.try_add_component(chirp!(10., 500., 1.)).is_ok());
+    }
+
     #[test]
     fn waveform_is_send() {
         fn assert_send<T: Send>() {}
@@ -657,4 +2047,10 @@ mod tests {
         fn assert_sync<T: Sync>() {}
         assert_sync::<Waveform<f64>>();
     }
+
+    #[test]
+    fn waveform_iterator_is_send() {
+        fn assert_send<T: Send>() {}
+        assert_send::<WaveformIterator<'_, f64, f64>>();
+    }
 }
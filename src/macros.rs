@@ -177,6 +177,468 @@ macro_rules! square {
     };
 }
 
+/// Builder macro for Bipolar Pulse [`PeriodicFunction`].
+///
+/// Takes 4 arguments - frequency, amplitude, phase, duty. In the annotated form, amplitude and phase are optional.
+///
+/// | argument | unit | notes |
+/// | -------- | ---- | ----- |
+/// | frequency | Hz | Frequecy of the periodic function. Also: 1 / period |
+/// | amplitude | *arbitrary* | The amplitude of the function in 0-peak notation. The function swings between `-amplitude` and `amplitude`. |
+/// | phase | *periods* | The phase shift of the function. Value of 1 means full shift around.
+/// | duty | *periods* | Fraction of the period the function stays high. |
+///
+/// # Examples
+///
+/// 50 Hz bipolar pulse of amplitude 1 and 10% duty cycle
+/// ```
+/// let pulse: wavegen::PeriodicFunction<f32> = wavegen::pulse!(frequency: 50., duty: 0.1);
+/// ```
+///
+/// 50 Hz bipolar pulse of amplitude 20, phase shift of half a turn and 25% duty cycle
+/// ```
+/// let pulse: wavegen::PeriodicFunction<f32> = wavegen::pulse!(50., 20., 0.5, 0.25);
+/// ```
+///
+/// [`PeriodicFunction`]: type.periodicfunction.html
+#[macro_export]
+macro_rules! pulse {
+    (frequency: $frequency:expr, duty: $duty:expr) => {
+        $crate::pulse!($frequency, 1.0, 0.0, $duty)
+    };
+    (frequency: $frequency:expr, amplitude: $amplitude:expr, duty: $duty:expr) => {
+        $crate::pulse!($frequency, $amplitude, 0.0, $duty)
+    };
+    (frequency: $frequency:expr, amplitude: $amplitude:expr, phase: $phase:expr, duty: $duty:expr) => {
+        $crate::pulse!($frequency, $amplitude, $phase, $duty)
+    };
+    ($frequency:expr, $amplitude:expr, $phase:expr, $duty:expr) => {
+        $crate::PeriodicFunction::pulse($frequency, $amplitude, $phase, $duty)
+    };
+}
+
+/// Builder macro for Unipolar Pulse [`PeriodicFunction`].
+///
+/// Takes 4 arguments - frequency, amplitude, phase, duty. In the annotated form, amplitude and phase are optional.
+///
+/// | argument | unit | notes |
+/// | -------- | ---- | ----- |
+/// | frequency | Hz | Frequecy of the periodic function. Also: 1 / period |
+/// | amplitude | *arbitrary* | The peak value of the function. The function swings between `0` and `amplitude`. |
+/// | phase | *periods* | The phase shift of the function. Value of 1 means full shift around.
+/// | duty | *periods* | Fraction of the period the function stays high. |
+///
+/// # Examples
+///
+/// 50 Hz unipolar pulse of amplitude 1 and 10% duty cycle
+/// ```
+/// let pulse: wavegen::PeriodicFunction<f32> = wavegen::unipolar_pulse!(frequency: 50., duty: 0.1);
+/// ```
+///
+/// 50 Hz unipolar pulse of amplitude 20, phase shift of half a turn and 25% duty cycle
+/// ```
+/// let pulse: wavegen::PeriodicFunction<f32> = wavegen::unipolar_pulse!(50., 20., 0.5, 0.25);
+/// ```
+///
+/// [`PeriodicFunction`]: type.periodicfunction.html
+#[macro_export]
+macro_rules! unipolar_pulse {
+    (frequency: $frequency:expr, duty: $duty:expr) => {
+        $crate::unipolar_pulse!($frequency, 1.0, 0.0, $duty)
+    };
+    (frequency: $frequency:expr, amplitude: $amplitude:expr, duty: $duty:expr) => {
+        $crate::unipolar_pulse!($frequency, $amplitude, 0.0, $duty)
+    };
+    (frequency: $frequency:expr, amplitude: $amplitude:expr, phase: $phase:expr, duty: $duty:expr) => {
+        $crate::unipolar_pulse!($frequency, $amplitude, $phase, $duty)
+    };
+    ($frequency:expr, $amplitude:expr, $phase:expr, $duty:expr) => {
+        $crate::PeriodicFunction::unipolar_pulse($frequency, $amplitude, $phase, $duty)
+    };
+}
+
+/// Builder macro for Triangle [`PeriodicFunction`].
+///
+/// Takes up to 3 arguments - frequency {amplitude, {phase}}
+///
+/// | argument | unit | notes |
+/// | -------- | ---- | ----- |
+/// | frequency | Hz | Frequecy of the periodic function. Also: 1 / period |
+/// | amplitude | *arbitrary* | The amplitude of the function in 0-peak notation. |
+/// | phase | *periods* | The phase shift of the function. Value of 1 means full shift around.
+///
+/// # Examples
+///
+/// 50 Hz triangle of amplitude 1 and no phase shift
+/// ```
+/// let triangle: wavegen::PeriodicFunction<f32> = wavegen::triangle!(50.);
+/// ```
+///
+/// 50 Hz triangle of amplitude 20 and phase shift of a quarter turn
+/// ```
+/// let triangle: wavegen::PeriodicFunction<f32> = wavegen::triangle!(frequency: 50., amplitude: 20., phase: 0.25);
+/// ```
+///
+/// [`PeriodicFunction`]: type.periodicfunction.html
+#[macro_export]
+macro_rules! triangle {
+    (frequency: $frequency:expr) => {
+        $crate::triangle!($frequency)
+    };
+    (frequency: $frequency:expr, amplitude: $amplitude:expr) => {
+        $crate::triangle!($frequency, $amplitude)
+    };
+    (frequency: $frequency:expr, amplitude: $amplitude:expr, phase: $phase:expr) => {
+        $crate::triangle!($frequency, $amplitude, $phase)
+    };
+    ($frequency:expr) => {
+        $crate::triangle!($frequency, 1.0, 0.0)
+    };
+    ($frequency:expr, $amplitude:expr) => {
+        $crate::triangle!($frequency, $amplitude, 0.0)
+    };
+    ($frequency:expr, $amplitude:expr, $phase:expr) => {
+        $crate::PeriodicFunction::triangle($frequency, $amplitude, $phase)
+    };
+}
+
+/// Builder macro for variable-symmetry Ramp [`PeriodicFunction`].
+///
+/// Takes 4 arguments - frequency, amplitude, phase, symmetry. In the annotated form, amplitude and phase are optional.
+///
+/// | argument | unit | notes |
+/// | -------- | ---- | ----- |
+/// | frequency | Hz | Frequecy of the periodic function. Also: 1 / period |
+/// | amplitude | *arbitrary* | The amplitude of the function in 0-peak notation. |
+/// | phase | *periods* | The phase shift of the function. Value of 1 means full shift around.
+/// | symmetry | *periods* | Fraction of the period spent rising. `0` is a falling sawtooth, `0.5` a triangle, `1` a rising sawtooth.
+///
+/// # Examples
+///
+/// 50 Hz ramp of amplitude 1, rising for 3/4 of the period
+/// ```
+/// let ramp: wavegen::PeriodicFunction<f32> = wavegen::ramp!(frequency: 50., symmetry: 0.75);
+/// ```
+///
+/// 50 Hz falling sawtooth of amplitude 20 and no phase shift
+/// ```
+/// let ramp: wavegen::PeriodicFunction<f32> = wavegen::ramp!(50., 20., 0., 0.);
+/// ```
+///
+/// [`PeriodicFunction`]: type.periodicfunction.html
+#[macro_export]
+macro_rules! ramp {
+    (frequency: $frequency:expr, symmetry: $symmetry:expr) => {
+        $crate::ramp!($frequency, 1.0, 0.0, $symmetry)
+    };
+    (frequency: $frequency:expr, amplitude: $amplitude:expr, symmetry: $symmetry:expr) => {
+        $crate::ramp!($frequency, $amplitude, 0.0, $symmetry)
+    };
+    (frequency: $frequency:expr, amplitude: $amplitude:expr, phase: $phase:expr, symmetry: $symmetry:expr) => {
+        $crate::ramp!($frequency, $amplitude, $phase, $symmetry)
+    };
+    ($frequency:expr, $amplitude:expr, $phase:expr, $symmetry:expr) => {
+        $crate::PeriodicFunction::ramp($frequency, $amplitude, $phase, $symmetry)
+    };
+}
+
+/// Builder macro for Chirp (swept sine) [`PeriodicFunction`].
+///
+/// Takes 3 to 7 arguments - start frequency, end frequency, duration {amplitude, {phase, {sweep, after}}}
+///
+/// | argument | unit | notes |
+/// | -------- | ---- | ----- |
+/// | start | Hz | Frequency at the beginning of the sweep. |
+/// | end | Hz | Frequency at the end of the sweep. |
+/// | duration | s | Duration of the sweep. |
+/// | amplitude | *arbitrary* | The amplitude of the function in 0-peak notation. |
+/// | phase | *periods* | The phase shift of the function. Value of 1 means full shift around.
+/// | sweep | [`Sweep`] | How the frequency changes during the sweep. Defaults to [`Sweep::Linear`]. |
+/// | after | [`AfterSweep`] | What happens after the sweep ends. Defaults to [`AfterSweep::Hold`]. |
+///
+/// In the annotated form, all arguments after `duration` are optional, but have to be given in the above order.
+///
+/// # Panics
+///
+/// This macro will cause panic if `duration` is not a finite, positive, non-zero number, or if the frequencies of an
+/// exponential or hyperbolic sweep are zero or of opposite signs. See [`PeriodicFunction::try_chirp`].
+///
+/// # Examples
+///
+/// Linear sweep from 20 Hz to 20 kHz during 10 seconds
+/// ```
+/// let chirp: wavegen::PeriodicFunction<f32> = wavegen::chirp!(20., 20_000., 10.);
+/// ```
+///
+/// Exponential sweep from 20 Hz to 20 kHz during 10 seconds, of amplitude 0.5, swept back and forth
+/// ```
+/// use wavegen::{chirp, AfterSweep, PeriodicFunction, Sweep};
+///
+/// let chirp: PeriodicFunction<f32> = chirp!(
+///     start: 20.,
+///     end: 20_000.,
+///     duration: 10.,
+///     amplitude: 0.5,
+///     sweep: Sweep::Exponential,
+///     after: AfterSweep::PingPong
+/// );
+/// ```
+///
+/// [`PeriodicFunction`]: type.periodicfunction.html
+/// [`PeriodicFunction::try_chirp`]: struct.PeriodicFunction.html#method.try_chirp
+/// [`Sweep`]: enum.Sweep.html
+/// [`Sweep::Linear`]: enum.Sweep.html#variant.Linear
+/// [`AfterSweep`]: enum.AfterSweep.html
+/// [`AfterSweep::Hold`]: enum.AfterSweep.html#variant.Hold
+#[macro_export]
+macro_rules! chirp {
+    (@or $value:expr, $default:expr) => {
+        $value
+    };
+    (@or , $default:expr) => {
+        $default
+    };
+    (
+        start: $start:expr,
+        end: $end:expr,
+        duration: $duration:expr
+        $(, amplitude: $amplitude:expr)?
+        $(, phase: $phase:expr)?
+        $(, sweep: $sweep:expr)?
+        $(, after: $after:expr)?
+        $(,)?
+    ) => {
+        $crate::chirp!(
+            $start,
+            $end,
+            $duration,
+            $crate::chirp!(@or $($amplitude)?, 1.0),
+            $crate::chirp!(@or $($phase)?, 0.0),
+            $crate::chirp!(@or $($sweep)?, $crate::Sweep::Linear),
+            $crate::chirp!(@or $($after)?, $crate::AfterSweep::Hold)
+        )
+    };
+    ($start:expr, $end:expr, $duration:expr) => {
+        $crate::chirp!($start, $end, $duration, 1.0, 0.0)
+    };
+    ($start:expr, $end:expr, $duration:expr, $amplitude:expr) => {
+        $crate::chirp!($start, $end, $duration, $amplitude, 0.0)
+    };
+    ($start:expr, $end:expr, $duration:expr, $amplitude:expr, $phase:expr) => {
+        $crate::chirp!(
+            $start,
+            $end,
+            $duration,
+            $amplitude,
+            $phase,
+            $crate::Sweep::Linear,
+            $crate::AfterSweep::Hold
+        )
+    };
+    ($start:expr, $end:expr, $duration:expr, $amplitude:expr, $phase:expr, $sweep:expr, $after:expr) => {
+        $crate::PeriodicFunction::chirp($start, $end, $duration, $amplitude, $phase, $sweep, $after)
+    };
+}
+
+/// Builder macro for ADSR envelope [`PeriodicFunction`].
+///
+/// Takes 5 arguments - attack, decay, sustain, release, trigger
+///
+/// | argument | unit | notes |
+/// | -------- | ---- | ----- |
+/// | attack | s | Time to rise from zero to one, after the gate opens. |
+/// | decay | s | Time to fall from one to the sustain level. |
+/// | sustain | *arbitrary* | Level held while the gate stays open. |
+/// | release | s | Time to fall to zero from the level the gate closed at. |
+/// | trigger | [`Trigger`] | When the gate opens and closes. |
+///
+/// In the annotated form, the trigger is given either by `on` and `off` times, or by `period` and `gate` length.
+///
+/// The envelope multiplies other functions, like in the examples below.
+///
+/// # Examples
+///
+/// A 440 Hz note, held from 1 to 2 seconds
+/// ```
+/// use wavegen::{adsr, sine, PeriodicFunction, Trigger};
+///
+/// let note: PeriodicFunction<f32> = sine!(440.) * adsr!(0.01, 0.1, 0.7, 0.3, Trigger::Once { on: 1., off: 2. });
+/// ```
+///
+/// A 440 Hz note, held for 0.5 second every second
+/// ```
+/// use wavegen::{adsr, sine, PeriodicFunction};
+///
+/// let notes: PeriodicFunction<f32> = sine!(440.) * adsr!(attack: 0.01, decay: 0.1, sustain: 0.7, release: 0.3, period: 1., gate: 0.5);
+/// ```
+///
+/// [`PeriodicFunction`]: type.periodicfunction.html
+/// [`Trigger`]: enum.Trigger.html
+#[macro_export]
+macro_rules! adsr {
+    (attack: $attack:expr, decay: $decay:expr, sustain: $sustain:expr, release: $release:expr, on: $on:expr, off: $off:expr) => {
+        $crate::adsr!(
+            $attack,
+            $decay,
+            $sustain,
+            $release,
+            $crate::Trigger::Once { on: $on, off: $off }
+        )
+    };
+    (attack: $attack:expr, decay: $decay:expr, sustain: $sustain:expr, release: $release:expr, period: $period:expr, gate: $gate:expr) => {
+        $crate::adsr!(
+            $attack,
+            $decay,
+            $sustain,
+            $release,
+            $crate::Trigger::Loop {
+                period: $period,
+                gate: $gate
+            }
+        )
+    };
+    ($attack:expr, $decay:expr, $sustain:expr, $release:expr, $trigger:expr) => {
+        $crate::PeriodicFunction::adsr($attack, $decay, $sustain, $release, $trigger)
+    };
+}
+
+/// Builder macro for AR (attack-release) envelope [`PeriodicFunction`].
+///
+/// Takes 3 arguments - attack, release, trigger
+///
+/// | argument | unit | notes |
+/// | -------- | ---- | ----- |
+/// | attack | s | Time to rise from zero to one, after the gate opens. |
+/// | release | s | Time to fall to zero from the level the gate closed at. |
+/// | trigger | [`Trigger`] | When the gate opens and closes. |
+///
+/// In the annotated form, the trigger is given either by `on` and `off` times, or by `period` and `gate` length.
+///
+/// # Examples
+///
+/// A 1 kHz test burst, 10 ms long, every 100 ms
+/// ```
+/// use wavegen::{ar, sine, PeriodicFunction};
+///
+/// let bursts: PeriodicFunction<f32> = sine!(1000.) * ar!(attack: 0.001, release: 0.001, period: 0.1, gate: 0.01);
+/// ```
+///
+/// [`PeriodicFunction`]: type.periodicfunction.html
+/// [`Trigger`]: enum.Trigger.html
+#[macro_export]
+macro_rules! ar {
+    (attack: $attack:expr, release: $release:expr, on: $on:expr, off: $off:expr) => {
+        $crate::ar!(
+            $attack,
+            $release,
+            $crate::Trigger::Once { on: $on, off: $off }
+        )
+    };
+    (attack: $attack:expr, release: $release:expr, period: $period:expr, gate: $gate:expr) => {
+        $crate::ar!(
+            $attack,
+            $release,
+            $crate::Trigger::Loop {
+                period: $period,
+                gate: $gate
+            }
+        )
+    };
+    ($attack:expr, $release:expr, $trigger:expr) => {
+        $crate::PeriodicFunction::ar($attack, $release, $trigger)
+    };
+}
+
+/// Builder macro for exponential decay envelope [`PeriodicFunction`].
+///
+/// Takes 1 or 2 arguments - time constant {trigger}
+///
+/// | argument | unit | notes |
+/// | -------- | ---- | ----- |
+/// | time constant | s | Time the level falls by the factor of e in. |
+/// | trigger | [`Trigger`] | When the envelope starts. Defaults to a single trigger at zero. |
+///
+/// In the annotated form, the trigger is given either by `on` time, or by `period`.
+///
+/// # Examples
+///
+/// A plucked 220 Hz string
+/// ```
+/// use wavegen::{exponential_decay, sawtooth, PeriodicFunction};
+///
+/// let pluck: PeriodicFunction<f32> = sawtooth!(220.) * exponential_decay!(0.5);
+/// ```
+///
+/// The same, plucked every 2 seconds
+/// ```
+/// use wavegen::{exponential_decay, sawtooth, PeriodicFunction};
+///
+/// let plucks: PeriodicFunction<f32> = sawtooth!(220.) * exponential_decay!(time_constant: 0.5, period: 2.);
+/// ```
+///
+/// [`PeriodicFunction`]: type.periodicfunction.html
+/// [`Trigger`]: enum.Trigger.html
+#[macro_export]
+macro_rules! exponential_decay {
+    (time_constant: $time_constant:expr) => {
+        $crate::exponential_decay!($time_constant)
+    };
+    (time_constant: $time_constant:expr, on: $on:expr) => {
+        $crate::exponential_decay!($time_constant, $crate::Trigger::Once { on: $on, off: $on })
+    };
+    (time_constant: $time_constant:expr, period: $period:expr) => {
+        $crate::exponential_decay!(
+            $time_constant,
+            $crate::Trigger::Loop {
+                period: $period,
+                gate: 0.0
+            }
+        )
+    };
+    ($time_constant:expr) => {
+        $crate::exponential_decay!($time_constant, $crate::Trigger::Once { on: 0.0, off: 0.0 })
+    };
+    ($time_constant:expr, $trigger:expr) => {
+        $crate::PeriodicFunction::exponential_decay($time_constant, $trigger)
+    };
+}
+
+/// Builder macro for piecewise-linear envelope [`PeriodicFunction`].
+///
+/// Takes 1 or 2 arguments - points {trigger}
+///
+/// | argument | unit | notes |
+/// | -------- | ---- | ----- |
+/// | points | (s, *arbitrary*) | Array of time since trigger and level pairs, linearly interpolated between. |
+/// | trigger | [`Trigger`] | When the envelope starts. Defaults to a single trigger at zero. |
+///
+/// The level of the first and last point is held before and after them, respectively.
+///
+/// # Examples
+///
+/// A trapezoidal envelope, looped every 3 seconds
+/// ```
+/// use wavegen::{piecewise_linear, sine, PeriodicFunction, Trigger};
+///
+/// let trapezoid: PeriodicFunction<f32> = piecewise_linear!(
+///     [(0., 0.), (0.5, 1.), (1.5, 1.), (2., 0.)],
+///     Trigger::Loop { period: 3., gate: 0. }
+/// );
+/// let tone = sine!(100.) * trapezoid;
+/// ```
+///
+/// [`PeriodicFunction`]: type.periodicfunction.html
+/// [`Trigger`]: enum.Trigger.html
+#[macro_export]
+macro_rules! piecewise_linear {
+    ($points:expr) => {
+        $crate::piecewise_linear!($points, $crate::Trigger::Once { on: 0.0, off: 0.0 })
+    };
+    ($points:expr, $trigger:expr) => {
+        $crate::PeriodicFunction::piecewise_linear(&$points, $trigger)
+    };
+}
+
 #[cfg(test)]
 mod tests {
     use float_cmp::approx_eq;
@@ -246,6 +708,121 @@ mod tests {
         assert!(approx_eq!(f64, zero, 0.0, epsilon = EPS));
     }
 
+    #[test]
+    fn pulse_with_half_duty_is_square() {
+        let pulse: PeriodicFunction<f64> = pulse!(frequency: 1.0, duty: 0.5);
+        let square = square!(1.0);
+
+        for x in [0.0, 0.1, 0.2, 0.3, 0.4, 0.6, 0.7, 0.8, 0.9] {
+            assert!(approx_eq!(
+                f64,
+                pulse.sample(x),
+                square.sample(x),
+                epsilon = EPS
+            ));
+        }
+    }
+
+    #[test]
+    fn unipolar_pulse_swings_between_zero_and_amplitude() {
+        let pulse: PeriodicFunction<f64> = unipolar_pulse!(1.0, 3.0, 0.0, 0.2);
+
+        assert!(approx_eq!(f64, pulse.sample(0.1), 3.0, epsilon = EPS));
+        assert!(approx_eq!(f64, pulse.sample(0.3), 0.0, epsilon = EPS));
+        assert!(approx_eq!(f64, pulse.sample(0.9), 0.0, epsilon = EPS));
+    }
+
+    #[test]
+    fn default_triangle_has_amplitude_of_one() {
+        let triangle = triangle!(1);
+
+        assert!(approx_eq!(f64, triangle.sample(0.0), -1.0, epsilon = EPS));
+        assert!(approx_eq!(f64, triangle.sample(0.25), 0.0, epsilon = EPS));
+        assert!(approx_eq!(f64, triangle.sample(0.5), 1.0, epsilon = EPS));
+        assert!(approx_eq!(f64, triangle.sample(0.75), 0.0, epsilon = EPS));
+    }
+
+    #[test]
+    fn ramp_with_full_symmetry_is_sawtooth() {
+        let ramp: PeriodicFunction<f64> = ramp!(frequency: 2.0, amplitude: 3.0, symmetry: 1.0);
+        let sawtooth = sawtooth!(2.0, 3.0);
+
+        for x in [0.0, 0.1, 0.2, 0.3, 0.4, 0.6, 0.8] {
+            assert!(approx_eq!(
+                f64,
+                ramp.sample(x),
+                sawtooth.sample(x),
+                epsilon = EPS
+            ));
+        }
+    }
+
+    #[test]
+    fn ramp_with_zero_symmetry_is_falling_sawtooth() {
+        let ramp: PeriodicFunction<f64> = ramp!(1.0, 1.0, 0.0, 0.0);
+
+        assert!(approx_eq!(f64, ramp.sample(0.0), 1.0, epsilon = EPS));
+        assert!(approx_eq!(f64, ramp.sample(0.5), 0.0, epsilon = EPS));
+        assert!(approx_eq!(f64, ramp.sample(0.99999), -1.0, epsilon = EPS));
+    }
+
+    #[test]
+    fn ramp_peaks_at_symmetry_point() {
+        let ramp: PeriodicFunction<f64> = ramp!(frequency: 1.0, symmetry: 0.25);
+
+        assert!(approx_eq!(f64, ramp.sample(0.25), 1.0, epsilon = EPS));
+        assert!(approx_eq!(f64, ramp.sample(0.125), 0.0, epsilon = EPS));
+        assert!(approx_eq!(f64, ramp.sample(0.625), 0.0, epsilon = EPS));
+    }
+
+    #[test]
+    fn chirp_annotated_and_positional_forms_are_equal() {
+        use crate::{AfterSweep, Sweep};
+
+        let positional: PeriodicFunction<f64> = chirp!(
+            10.0,
+            20.0,
+            1.0,
+            2.0,
+            0.0,
+            Sweep::Exponential,
+            AfterSweep::Repeat
+        );
+        let annotated: PeriodicFunction<f64> = chirp!(
+            start: 10.0,
+            end: 20.0,
+            duration: 1.0,
+            amplitude: 2.0,
+            sweep: Sweep::Exponential,
+            after: AfterSweep::Repeat,
+        );
+
+        for x in [0.0, 0.1, 0.5, 1.3, 2.7] {
+            assert!(approx_eq!(
+                f64,
+                positional.sample(x),
+                annotated.sample(x),
+                epsilon = EPS
+            ));
+        }
+    }
+
+    #[test]
+    fn default_chirp_is_linear_and_holds() {
+        let chirp: PeriodicFunction<f64> = chirp!(start: 1.0, end: 3.0, duration: 1.0);
+        let sine = sine!(3.0);
+
+        // after the sweep, phase advanced by 2 full periods, so the chirp is aligned with the end frequency sine
+        for x in [1.1, 1.25, 2.0, 5.7] {
+            assert!(approx_eq!(
+                f64,
+                chirp.sample(x),
+                sine.sample(x),
+                epsilon = EPS
+            ));
+        }
+    }
+
     #[test]
     fn default_square_has_amplitude_of_one() {
         let square = square!(1);
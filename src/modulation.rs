@@ -0,0 +1,487 @@
+//! Amplitude, frequency and phase modulation builders.
+
+use crate::ops::{common_frequency, derived_kind};
+use crate::{finite, Error, Kind, PeriodicFunction, Precision, Two};
+use alloc::{boxed::Box, vec, vec::Vec};
+use num_traits::NumCast;
+
+/// Number of segments one period of the frequency modulator is integrated over.
+const SEGMENTS: usize = 1024;
+
+/// Running integral of a periodic function, tabulated over one period.
+///
+/// Between the tabulated points, the integral is interpolated by cubic Hermite splines, with the function itself as
+/// the derivative. This keeps the integral continuous even for discontinuous functions, like square waves.
+struct Integral<P: Precision> {
+    period: P,
+    step: P,
+    /// Integral from the start of the period to the start of each segment, with the whole period last.
+    integrals: Vec<P>,
+    /// Function at the start of each segment, and at the end of the period.
+    values: Vec<P>,
+}
+
+impl<P: Precision> Integral<P> {
+    fn new(function: &dyn Fn(P) -> P, frequency: P) -> Self {
+        let period = frequency.recip();
+        let segments: P = NumCast::from(SEGMENTS).unwrap_or_else(P::one);
+        let step = period / segments;
+        let start = |i: usize| step * NumCast::from(i).unwrap_or_else(P::zero);
+
+        let mut integrals = Vec::with_capacity(SEGMENTS + 1);
+        let mut sum = P::zero();
+        integrals.push(sum);
+        for i in 0..SEGMENTS {
+            sum = sum + gauss_legendre(function, start(i), step);
+            integrals.push(sum);
+        }
+        let values = (0..=SEGMENTS).map(|i| function(start(i))).collect();
+
+        Self {
+            period,
+            step,
+            integrals,
+            values,
+        }
+    }
+
+    /// Integral of the function from `0` to `t`.
+    fn at(&self, t: P) -> P {
+        let periods = (t / self.period).floor();
+        let tau = t - periods * self.period;
+        let segment = (tau / self.step).to_usize().unwrap_or(0).min(SEGMENTS - 1);
+        let s = tau / self.step - NumCast::from(segment).unwrap_or_else(P::zero);
+
+        let three = P::two() + P::one();
+        let s2 = s * s;
+        let s3 = s2 * s;
+        let h00 = P::two() * s3 - three * s2 + P::one();
+        let h10 = s3 - P::two() * s2 + s;
+        let h01 = three * s2 - P::two() * s3;
+        let h11 = s3 - s2;
+
+        periods * self.integrals[SEGMENTS]
+            + h00 * self.integrals[segment]
+            + h10 * self.step * self.values[segment]
+            + h01 * self.integrals[segment + 1]
+            + h11 * self.step * self.values[segment + 1]
+    }
+}
+
+/// Integral of `f` over `[a, a + h]` by two point Gauss-Legendre quadrature, which does not sample the ends.
+#[inline]
+fn gauss_legendre<P: Precision>(f: &dyn Fn(P) -> P, a: P, h: P) -> P {
+    let middle = a + h / P::two();
+    let offset = h / (P::two() * (P::two() + P::one()).sqrt());
+
+    h / P::two() * (f(middle - offset) + f(middle + offset))
+}
+
+impl<P: Precision> PeriodicFunction<P> {
+    /// Amplitude modulation builder.
+    ///
+    /// The result is `carrier(t) * (1 + depth * modulator(t))`, so a `depth` of `1` fully modulates a carrier with a
+    /// modulator swinging between `-1` and `1`.
+    ///
+    /// # Examples
+    ///
+    /// 1 kHz tone, modulated at 50% depth by a 10 Hz sine:
+    ///
+    /// ```
+    /// use wavegen::{sine, PeriodicFunction};
+    ///
+    /// let am: PeriodicFunction<f32> = PeriodicFunction::am(sine!(1000.), sine!(10.), 0.5);
+    ///
+    /// assert!((am.sample(0.025 + 0.00025) - 1.5).abs() < 1e-3);
+    /// ```
+    #[must_use]
+    pub fn am(carrier: Self, modulator: Self, depth: impl Into<P>) -> Self {
+        let depth = depth.into();
+        let operands = [&carrier, &modulator];
+        let kind = derived_kind(&operands, |mut kinds| {
+            let modulator = Box::new(kinds.remove(1));
+            let carrier = Box::new(kinds.remove(0));
+
+            Kind::Am {
+                carrier,
+                modulator,
+                depth,
+            }
+        });
+        let frequency = common_frequency(&operands);
+
+        Self::derived(
+            kind,
+            frequency,
+            vec![carrier, modulator],
+            move |mut operands| {
+                let modulator = operands.remove(1);
+                let carrier = operands.remove(0);
+
+                move |t| carrier(t) * (P::one() + depth * modulator(t))
+            },
+        )
+    }
+
+    /// Frequency modulation builder.
+    ///
+    /// The result is a unit sine, with instantaneous frequency of `carrier_frequency + deviation * modulator(t)` Hz.
+    /// The frequency is integrated, so the phase stays continuous however the modulator changes.
+    ///
+    /// The modulator is integrated over its period, so it has to have one: a built-in function of known frequency, or
+    /// a sum or product of them at harmonically related frequencies, like `sine!(3.) + sine!(5.)`, possibly with a DC
+    /// offset. Noise, chirps and custom functions are rejected.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::AperiodicModulator`] if the modulator has no known frequency, or [`Error::InvalidFrequency`] if
+    /// the carrier frequency or the deviation is not a finite number.
+    ///
+    /// # Examples
+    ///
+    /// 1 kHz tone, swept by ±100 Hz by a 5 Hz triangle:
+    ///
+    /// ```
+    /// use wavegen::{sine, triangle, Error, PeriodicFunction};
+    ///
+    /// let fm: PeriodicFunction<f32> = PeriodicFunction::fm(1000., triangle!(5.), 100.).unwrap();
+    ///
+    /// assert!(PeriodicFunction::<f32>::fm(1000., sine!(3.) + sine!(5.), 100.).is_ok());
+    /// assert_eq!(
+    ///     Some(Error::AperiodicModulator),
+    ///     PeriodicFunction::<f32>::fm(1000., PeriodicFunction::custom(|t| t), 100.).err()
+    /// );
+    /// ```
+    #[allow(clippy::needless_pass_by_value)]
+    pub fn fm(
+        carrier_frequency: impl Into<P>,
+        modulator: Self,
+        deviation: impl Into<P>,
+    ) -> Result<Self, Error> {
+        let carrier_frequency = finite(carrier_frequency.into(), Error::InvalidFrequency)?;
+        let deviation = finite(deviation.into(), Error::InvalidFrequency)?;
+        let frequency = modulator.frequency.ok_or(Error::AperiodicModulator)?;
+
+        let kind = derived_kind(&[&modulator], |mut kinds| Kind::Fm {
+            carrier_frequency,
+            modulator: Box::new(kinds.remove(0)),
+            deviation,
+        });
+
+        Ok(Self::derived(
+            kind,
+            None,
+            vec![modulator],
+            move |operands| {
+                let integral = Integral::new(&*operands[0], frequency);
+
+                move |t| {
+                    let cycles =
+                        (carrier_frequency * t).fract() + (deviation * integral.at(t)).fract();
+
+                    (P::two() * P::PI() * cycles).sin()
+                }
+            },
+        ))
+    }
+
+    /// Phase modulation builder.
+    ///
+    /// The result is a unit sine of `carrier_frequency`, with its phase shifted by `deviation * modulator(t)`
+    /// radians.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidFrequency`] if the carrier frequency or the deviation is not a finite number.
+    ///
+    /// # Examples
+    ///
+    /// 1 kHz tone, with modulation index of 2 by a 50 Hz sine:
+    ///
+    /// ```
+    /// use wavegen::{sine, Error, PeriodicFunction};
+    ///
+    /// let pm: PeriodicFunction<f32> = PeriodicFunction::pm(1000., sine!(50.), 2.).unwrap();
+    ///
+    /// assert_eq!(
+    ///     Some(Error::InvalidFrequency),
+    ///     PeriodicFunction::<f32>::pm(f32::NAN, sine!(50.), 2.).err()
+    /// );
+    /// ```
+    pub fn pm(
+        carrier_frequency: impl Into<P>,
+        modulator: Self,
+        deviation: impl Into<P>,
+    ) -> Result<Self, Error> {
+        let carrier_frequency = finite(carrier_frequency.into(), Error::InvalidFrequency)?;
+        let deviation = finite(deviation.into(), Error::InvalidFrequency)?;
+        let kind = derived_kind(&[&modulator], |mut kinds| Kind::Pm {
+            carrier_frequency,
+            modulator: Box::new(kinds.remove(0)),
+            deviation,
+        });
+
+        Ok(Self::derived(
+            kind,
+            None,
+            vec![modulator],
+            move |mut operands| {
+                let modulator = operands.remove(0);
+
+                move |t| {
+                    let radians = P::two() * P::PI() * (carrier_frequency * t).fract();
+
+                    (radians + deviation * modulator(t)).sin()
+                }
+            },
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        chirp, dc_bias, sine, square, triangle, BandLimit, Error, Kind, PeriodicFunction, Waveform,
+    };
+    use alloc::{boxed::Box, vec, vec::Vec};
+    use core::f64::consts::PI;
+    use float_cmp::approx_eq;
+    use paste::paste;
+
+    const TIMES: [f64; 7] = [0.0, 0.013, 0.25, 0.4, 0.71, 3.3, 1234.567];
+
+    #[test]
+    fn am_is_applied_pointwise() {
+        let carrier: PeriodicFunction<f64> = sine!(100.);
+        let modulator: PeriodicFunction<f64> = triangle!(3.);
+        let am = PeriodicFunction::am(sine!(100.), triangle!(3.), 0.7);
+
+        for t in TIMES {
+            let expected = carrier.sample(t) * (1.0 + 0.7 * modulator.sample(t));
+
+            assert!(approx_eq!(f64, expected, am.sample(t)));
+        }
+    }
+
+    #[test]
+    fn am_keeps_common_frequency() {
+        let am = PeriodicFunction::<f64>::am(sine!(10.), square!(10.), 0.5);
+
+        assert_eq!(Some(10.0), am.frequency);
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn fm_by_sine_matches_closed_form() {
+        let (fc, fm, deviation) = (1000.0, 7.0, 150.0);
+        let f = PeriodicFunction::<f64>::fm(fc, sine!(fm), deviation).unwrap();
+
+        for t in TIMES {
+            let cycles = fc * t + deviation * (1.0 - (2.0 * PI * fm * t).cos()) / (2.0 * PI * fm);
+            let expected = (2.0 * PI * cycles).sin();
+
+            assert!(
+                approx_eq!(f64, expected, f.sample(t), epsilon = 1e-6),
+                "f({}) = {}, expected {}",
+                t,
+                f.sample(t),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    #[allow(clippy::cast_precision_loss, clippy::unwrap_used)]
+    fn fm_by_square_has_alternating_frequency() {
+        let f = PeriodicFunction::<f64>::fm(1000.0, square!(1.), 200.0).unwrap();
+
+        // 1200 Hz for the first half of the modulator period, 800 Hz for the second
+        for (t, expected) in [
+            (0.25, 1200.0),
+            (0.75, 800.0),
+            (100.25, 1200.0),
+            (100.75, 800.0),
+        ] {
+            let crossings = (0..10_000)
+                .map(|i| f.sample(t - 0.05 + f64::from(i) * 1e-5))
+                .collect::<Vec<_>>()
+                .windows(2)
+                .filter(|w| w[0].signum() != w[1].signum())
+                .count();
+
+            assert!(approx_eq!(
+                f64,
+                crossings as f64 / 0.2,
+                expected,
+                epsilon = 10.0
+            ));
+        }
+    }
+
+    macro_rules! test_fm_continuity {
+        ($($name:ident: $modulator:expr)*) => {
+            $(
+                paste! {
+                    #[test]
+                    #[allow(clippy::unwrap_used)]
+                    fn [<fm_by_ $name _is_continuous>]() {
+                        let f = PeriodicFunction::<f64>::fm(100.0, $modulator, 50.0).unwrap();
+                        // maximum slope of a unit sine at 150 Hz
+                        let step = 1e-6;
+                        let max_change = 2.0 * PI * 150.0 * step * 1.01;
+
+                        for t in [0.5, 1.0, 1.5, 2.0, 10.0, 10.5] {
+                            let before = f.sample(t - step / 2.0);
+                            let after = f.sample(t + step / 2.0);
+
+                            assert!((before - after).abs() < max_change, "discontinuity at {}", t);
+                        }
+                    }
+                }
+            )*
+        };
+    }
+
+    test_fm_continuity! {
+        sine: sine!(1.)
+        square: square!(1.)
+        triangle: triangle!(1.)
+        band_limited_square: PeriodicFunction::band_limited_square(1., 1., 0., BandLimit::Additive)
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn fm_without_deviation_is_sine() {
+        let sine: PeriodicFunction<f64> = sine!(440.);
+        let fm = PeriodicFunction::<f64>::fm(440.0, square!(3.), 0.0).unwrap();
+
+        for t in TIMES {
+            assert!(approx_eq!(
+                f64,
+                sine.sample(t),
+                fm.sample(t),
+                epsilon = 1e-6
+            ));
+        }
+    }
+
+    #[test]
+    fn fm_by_aperiodic_modulator_fails() {
+        for modulator in [
+            PeriodicFunction::custom(|t| t),
+            PeriodicFunction::white_noise(10., 1., 0),
+            chirp!(1., 2., 1.),
+            sine!(1.) + sine!(65.),
+        ] {
+            assert_eq!(
+                Some(Error::AperiodicModulator),
+                PeriodicFunction::<f64>::fm(100.0, modulator, 1.0).err()
+            );
+        }
+        assert_eq!(
+            Some(Error::InvalidFrequency),
+            PeriodicFunction::<f64>::fm(f64::NAN, sine!(1.), 1.0).err()
+        );
+    }
+
+    #[test]
+    fn pm_by_non_finite_parameters_fails() {
+        for (carrier_frequency, deviation) in [
+            (f64::NAN, 1.0),
+            (f64::INFINITY, 1.0),
+            (100.0, f64::NAN),
+            (100.0, f64::NEG_INFINITY),
+        ] {
+            assert_eq!(
+                Some(Error::InvalidFrequency),
+                PeriodicFunction::<f64>::pm(carrier_frequency, sine!(1.), deviation).err()
+            );
+        }
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn fm_by_sum_of_harmonics_matches_closed_form() {
+        let modulator = sine!(3.) + sine!(5., 0.5) + dc_bias!(0.2);
+        let f = PeriodicFunction::<f64>::fm(1000.0, modulator, 100.0).unwrap();
+
+        for t in TIMES {
+            let integral = 0.2 * t
+                + (1.0 - (2.0 * PI * 3.0 * t).cos()) / (2.0 * PI * 3.0)
+                + 0.5 * (1.0 - (2.0 * PI * 5.0 * t).cos()) / (2.0 * PI * 5.0);
+            let expected = (2.0 * PI * (1000.0 * t + 100.0 * integral)).sin();
+
+            assert!(
+                approx_eq!(f64, expected, f.sample(t), epsilon = 1e-6),
+                "f({}) = {}, expected {}",
+                t,
+                f.sample(t),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn pm_is_applied_pointwise() {
+        let modulator: PeriodicFunction<f64> = triangle!(3.);
+        let pm = PeriodicFunction::pm(100.0, triangle!(3.), 2.0).unwrap();
+
+        for t in TIMES {
+            let expected = (2.0 * PI * 100.0 * t + 2.0 * modulator.sample(t)).sin();
+
+            assert!(approx_eq!(f64, expected, pm.sample(t), epsilon = 1e-6));
+        }
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used, clippy::float_cmp)]
+    fn modulation_kinds_are_recorded_and_rebuilt() {
+        let functions: [PeriodicFunction<f64>; 3] = [
+            PeriodicFunction::am(sine!(100.), sine!(3.), 0.5),
+            PeriodicFunction::fm(100.0, sine!(3.), 10.0).unwrap(),
+            PeriodicFunction::pm(100.0, sine!(3.), 2.0).unwrap(),
+        ];
+
+        assert_eq!(
+            Kind::Fm {
+                carrier_frequency: 100.0,
+                modulator: Box::new(Kind::Sine {
+                    frequency: 3.0,
+                    amplitude: 1.0,
+                    phase: 0.0
+                }),
+                deviation: 10.0
+            },
+            *functions[1].kind()
+        );
+
+        for f in &functions {
+            let rebuilt = f.kind().build().unwrap();
+
+            assert_eq!(f.kind(), rebuilt.kind());
+            assert!(TIMES.iter().all(|&t| f.sample(t) == rebuilt.sample(t)));
+        }
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp, clippy::unwrap_used)]
+    fn band_limited_modulator_is_bound() {
+        let modulator =
+            || PeriodicFunction::band_limited_square(1000., 1., 0., BandLimit::PolyBlep);
+        let unbound = PeriodicFunction::<f64>::fm(5000.0, modulator(), 1000.0).unwrap();
+        let bound =
+            PeriodicFunction::<f64>::fm(5000.0, modulator().bound(44100.0), 1000.0).unwrap();
+        let wf = Waveform::<f64, f64>::with_components(
+            44100.0,
+            vec![PeriodicFunction::fm(5000.0, modulator(), 1000.0).unwrap()],
+        );
+        let times = (0..441).map(|i| f64::from(i) / 44100.0);
+
+        assert!(wf
+            .iter()
+            .zip(times.clone())
+            .all(|(y, t)| approx_eq!(f64, y, bound.sample(t))));
+        assert!(times.clone().any(|t| bound.sample(t) != unbound.sample(t)));
+    }
+}
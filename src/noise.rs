@@ -0,0 +1,387 @@
+//! Seeded noise builders.
+//!
+//! All the noise functions are counter-based: the noise is drawn at a fixed `rate` (usually equal to the [`Waveform`]'s
+//! sample rate) and every drawn value is a pure function of the `seed` and the index of the draw. This makes them
+//! fully reproducible, independent of how many times (or in which order) they are sampled.
+//!
+//! [`Waveform`]: crate::Waveform
+
+use crate::{Kind, NoiseColor, PeriodicFunction, Precision, Two};
+use alloc::boxed::Box;
+use num_traits::NumCast;
+
+/// Number of octave rows used by the pink and brown noise generators.
+const ROWS: u64 = 16;
+
+/// Stream used to derive the update offset of each octave row.
+const OFFSET_STREAM: u64 = 1 << 32;
+
+/// Stream used to draw the second uniform variable of the Box-Muller transform.
+const GAUSS_STREAM: u64 = 1 << 33;
+
+#[inline]
+fn mix(x: u64) -> u64 {
+    // SplitMix64 finalizer
+    let mut z = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+
+    z ^ (z >> 31)
+}
+
+#[inline]
+pub(crate) fn hash(seed: u64, stream: u64, index: u64) -> u64 {
+    mix(seed ^ mix(stream ^ mix(index)))
+}
+
+/// Maps a hash to `[0, 1)` range.
+#[inline]
+fn unit<P: Precision>(h: u64) -> P {
+    let mantissa: P = NumCast::from(h >> 11).unwrap_or_else(P::zero);
+    let scale: P = NumCast::from(1u64 << 53).unwrap_or_else(P::one);
+
+    mantissa / scale
+}
+
+/// Maps a hash to `[-1, 1)` range.
+#[inline]
+fn uniform<P: Precision>(h: u64) -> P {
+    P::two() * unit::<P>(h) - P::one()
+}
+
+/// Converts time into the index of a noise draw happening at `rate` Hz.
+#[inline]
+#[allow(clippy::cast_sign_loss)]
+pub(crate) fn draw_index<P: Precision>(t: P, rate: P) -> u64 {
+    (t * rate).round().to_i64().unwrap_or(0) as u64
+}
+
+#[inline]
+fn white<P: Precision>(seed: u64, n: u64) -> P {
+    uniform(hash(seed, 0, n))
+}
+
+/// Sum of octave rows, row `k` being held for `2^k` draws and weighted by `2^(k * exponent)`, normalized to `[-1, 1]`.
+fn octave_rows<P: Precision>(seed: u64, n: u64, exponent: P) -> P {
+    let mut sum = P::zero();
+    let mut norm = P::zero();
+
+    for k in 0..ROWS {
+        let offset = hash(seed, OFFSET_STREAM + k, 0);
+        let held = n.wrapping_add(offset) >> k;
+        let weight = P::two().powf(exponent * NumCast::from(k).unwrap_or_else(P::zero));
+
+        sum = sum + weight * uniform::<P>(hash(seed, k + 1, held));
+        norm = norm + weight;
+    }
+
+    sum / norm
+}
+
+#[inline]
+fn pink<P: Precision>(seed: u64, n: u64) -> P {
+    octave_rows(seed, n, P::zero())
+}
+
+impl<P: Precision> PeriodicFunction<P> {
+    /// Uniform white noise builder.
+    ///
+    /// A new value, uniformly distributed in `[-amplitude, amplitude)` range, is drawn `rate` times per second.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wavegen::{Waveform, PeriodicFunction};
+    ///
+    /// let wf = Waveform::<f32>::with_components(44100.0, vec![PeriodicFunction::white_noise(44100.0, 1.0, 42)]);
+    ///
+    /// assert!(wf.iter().take(1000).all(|y| (-1.0..1.0).contains(&y)));
+    /// ```
+    #[inline]
+    pub fn white_noise(rate: impl Into<P>, amplitude: impl Into<P>, seed: u64) -> Self {
+        let rate = rate.into();
+        let amplitude = amplitude.into();
+
+        Self::new(Box::new(move |t| {
+            amplitude * white::<P>(seed, draw_index(t, rate))
+        }))
+        .with_kind(Kind::Noise {
+            color: NoiseColor::White,
+            rate,
+            amplitude,
+            seed,
+        })
+    }
+
+    /// Gaussian white noise builder.
+    ///
+    /// A new value, normally distributed around `0` with standard deviation of `std_dev`, is drawn `rate` times per second.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wavegen::{Waveform, PeriodicFunction};
+    ///
+    /// let wf = Waveform::<f64, f64>::with_components(1000.0, vec![PeriodicFunction::gaussian_noise(1000.0, 2.0, 42)]);
+    /// let mean = wf.iter().take(10000).sum::<f64>() / 10000.0;
+    ///
+    /// assert!(mean.abs() < 0.1);
+    /// ```
+    #[inline]
+    pub fn gaussian_noise(rate: impl Into<P>, std_dev: impl Into<P>, seed: u64) -> Self {
+        let rate = rate.into();
+        let std_dev = std_dev.into();
+
+        Self::new(Box::new(move |t| {
+            let n = draw_index(t, rate);
+            // Box-Muller transform, with u1 in (0, 1] to keep the logarithm finite
+            let u1 = P::one() - unit::<P>(hash(seed, 0, n));
+            let u2 = unit::<P>(hash(seed, GAUSS_STREAM, n));
+            let radius = (P::two().neg() * u1.ln()).sqrt();
+
+            std_dev * radius * (P::two() * P::PI() * u2).cos()
+        }))
+        .with_kind(Kind::Noise {
+            color: NoiseColor::Gaussian,
+            rate,
+            amplitude: std_dev,
+            seed,
+        })
+    }
+
+    /// Pink noise builder.
+    ///
+    /// The noise has a power spectral density falling by 3 dB per octave (`1/f`), and is generated using
+    /// the Voss-McCartney algorithm. Values are drawn `rate` times per second and stay within `[-amplitude, amplitude]`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let _ = wavegen::PeriodicFunction::<f32>::pink_noise(44100.0, 1.0, 42);
+    /// ```
+    #[inline]
+    pub fn pink_noise(rate: impl Into<P>, amplitude: impl Into<P>, seed: u64) -> Self {
+        let rate = rate.into();
+        let amplitude = amplitude.into();
+
+        Self::new(Box::new(move |t| {
+            amplitude * pink::<P>(seed, draw_index(t, rate))
+        }))
+        .with_kind(Kind::Noise {
+            color: NoiseColor::Pink,
+            rate,
+            amplitude,
+            seed,
+        })
+    }
+
+    /// Brown (red) noise builder.
+    ///
+    /// The noise has a power spectral density falling by 6 dB per octave (`1/f^2`), and is generated using
+    /// Voss-McCartney algorithm with octave rows weighted to match the slope. Values are drawn `rate` times per second
+    /// and stay within `[-amplitude, amplitude]`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let _ = wavegen::PeriodicFunction::<f32>::brown_noise(44100.0, 1.0, 42);
+    /// ```
+    #[inline]
+    pub fn brown_noise(rate: impl Into<P>, amplitude: impl Into<P>, seed: u64) -> Self {
+        let rate = rate.into();
+        let amplitude = amplitude.into();
+        let exponent = P::one() / P::two();
+
+        Self::new(Box::new(move |t| {
+            amplitude * octave_rows::<P>(seed, draw_index(t, rate), exponent)
+        }))
+        .with_kind(Kind::Noise {
+            color: NoiseColor::Brown,
+            rate,
+            amplitude,
+            seed,
+        })
+    }
+
+    /// Blue noise builder.
+    ///
+    /// The noise has a power spectral density rising by 3 dB per octave (`f`), and is generated by differentiating
+    /// pink noise. Values are drawn `rate` times per second and stay within `[-amplitude, amplitude]`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let _ = wavegen::PeriodicFunction::<f32>::blue_noise(44100.0, 1.0, 42);
+    /// ```
+    #[inline]
+    pub fn blue_noise(rate: impl Into<P>, amplitude: impl Into<P>, seed: u64) -> Self {
+        let rate = rate.into();
+        let amplitude = amplitude.into();
+
+        Self::new(Box::new(move |t| {
+            let n = draw_index(t, rate);
+            let diff = pink::<P>(seed, n) - pink::<P>(seed, n.wrapping_sub(1));
+
+            amplitude * diff / P::two()
+        }))
+        .with_kind(Kind::Noise {
+            color: NoiseColor::Blue,
+            rate,
+            amplitude,
+            seed,
+        })
+    }
+
+    /// Violet noise builder.
+    ///
+    /// The noise has a power spectral density rising by 6 dB per octave (`f^2`), and is generated by differentiating
+    /// white noise. Values are drawn `rate` times per second and stay within `[-amplitude, amplitude]`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let _ = wavegen::PeriodicFunction::<f32>::violet_noise(44100.0, 1.0, 42);
+    /// ```
+    #[inline]
+    pub fn violet_noise(rate: impl Into<P>, amplitude: impl Into<P>, seed: u64) -> Self {
+        let rate = rate.into();
+        let amplitude = amplitude.into();
+
+        Self::new(Box::new(move |t| {
+            let n = draw_index(t, rate);
+            let diff = white::<P>(seed, n) - white::<P>(seed, n.wrapping_sub(1));
+
+            amplitude * diff / P::two()
+        }))
+        .with_kind(Kind::Noise {
+            color: NoiseColor::Violet,
+            rate,
+            amplitude,
+            seed,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Waveform;
+    use alloc::{vec, vec::Vec};
+    use float_cmp::approx_eq;
+    use paste::paste;
+    use rustfft::{num_complex::Complex, FftPlanner};
+
+    const N_SAMPLES: usize = 1 << 16;
+    const SEGMENT: usize = 1024;
+
+    fn samples(f: PeriodicFunction<f64>) -> Vec<f64> {
+        Waveform::<f64, f64>::with_components(1000.0, vec![f])
+            .iter()
+            .take(N_SAMPLES)
+            .collect()
+    }
+
+    /// Averaged periodogram, fitted with a line in log-log scale. Returns the slope, in powers of frequency.
+    #[allow(clippy::cast_precision_loss)]
+    fn spectral_slope(samples: &[f64]) -> f64 {
+        let fft = FftPlanner::<f64>::new().plan_fft_forward(SEGMENT);
+        let mut psd = vec![0.0; SEGMENT / 2];
+
+        for chunk in samples.chunks_exact(SEGMENT) {
+            let mut buffer: Vec<_> = chunk.iter().map(|&s| Complex::new(s, 0.0)).collect();
+            fft.process(&mut buffer);
+            for (p, c) in psd.iter_mut().zip(buffer) {
+                *p += c.norm_sqr();
+            }
+        }
+
+        // fit over bins 4..128, that is from 1/256 to 1/8 of the draw rate
+        let points: Vec<(f64, f64)> = (4..128)
+            .map(|i| ((i as f64).log2(), psd[i].log2()))
+            .collect();
+        let n = points.len() as f64;
+        let mean_x = points.iter().map(|p| p.0).sum::<f64>() / n;
+        let mean_y = points.iter().map(|p| p.1).sum::<f64>() / n;
+        let cov = points
+            .iter()
+            .map(|p| (p.0 - mean_x) * (p.1 - mean_y))
+            .sum::<f64>();
+        let var = points.iter().map(|p| (p.0 - mean_x).powi(2)).sum::<f64>();
+
+        cov / var
+    }
+
+    macro_rules! test_noise {
+        ($($name:ident: $builder:ident => $slope:expr)*) => {
+            $(
+                paste! {
+                    #[test]
+                    fn [<$name _noise_has_expected_spectral_slope>]() {
+                        let s = samples(PeriodicFunction::$builder(1000.0, 1.0, 7));
+                        let slope = spectral_slope(&s);
+
+                        assert!(approx_eq!(f64, slope, $slope, epsilon = 0.3), "slope = {}", slope);
+                    }
+
+                    #[test]
+                    fn [<$name _noise_stays_within_amplitude>]() {
+                        let s = samples(PeriodicFunction::$builder(1000.0, 2.5, 7));
+
+                        assert!(s.iter().all(|x| x.abs() <= 2.5));
+                    }
+
+                    #[test]
+                    fn [<$name _noise_is_reproducible>]() {
+                        let a = samples(PeriodicFunction::$builder(1000.0, 1.0, 11));
+                        let b = samples(PeriodicFunction::$builder(1000.0, 1.0, 11));
+                        let c = samples(PeriodicFunction::$builder(1000.0, 1.0, 12));
+
+                        assert_eq!(a, b);
+                        assert_ne!(a, c);
+                    }
+                }
+            )*
+        };
+    }
+
+    test_noise! {
+        white: white_noise => 0.0
+        pink: pink_noise => -1.0
+        brown: brown_noise => -2.0
+        blue: blue_noise => 1.0
+        violet: violet_noise => 2.0
+    }
+
+    #[test]
+    #[allow(clippy::cast_precision_loss)]
+    fn gaussian_noise_has_expected_moments() {
+        let s = samples(PeriodicFunction::gaussian_noise(1000.0, 3.0, 7));
+        let n = s.len() as f64;
+        let mean = s.iter().sum::<f64>() / n;
+        let variance = s.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / n;
+
+        assert!(approx_eq!(f64, mean, 0.0, epsilon = 0.05));
+        assert!(approx_eq!(f64, variance.sqrt(), 3.0, epsilon = 0.05));
+    }
+
+    #[test]
+    fn gaussian_noise_is_white() {
+        let s = samples(PeriodicFunction::gaussian_noise(1000.0, 1.0, 7));
+
+        assert!(approx_eq!(f64, spectral_slope(&s), 0.0, epsilon = 0.3));
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn white_noise_is_held_between_draws() {
+        let wf = Waveform::<f64, f64>::with_components(
+            1000.0,
+            vec![PeriodicFunction::white_noise(250.0, 1.0, 3)],
+        );
+        let s: Vec<f64> = wf.iter().take(1000).collect();
+
+        // with 4 samples per draw, each value is repeated at least 3 times in a row
+        let changes = s.windows(2).filter(|w| w[0] != w[1]).count();
+        assert!(changes <= 250);
+    }
+}
@@ -0,0 +1,420 @@
+//! Nyquist-Shannon rule validation.
+
+use crate::{Interpolation, Precision, SampleType, Two, Waveform};
+use alloc::{vec, vec::Vec};
+use core::f64::consts::PI;
+use num_traits::{Float, NumCast};
+
+/// Smallest supported harmonic magnitude threshold, bounding the number of examined harmonics.
+const MIN_THRESHOLD: f64 = 1e-6;
+
+/// Shape of the spectrum of a built-in periodic function.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Shape<P> {
+    /// Only the fundamental.
+    Tone,
+    Square,
+    Sawtooth,
+    Ramp(P),
+    Pulse(P),
+    UnipolarPulse(P),
+    /// One period given by a table of equally spaced values, interpolated between them.
+    Table(Vec<P>, Interpolation),
+}
+
+/// Spectral description of a built-in periodic function.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Spectrum<P> {
+    pub(crate) fundamental: P,
+    pub(crate) shape: Shape<P>,
+}
+
+impl<P: Precision> Spectrum<P> {
+    /// Magnitude of `n`-th harmonic, relative to the function's amplitude.
+    #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation)]
+    fn magnitude(&self, n: u64) -> P {
+        let n_p: P = NumCast::from(n).unwrap_or_else(P::infinity);
+        let pi = P::PI();
+        let four = P::two() * P::two();
+
+        match self.shape {
+            Shape::Tone if n == 1 => P::one(),
+            Shape::Square if n % 2 == 1 => four / (pi * n_p),
+            Shape::Tone | Shape::Square => P::zero(),
+            Shape::Ramp(s) if s > P::zero() && s < P::one() => {
+                P::two() * (n_p * pi * s).sin().abs() / (n_p * n_p * pi * pi * s * (P::one() - s))
+            }
+            Shape::Sawtooth | Shape::Ramp(_) => P::two() / (pi * n_p),
+            Shape::Pulse(d) => four * (n_p * pi * d).sin().abs() / (pi * n_p),
+            Shape::UnipolarPulse(d) => P::two() * (n_p * pi * d).sin().abs() / (pi * n_p),
+            Shape::Table(ref values, interpolation) => {
+                let harmonics = table_harmonics(values);
+                let len = (harmonics.len() as u64).max(1);
+                let magnitude = harmonics.get((n % len) as usize).copied().unwrap_or(0.0)
+                    * response(interpolation, n as f64 / len as f64);
+
+                NumCast::from(magnitude).unwrap_or_else(P::nan)
+            }
+        }
+    }
+
+    /// Lowest harmonic above `nyquist` with magnitude of at least `threshold`, along with the magnitude.
+    fn alias(&self, nyquist: P, threshold: P) -> Option<(u64, P)> {
+        let fundamental = self.fundamental.abs();
+        if fundamental <= P::zero() {
+            return None;
+        }
+
+        let first = (nyquist / fundamental)
+            .floor()
+            .to_u64()
+            .unwrap_or(u64::MAX)
+            .saturating_add(1);
+
+        if let Shape::Table(ref values, interpolation) = self.shape {
+            return table_alias(values, interpolation, first, threshold);
+        }
+
+        // all the other shapes are bound by 4 / (PI * n) envelope
+        let last = (P::two() * P::two() / (P::PI() * threshold))
+            .floor()
+            .to_u64()
+            .unwrap_or(u64::MAX)
+            .max(1);
+
+        (first..=last)
+            .map(|n| (n, self.magnitude(n)))
+            .find(|(_, magnitude)| *magnitude > P::zero() && *magnitude >= threshold)
+    }
+}
+
+/// Magnitudes of the harmonics of one period given by `values`, relative to its amplitude, before interpolation.
+///
+/// This is the discrete Fourier transform of `values`, so the `k`-th magnitude is shared by all the harmonics `n` with
+/// `n % len == k`, until the interpolation between the values weights them with its frequency response.
+fn table_harmonics<P: Precision>(values: &[P]) -> Vec<f64> {
+    let len = values.len();
+    let scale: f64 = NumCast::from(len).unwrap_or(f64::NAN);
+    let twiddles: Vec<(f64, f64)> = (0..len)
+        .map(|i| {
+            let i: f64 = NumCast::from(i).unwrap_or(f64::NAN);
+
+            Float::sin_cos(2.0 * PI * i / scale)
+        })
+        .collect();
+
+    (0..len)
+        .map(|k| {
+            let mut i = 0;
+            let (re, im) = values.iter().fold((0.0, 0.0), |(re, im), x| {
+                let x = x.to_f64().unwrap_or(f64::NAN);
+                let (sin, cos) = twiddles[i];
+                i = (i + k) % len;
+
+                (re + x * cos, im - x * sin)
+            });
+
+            2.0 * Float::hypot(re, im) / scale
+        })
+        .collect()
+}
+
+/// Magnitude of the frequency response of `interpolation` at `x` cycles per table value.
+fn response(interpolation: Interpolation, x: f64) -> f64 {
+    let sinc = |x: f64| {
+        if x == 0.0 {
+            1.0
+        } else {
+            Float::sin(PI * x) / (PI * x)
+        }
+    };
+
+    match interpolation {
+        Interpolation::None => Float::abs(sinc(x)),
+        Interpolation::Linear => sinc(x) * sinc(x),
+        Interpolation::Cubic => {
+            Float::abs(sinc(x) * sinc(x) * (3.0 * sinc(x) * sinc(x) - 2.0 * sinc(2.0 * x)))
+        }
+    }
+}
+
+/// Upper bound of [`response`] at `x`, and at `x + 1`, `x + 2` and so on, as `|sin(PI * x)|` is the same for all of
+/// them.
+fn response_bound(interpolation: Interpolation, x: f64) -> f64 {
+    let sinc = Float::abs(Float::sin(PI * x)) / (PI * x);
+    let sinc_2 = Float::abs(Float::sin(2.0 * PI * x)) / (2.0 * PI * x);
+
+    match interpolation {
+        Interpolation::None => sinc,
+        Interpolation::Linear => sinc * sinc,
+        Interpolation::Cubic => sinc * sinc * (3.0 * sinc * sinc + 2.0 * sinc_2),
+    }
+}
+
+/// Lowest harmonic, from `first` on, of one period given by `values` and `interpolation`, with magnitude of at least
+/// `threshold`, along with the magnitude.
+///
+/// Only the lowest harmonic of each of the `len` magnitudes of [`table_harmonics`] has to be checked, and the higher ones
+/// only while their [`response_bound`] is above `threshold`.
+#[allow(clippy::cast_precision_loss)]
+fn table_alias<P: Precision>(
+    values: &[P],
+    interpolation: Interpolation,
+    first: u64,
+    threshold: P,
+) -> Option<(u64, P)> {
+    let threshold = threshold.to_f64()?;
+    let harmonics = table_harmonics(values);
+    let len = harmonics.len() as u64;
+
+    harmonics
+        .iter()
+        .zip(0..len)
+        .filter(|(magnitude, _)| magnitude.is_finite() && **magnitude > 0.0)
+        .filter_map(|(&magnitude, k)| {
+            let mut n = first.checked_add((k + len - first % len) % len)?;
+
+            loop {
+                let x = n as f64 / len as f64;
+                if magnitude * response_bound(interpolation, x) < threshold {
+                    return None;
+                }
+
+                let alias = magnitude * response(interpolation, x);
+                if alias >= threshold {
+                    return Some((n, alias));
+                }
+
+                n = n.checked_add(len)?;
+            }
+        })
+        .min_by_key(|&(n, _)| n)
+        .map(|(n, magnitude)| (n, NumCast::from(magnitude).unwrap_or_else(P::nan)))
+}
+
+/// Single component of a [`Waveform`] producing aliased output.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Alias<P> {
+    /// Index of the component, as in [`Waveform::components`].
+    pub component: usize,
+    /// Number of the lowest offending harmonic, `1` being the fundamental.
+    pub harmonic: u64,
+    /// Frequency of the offending harmonic.
+    pub frequency: P,
+    /// Magnitude of the offending harmonic, relative to the component's amplitude.
+    pub magnitude: P,
+}
+
+/// Report of [`Waveform`] components violating the Nyquist-Shannon rule, as returned by [`Waveform::validate`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct AliasingReport<P> {
+    /// Nyquist frequency, that is half of the sample rate.
+    pub nyquist: P,
+    /// Components with any harmonic of significant magnitude above the Nyquist frequency.
+    pub aliases: Vec<Alias<P>>,
+}
+
+impl<P> AliasingReport<P> {
+    /// Returns `true` if no aliasing component was found.
+    pub fn is_ok(&self) -> bool {
+        self.aliases.is_empty()
+    }
+}
+
+impl<T: SampleType, P: Precision> Waveform<T, P> {
+    /// Checks the components of this [`Waveform`] against the Nyquist-Shannon rule.
+    ///
+    /// Every built-in periodic function is checked for harmonics above the Nyquist frequency with magnitude, relative to
+    /// the function's amplitude, of at least `threshold`. Pure tones (sine, chirp) only have the fundamental, while the
+    /// square, sawtooth, triangle, ramp and pulse functions have infinite series of harmonics. Thresholds below `1e-6`
+    /// are treated as `1e-6`.
+    ///
+    /// Wavetables are checked against the harmonics of their table, including the images of them left by the
+    /// interpolation between its values. Custom functions, noise and stateful components are not checked, as their
+    /// spectrum is unknown.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wavegen::{Waveform, sine, square};
+    ///
+    /// let wf = Waveform::<f32>::with_components(900.0, vec![sine!(100.), square!(100.)]);
+    ///
+    /// // Fundamentals are fine...
+    /// assert!(wf.validate(1.0).is_ok());
+    ///
+    /// // ...but the 5th harmonic of the square, of 4 / (5 * PI) magnitude, is aliased.
+    /// let report = wf.validate(0.1);
+    /// assert_eq!(1, report.aliases.len());
+    /// assert_eq!(1, report.aliases[0].component);
+    /// assert_eq!(5, report.aliases[0].harmonic);
+    /// assert_eq!(500.0, report.aliases[0].frequency);
+    /// ```
+    pub fn validate(&self, threshold: impl Into<P>) -> AliasingReport<P> {
+        let min_threshold: P = NumCast::from(MIN_THRESHOLD).unwrap_or_else(P::epsilon);
+        let threshold = threshold.into().max(min_threshold);
+        let nyquist = self.sample_rate / P::two();
+        let mut report = AliasingReport {
+            nyquist,
+            aliases: vec![],
+        };
+
+        for (component, spectrum) in self
+            .components
+            .iter()
+            .enumerate()
+            .filter_map(|(i, c)| c.spectrum.as_ref().map(|s| (i, s)))
+        {
+            if let Some((harmonic, magnitude)) = spectrum.alias(nyquist, threshold) {
+                report.aliases.push(Alias {
+                    component,
+                    harmonic,
+                    frequency: spectrum.fundamental.abs()
+                        * NumCast::from(harmonic).unwrap_or_else(P::infinity),
+                    magnitude,
+                });
+            }
+        }
+
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        dc_bias, pulse, ramp, sawtooth, sine, square, triangle, unipolar_pulse, PeriodicFunction,
+    };
+    use float_cmp::approx_eq;
+    use paste::paste;
+
+    macro_rules! test_magnitude {
+        ($($name:ident: $shape:expr => [$(($n:expr, $magnitude:expr)),*])*) => {
+            $(
+                paste! {
+                    #[test]
+                    fn [<$name _harmonic_magnitudes>]() {
+                        let spectrum = Spectrum::<f64> { fundamental: 1.0, shape: $shape };
+
+                        $(
+                            assert!(approx_eq!(f64, spectrum.magnitude($n), $magnitude, epsilon = 1e-9));
+                        )*
+                    }
+                }
+            )*
+        };
+    }
+
+    test_magnitude! {
+        tone: Shape::Tone => [(1, 1.0), (2, 0.0), (3, 0.0)]
+        square: Shape::Square => [(1, 4.0 / core::f64::consts::PI), (2, 0.0), (3, 4.0 / (3.0 * core::f64::consts::PI))]
+        sawtooth: Shape::Sawtooth => [(1, 2.0 / core::f64::consts::PI), (4, 0.5 / core::f64::consts::PI)]
+        triangle: Shape::Ramp(0.5) => [(1, 8.0 / (core::f64::consts::PI * core::f64::consts::PI)), (2, 0.0), (3, 8.0 / (9.0 * core::f64::consts::PI * core::f64::consts::PI))]
+        falling_sawtooth: Shape::Ramp(0.0) => [(1, 2.0 / core::f64::consts::PI), (2, 1.0 / core::f64::consts::PI)]
+        half_duty_pulse: Shape::Pulse(0.5) => [(1, 4.0 / core::f64::consts::PI), (2, 0.0), (3, 4.0 / (3.0 * core::f64::consts::PI))]
+        quarter_duty_unipolar_pulse: Shape::UnipolarPulse(0.25) => [(4, 0.0), (2, 1.0 / core::f64::consts::PI)]
+        held_table: Shape::Table(vec![1.0, -1.0], Interpolation::None) => [(1, 4.0 / core::f64::consts::PI), (2, 0.0), (3, 4.0 / (3.0 * core::f64::consts::PI))]
+        linear_table: Shape::Table(vec![0.5, 0.5, 0.5], Interpolation::Linear) => [(1, 0.0), (3, 0.0), (4, 0.0)]
+    }
+
+    #[test]
+    fn sine_below_nyquist_is_ok() {
+        let wf = Waveform::<f64, f64>::with_components(100.0, vec![sine!(49.), sine!(-50.)]);
+
+        assert!(wf.validate(1e-9).is_ok());
+    }
+
+    #[test]
+    fn sine_above_nyquist_is_reported() {
+        let wf = Waveform::<f64, f64>::with_components(100.0, vec![sine!(10.), sine!(60.)]);
+        let report = wf.validate(0.5);
+
+        assert_eq!(
+            report,
+            AliasingReport {
+                nyquist: 50.0,
+                aliases: vec![Alias {
+                    component: 1,
+                    harmonic: 1,
+                    frequency: 60.0,
+                    magnitude: 1.0
+                }]
+            }
+        );
+    }
+
+    #[test]
+    fn harmonics_below_threshold_are_ignored() {
+        let wf = Waveform::<f64, f64>::with_components(44100.0, vec![triangle!(2000.)]);
+
+        // 13th harmonic is the first odd one above Nyquist, its magnitude is 8 / (169 * PI^2) ~= 0.0048
+        assert!(wf.validate(0.005).is_ok());
+        assert_eq!(13, wf.validate(0.004).aliases[0].harmonic);
+    }
+
+    #[test]
+    fn zero_magnitude_harmonics_are_skipped() {
+        let wf = Waveform::<f64, f64>::with_components(100.0, vec![square!(25.)]);
+
+        // 2nd harmonic is exactly at Nyquist, 3rd is the first odd one above
+        assert_eq!(3, wf.validate(0.01).aliases[0].harmonic);
+    }
+
+    #[test]
+    fn unknown_spectra_are_not_reported() {
+        let mut wf = Waveform::<f64, f64>::with_components(
+            100.0,
+            vec![
+                dc_bias!(1.),
+                PeriodicFunction::custom(|t: f64| (t * 1000.0).sin()),
+                PeriodicFunction::white_noise(1000.0, 1.0, 0),
+            ],
+        );
+        wf.add_component(sawtooth!(10.));
+
+        let report = wf.validate(0.01);
+
+        assert_eq!(1, report.aliases.len());
+        assert_eq!(3, report.aliases[0].component);
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn chirp_is_checked_against_its_highest_frequency() {
+        let wf = Waveform::<f64, f64>::with_components(
+            1000.0,
+            vec![PeriodicFunction::chirp(
+                900.0,
+                100.0,
+                1.0,
+                1.0,
+                0.0,
+                crate::Sweep::Linear,
+                crate::AfterSweep::Hold,
+            )],
+        );
+
+        assert_eq!(900.0, wf.validate(0.5).aliases[0].frequency);
+    }
+
+    #[test]
+    fn all_periodic_builders_have_spectrum() {
+        let wf = Waveform::<f64, f64>::with_components(
+            10.0,
+            vec![
+                sine!(100.),
+                square!(100.),
+                sawtooth!(100.),
+                triangle!(100.),
+                ramp!(100., 1., 0., 0.3),
+                pulse!(100., 1., 0., 0.3),
+                unipolar_pulse!(100., 1., 0., 0.3),
+                PeriodicFunction::dds(100., 1., 0., crate::Dds::new(32, 12, 14)),
+                PeriodicFunction::wavetable(100., 1., 0., vec![0., 1.], Interpolation::Linear),
+            ],
+        );
+
+        assert_eq!(9, wf.validate(0.01).aliases.len());
+    }
+}
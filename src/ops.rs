@@ -0,0 +1,519 @@
+//! Arithmetic on periodic functions.
+
+use crate::{Error, Kind, PeriodicFunction, Precision, SampleType, Two, Waveform};
+use alloc::{boxed::Box, sync::Arc, vec, vec::Vec};
+use core::convert::TryFrom;
+use core::ops::{Add, Mul, Neg, Sub};
+use num_traits::{Float, NumCast};
+
+/// Kind of a function derived from `operands`, which is [`Kind::Custom`] if any of them is custom.
+pub(crate) fn derived_kind<P: Precision>(
+    operands: &[&PeriodicFunction<P>],
+    kind: impl FnOnce(Vec<Kind<P>>) -> Kind<P>,
+) -> Kind<P> {
+    if operands.iter().any(|f| *f.kind() == Kind::Custom) {
+        Kind::Custom
+    } else {
+        kind(operands.iter().map(|f| f.kind().clone()).collect())
+    }
+}
+
+/// Function of time sampled by an operand of a derived function, shared by its unbound and bound versions.
+pub(crate) type Operand<P> = Arc<dyn Fn(P) -> P + Send + Sync>;
+
+/// Highest harmonic of a common frequency, at which the operands are still taken as harmonics of it.
+const MAX_HARMONIC: u32 = 64;
+
+/// Common frequency of `operands`, that is the highest frequency they all are harmonics of, if there is one.
+///
+/// Constant operands fit any frequency, so they are skipped. Only harmonics up to [`MAX_HARMONIC`] are recognised,
+/// which keeps the common period short compared to the periods of the operands.
+pub(crate) fn common_frequency<P: Precision>(operands: &[&PeriodicFunction<P>]) -> Option<P> {
+    let frequencies = operands
+        .iter()
+        .filter(|f| !matches!(f.kind(), Kind::DcBias { .. }))
+        .map(|f| f.frequency)
+        .collect::<Option<Vec<_>>>()?;
+    let first = *frequencies.first()?;
+    let highest = frequencies.iter().fold(first, |highest, f| highest.max(*f));
+    let common = frequencies
+        .iter()
+        .try_fold(first, |common, f| fundamental(common, *f))?;
+
+    if highest / common <= NumCast::from(MAX_HARMONIC)? {
+        Some(common)
+    } else {
+        None
+    }
+}
+
+/// Highest frequency both `a` and `b` are harmonics of, if `a` is one of its first [`MAX_HARMONIC`] harmonics.
+fn fundamental<P: Precision>(a: P, b: P) -> Option<P> {
+    let ratio = b / a;
+    // both frequencies are rounded, so their ratio is only known to a few units in the last place
+    let tolerance = P::epsilon() * P::two() * P::two();
+
+    (1..=MAX_HARMONIC).find_map(|n| {
+        let n: P = NumCast::from(n)?;
+        let harmonic = ratio * n;
+        let m = harmonic.round();
+
+        if m >= P::one() && (harmonic - m).abs() <= tolerance * harmonic {
+            Some(a / n)
+        } else {
+            None
+        }
+    })
+}
+
+impl<P: Precision> PeriodicFunction<P> {
+    /// Builds a function with given `kind` and `frequency`, derived from `operands` by `build`.
+    ///
+    /// `build` gets the functions of time the operands sample. If any of the operands depends on the sample rate, the
+    /// derived function gets built again once the sample rate is known, from the operands bound to it, so that each of
+    /// them, custom ones included, is sampled the way it would be on its own.
+    pub(crate) fn derived<B, F>(
+        kind: Kind<P>,
+        frequency: Option<P>,
+        operands: Vec<Self>,
+        build: B,
+    ) -> Self
+    where
+        B: Fn(Vec<Operand<P>>) -> F + Send + Sync + 'static,
+        F: Fn(P) -> P + Send + Sync + 'static,
+    {
+        let (inners, binds): (Vec<Operand<P>>, Vec<_>) = operands
+            .into_iter()
+            .map(|f| (Operand::from(f.inner), f.bind))
+            .unzip();
+        let mut derived = Self::new(Box::new(build(inners.clone())));
+        derived.frequency = frequency;
+
+        if binds.iter().any(Option::is_some) {
+            derived = derived.with_bind(move |sample_rate| {
+                build(
+                    inners
+                        .iter()
+                        .zip(&binds)
+                        .map(|(inner, bind)| match bind {
+                            Some(bind) => Operand::from(bind(sample_rate)),
+                            None => Arc::clone(inner),
+                        })
+                        .collect(),
+                )
+            });
+        }
+
+        derived.with_kind(kind)
+    }
+
+    /// Builds a function of `self`, keeping its frequency.
+    fn map<F>(self, kind: impl FnOnce(Box<Kind<P>>) -> Kind<P>, f: F) -> Self
+    where
+        F: Fn(P) -> P + Clone + Send + Sync + 'static,
+    {
+        let kind = derived_kind(&[&self], |mut kinds| kind(Box::new(kinds.remove(0))));
+        let frequency = self.frequency;
+
+        Self::derived(kind, frequency, vec![self], move |mut operands| {
+            let function = operands.remove(0);
+            let f = f.clone();
+
+            move |t| f(function(t))
+        })
+    }
+
+    /// Composes two functions, so that the result is `self(inner(t))`.
+    ///
+    /// # Examples
+    ///
+    /// Sine with phase modulated by a slow triangle:
+    ///
+    /// ```
+    /// use wavegen::{sine, triangle, PeriodicFunction};
+    ///
+    /// let warp: PeriodicFunction<f32> = triangle!(0.5, 2.) + PeriodicFunction::custom(|t| t);
+    /// let _ = sine!(440.).compose(warp);
+    /// ```
+    #[must_use]
+    pub fn compose(self, inner: Self) -> Self {
+        let kind = derived_kind(&[&self, &inner], |mut kinds| {
+            let inner = Box::new(kinds.remove(1));
+            let outer = Box::new(kinds.remove(0));
+
+            Kind::Compose { outer, inner }
+        });
+
+        Self::derived(kind, None, vec![self, inner], |mut operands| {
+            let inner = operands.remove(1);
+            let outer = operands.remove(0);
+
+            move |t| outer(inner(t))
+        })
+    }
+
+    /// Absolute value of the function.
+    ///
+    /// # Examples
+    ///
+    /// Full-wave rectified sine:
+    ///
+    /// ```
+    /// use wavegen::{sine, PeriodicFunction};
+    ///
+    /// let rectified: PeriodicFunction<f32> = sine!(50.).abs();
+    ///
+    /// assert_eq!(1.0, rectified.sample(0.015));
+    /// ```
+    #[must_use]
+    pub fn abs(self) -> Self {
+        self.map(Kind::Abs, Float::abs)
+    }
+
+    /// Clamps the function to `[min, max]` range.
+    ///
+    /// # Examples
+    ///
+    /// Clipped sine:
+    ///
+    /// ```
+    /// use wavegen::{sine, PeriodicFunction};
+    ///
+    /// let clipped: PeriodicFunction<f32> = sine!(50.).clamp(-0.5, 0.5);
+    ///
+    /// assert_eq!(0.5, clipped.sample(0.005));
+    /// ```
+    #[must_use]
+    pub fn clamp(self, min: impl Into<P>, max: impl Into<P>) -> Self {
+        let min = min.into();
+        let max = max.into();
+
+        self.map(
+            |function| Kind::Clamp { function, min, max },
+            move |x| x.max(min).min(max),
+        )
+    }
+
+    /// Raises the function to the power of `exponent`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wavegen::{sine, PeriodicFunction};
+    ///
+    /// let squared: PeriodicFunction<f32> = sine!(50.).powf(2.);
+    ///
+    /// assert!(squared.sample(0.001) >= 0.0);
+    /// ```
+    #[must_use]
+    pub fn powf(self, exponent: impl Into<P>) -> Self {
+        let exponent = exponent.into();
+
+        self.map(
+            |function| Kind::Powf { function, exponent },
+            move |x| x.powf(exponent),
+        )
+    }
+
+    /// Sums up `functions` into a single one.
+    fn sum(functions: Vec<Self>) -> Self {
+        let operands = functions.iter().collect::<Vec<_>>();
+        let kind = derived_kind(&operands, Kind::Sum);
+        let frequency = common_frequency(&operands);
+
+        Self::derived(kind, frequency, functions, |operands| {
+            move |t| operands.iter().map(|f| f(t)).sum()
+        })
+    }
+
+    /// Multiplies `functions` into a single one.
+    fn product(functions: Vec<Self>) -> Self {
+        let operands = functions.iter().collect::<Vec<_>>();
+        let kind = derived_kind(&operands, Kind::Product);
+        let frequency = common_frequency(&operands);
+
+        Self::derived(kind, frequency, functions, |operands| {
+            move |t| operands.iter().fold(P::one(), |product, f| product * f(t))
+        })
+    }
+
+    /// Builds a function from a list of kinds, combined by `combine`, or returns the [`Error`] of the first one that
+    /// fails to build.
+    fn from_kinds(kinds: &[Kind<P>], combine: fn(Vec<Self>) -> Self) -> Result<Self, Error> {
+        kinds
+            .iter()
+            .map(Kind::try_build)
+            .collect::<Result<Vec<_>, _>>()
+            .map(combine)
+    }
+
+    pub(crate) fn sum_of(kinds: &[Kind<P>]) -> Result<Self, Error> {
+        Self::from_kinds(kinds, Self::sum)
+    }
+
+    pub(crate) fn product_of(kinds: &[Kind<P>]) -> Result<Self, Error> {
+        Self::from_kinds(kinds, Self::product)
+    }
+}
+
+impl<P: Precision> Add for PeriodicFunction<P> {
+    type Output = Self;
+
+    /// Sums two functions.
+    fn add(self, rhs: Self) -> Self {
+        Self::sum(vec![self, rhs])
+    }
+}
+
+impl<P: Precision> Sub for PeriodicFunction<P> {
+    type Output = Self;
+
+    /// Subtracts two functions.
+    fn sub(self, rhs: Self) -> Self {
+        Self::sum(vec![self, -rhs])
+    }
+}
+
+impl<P: Precision> Mul for PeriodicFunction<P> {
+    type Output = Self;
+
+    /// Multiplies two functions, as in ring modulation.
+    fn mul(self, rhs: Self) -> Self {
+        Self::product(vec![self, rhs])
+    }
+}
+
+impl<P: Precision> Neg for PeriodicFunction<P> {
+    type Output = Self;
+
+    /// Negates the function.
+    fn neg(self) -> Self {
+        let spectrum = self.spectrum.clone();
+        let mut negated = self.map(Kind::Neg, Neg::neg);
+        negated.spectrum = spectrum;
+
+        negated
+    }
+}
+
+impl<T: SampleType, P: Precision> TryFrom<Waveform<T, P>> for PeriodicFunction<P> {
+    type Error = Error;
+
+    /// Turns the [`Waveform`] into a single [`PeriodicFunction`], being the sum of its components.
+    ///
+    /// The components are rebound to the sample rate of the [`Waveform`] the function is eventually added to.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::StatefulComponent`] if the [`Waveform`] has any stateful components, as they cannot be
+    /// evaluated at arbitrary time.
+    fn try_from(waveform: Waveform<T, P>) -> Result<Self, Error> {
+        if !waveform.stateful_components.is_empty() {
+            return Err(Error::StatefulComponent);
+        }
+
+        Ok(Self::sum(waveform.components))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        dc_bias, sawtooth, sine, square, BandLimit, Error, Kind, PeriodicFunction,
+        StatefulFunction, Waveform,
+    };
+    use alloc::{boxed::Box, vec};
+    use core::convert::TryFrom;
+    use core::ops::{Add, Mul, Sub};
+    use float_cmp::approx_eq;
+    use paste::paste;
+
+    const TIMES: [f64; 6] = [0.0, 0.013, 0.25, 0.4, 0.71, 3.3];
+
+    macro_rules! test_operator {
+        ($($name:ident: $f:expr, $g:expr, |$a:ident, $b:ident| $expected:expr)*) => {
+            $(
+                paste! {
+                    #[test]
+                    fn [<$name _is_applied_pointwise>]() {
+                        let f: PeriodicFunction<f64> = $f;
+                        let g: PeriodicFunction<f64> = $g;
+                        let combined = PeriodicFunction::$name($f, $g);
+
+                        for t in TIMES {
+                            let ($a, $b) = (f.sample(t), g.sample(t));
+
+                            assert!(approx_eq!(f64, $expected, combined.sample(t)));
+                        }
+                    }
+                }
+            )*
+        };
+    }
+
+    test_operator! {
+        add: sine!(1.), square!(0.1), |a, b| a + b
+        sub: sine!(1.), sawtooth!(2.), |a, b| a - b
+        mul: sine!(1.), square!(0.1), |a, b| a * b
+        compose: sine!(1.), sawtooth!(2.), |_a, b| sine!(1.).sample(b)
+    }
+
+    macro_rules! test_map {
+        ($($name:ident: $g:expr, |$a:ident| $expected:expr)*) => {
+            $(
+                paste! {
+                    #[test]
+                    fn [<$name _is_applied_pointwise>]() {
+                        let f: PeriodicFunction<f64> = sine!(1., 2.);
+                        let mapped = $g(sine!(1., 2.));
+
+                        for t in TIMES {
+                            let $a = f.sample(t);
+
+                            assert!(approx_eq!(f64, $expected, mapped.sample(t)));
+                        }
+                    }
+                }
+            )*
+        };
+    }
+
+    test_map! {
+        neg: |f: PeriodicFunction<f64>| -f, |a| -a
+        abs: |f: PeriodicFunction<f64>| f.abs(), |a| a.abs()
+        clamp: |f: PeriodicFunction<f64>| f.clamp(-1.0, 0.5), |a| a.clamp(-1.0, 0.5)
+        powf: |f: PeriodicFunction<f64>| f.powf(3.0), |a| a.powf(3.0)
+    }
+
+    #[test]
+    fn combined_kinds_are_recorded() {
+        let f: PeriodicFunction<f64> = (sine!(1.) * square!(0.1) - dc_bias!(1.)).abs();
+
+        assert_eq!(
+            &Kind::Abs(Box::new(Kind::Sum(vec![
+                Kind::Product(vec![sine!(1.).kind().clone(), square!(0.1).kind().clone()]),
+                Kind::Neg(Box::new(dc_bias!(1.).kind().clone())),
+            ]))),
+            f.kind()
+        );
+    }
+
+    #[test]
+    fn combined_kinds_rebuild_same_function() {
+        let f: PeriodicFunction<f64> =
+            (sine!(1.) * square!(0.1)).compose(sawtooth!(3.).clamp(-0.5, 0.5)) + sine!(2.).powf(2.);
+        let rebuilt = f.kind().build().unwrap_or_else(|| dc_bias!(f64::NAN));
+
+        for t in TIMES {
+            assert!(approx_eq!(f64, f.sample(t), rebuilt.sample(t)));
+        }
+    }
+
+    #[test]
+    fn custom_operand_makes_custom_kind() {
+        let f: PeriodicFunction<f64> = sine!(1.) + PeriodicFunction::custom(|t| t);
+
+        assert_eq!(&Kind::Custom, f.kind());
+        assert_eq!(&Kind::Custom, (-f).kind());
+    }
+
+    #[test]
+    fn band_limited_operands_are_bound_to_sample_rate() {
+        let band_limited = || {
+            PeriodicFunction::<f64>::band_limited_sawtooth(1001.0, 1.0, 0.0, BandLimit::Additive)
+        };
+        let expected = Waveform::<f64, f64>::with_components(44100.0, vec![band_limited()]);
+        let wf = Waveform::<f64, f64>::with_components(
+            44100.0,
+            vec![(band_limited() * dc_bias!(2.)).abs()],
+        );
+
+        for (a, b) in expected.iter().zip(wf.iter()).take(1000) {
+            assert!(approx_eq!(f64, (a * 2.0).abs(), b, epsilon = 1e-9));
+        }
+    }
+
+    #[test]
+    fn band_limited_operands_are_bound_next_to_custom_ones() {
+        let band_limited =
+            || PeriodicFunction::<f64>::band_limited_square(1001.0, 1.0, 0.0, BandLimit::PolyBlep);
+        let expected = Waveform::<f64, f64>::with_components(44100.0, vec![band_limited()]);
+        let wf = Waveform::<f64, f64>::with_components(
+            44100.0,
+            vec![band_limited() + PeriodicFunction::custom(|t| t)],
+        );
+
+        for (i, (a, b)) in expected.iter().zip(wf.iter()).take(1000).enumerate() {
+            let t = f64::from(u32::try_from(i).unwrap_or(0)) / 44100.0;
+
+            assert!(approx_eq!(f64, a + t, b, epsilon = 1e-9), "sample {i}");
+        }
+    }
+
+    #[test]
+    fn waveform_turns_into_function() {
+        let wf = Waveform::<f64, f64>::with_components(100.0, vec![sine!(1.), sawtooth!(2.)]);
+        let expected = Waveform::<f64, f64>::with_components(100.0, vec![sine!(1.), sawtooth!(2.)]);
+        let f = PeriodicFunction::try_from(wf).unwrap_or_else(|_| dc_bias!(f64::NAN));
+
+        for t in TIMES {
+            let sum = expected
+                .components()
+                .iter()
+                .map(|c| c.sample(t))
+                .sum::<f64>();
+
+            assert!(approx_eq!(f64, sum, f.sample(t)));
+        }
+
+        let outer = Waveform::<f64, f64>::with_components(100.0, vec![f * sine!(0.1)]);
+        assert!(outer.iter().take(100).all(f64::is_finite));
+    }
+
+    #[test]
+    fn waveform_with_stateful_components_does_not_turn_into_function() {
+        let mut wf = Waveform::<f64, f64>::new(100.0);
+        wf.add_stateful_component(StatefulFunction::new(|_| |t| t));
+
+        assert_eq!(
+            Some(Error::StatefulComponent),
+            PeriodicFunction::try_from(wf).err()
+        );
+    }
+
+    #[test]
+    fn same_frequency_is_kept() {
+        let f: PeriodicFunction<f32> = sine!(1000.) * -square!(1000.);
+        let g: PeriodicFunction<f32> = sine!(1000.) + square!(10.);
+
+        assert_eq!(Some(1000.0), f.frequency);
+        assert_eq!(None, g.frequency);
+        assert_eq!(Some(1000.0), f.abs().frequency);
+    }
+
+    macro_rules! test_common_frequency {
+        ($($name:ident: $function:expr => $frequency:expr)*) => {
+            $(
+                paste! {
+                    #[test]
+                    fn [<common_frequency_of_ $name>]() {
+                        let f: PeriodicFunction<f32> = $function;
+
+                        assert_eq!($frequency, f.frequency);
+                    }
+                }
+            )*
+        };
+    }
+
+    test_common_frequency! {
+        harmonics: sine!(3.) + sine!(5.) => Some(1.0)
+        fifth: sine!(440.) * square!(660.) => Some(220.0)
+        rounded_frequencies: sine!(0.1) + sine!(0.3) - sine!(0.7) => Some(0.1)
+        offset: sine!(10.) + dc_bias!(1.) => Some(10.0)
+        constants: dc_bias!(1.) + dc_bias!(2.) => None
+        distant_harmonics: sine!(1.) + sine!(65.) => None
+        detuned: sine!(440.) + sine!(440.5) => None
+        custom: sine!(10.) + PeriodicFunction::custom(|t| t) => None
+    }
+}
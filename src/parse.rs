@@ -0,0 +1,853 @@
+//! Text expression parser.
+
+use crate::{
+    AfterSweep, BandLimit, Error, Kind, NoiseColor, PeriodicFunction, Precision, SampleType, Sweep,
+    Waveform,
+};
+use alloc::{vec, vec::Vec};
+use core::fmt;
+use num_traits::NumCast;
+
+/// Reason of a [`ParseError`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ParseErrorKind {
+    /// Character not allowed at this position.
+    UnexpectedCharacter(char),
+    /// Expression ended prematurely.
+    UnexpectedEnd,
+    /// Number literal could not be parsed, or does not fit the precision type as a finite number.
+    InvalidNumber,
+    /// Function name is not known.
+    UnknownFunction,
+    /// Argument name is not known to the function.
+    UnknownArgument,
+    /// Argument was given more than once.
+    DuplicateArgument,
+    /// Required argument was not given.
+    MissingArgument(&'static str),
+    /// Function was given more positional arguments than it has parameters.
+    TooManyArguments,
+    /// Positional argument was given after a named one.
+    PositionalAfterNamed,
+    /// Argument has a value of the wrong type, like a name where a number is expected, or an unknown name.
+    InvalidValue,
+    /// Function parameters were rejected by the builder, positioned at the offending argument if it was given.
+    Invalid(Error),
+}
+
+impl fmt::Display for ParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseErrorKind::UnexpectedCharacter(c) => write!(f, "unexpected character {c:?}"),
+            ParseErrorKind::UnexpectedEnd => write!(f, "unexpected end of expression"),
+            ParseErrorKind::InvalidNumber => write!(f, "invalid number"),
+            ParseErrorKind::UnknownFunction => write!(f, "unknown function"),
+            ParseErrorKind::UnknownArgument => write!(f, "unknown argument"),
+            ParseErrorKind::DuplicateArgument => write!(f, "duplicate argument"),
+            ParseErrorKind::MissingArgument(name) => write!(f, "missing argument `{name}`"),
+            ParseErrorKind::TooManyArguments => write!(f, "too many arguments"),
+            ParseErrorKind::PositionalAfterNamed => {
+                write!(f, "positional argument after a named one")
+            }
+            ParseErrorKind::InvalidValue => write!(f, "invalid argument value"),
+            ParseErrorKind::Invalid(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+/// Error returned by [`Waveform::parse`] and [`PeriodicFunction::parse`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseError {
+    /// Byte offset in the expression, where the error was found.
+    pub position: usize,
+    /// Reason of the error.
+    pub kind: ParseErrorKind,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at position {}", self.kind, self.position)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseError {}
+
+#[derive(Clone, Copy)]
+enum Value<'a> {
+    Number(&'a str),
+    Name(&'a str),
+}
+
+#[derive(Clone, Copy)]
+struct Arg<'a> {
+    name: Option<&'a str>,
+    value: Value<'a>,
+    position: usize,
+}
+
+/// Arguments of a function call, bound to the function's parameters.
+struct Bound<'a> {
+    values: Vec<Option<(Value<'a>, usize)>>,
+    params: &'static [&'static str],
+    position: usize,
+}
+
+impl<'a> Bound<'a> {
+    fn bind(
+        args: &[Arg<'a>],
+        params: &'static [&'static str],
+        position: usize,
+    ) -> Result<Self, ParseError> {
+        let mut values = vec![None; params.len()];
+
+        for (i, arg) in args.iter().enumerate() {
+            let slot = match arg.name {
+                Some(name) => params
+                    .iter()
+                    .position(|p| *p == name)
+                    .ok_or_else(|| error(arg.position, ParseErrorKind::UnknownArgument))?,
+                None if i < params.len() => i,
+                None => return Err(error(arg.position, ParseErrorKind::TooManyArguments)),
+            };
+
+            if values[slot].is_some() {
+                return Err(error(arg.position, ParseErrorKind::DuplicateArgument));
+            }
+            values[slot] = Some((arg.value, arg.position));
+        }
+
+        Ok(Self {
+            values,
+            params,
+            position,
+        })
+    }
+
+    fn get(&self, i: usize) -> Result<(Value<'a>, usize), ParseError> {
+        self.values[i].ok_or_else(|| {
+            error(
+                self.position,
+                ParseErrorKind::MissingArgument(self.params[i]),
+            )
+        })
+    }
+
+    fn number<P: Precision>(&self, i: usize) -> Result<P, ParseError> {
+        match self.get(i)? {
+            (Value::Number(s), position) => s
+                .parse::<f64>()
+                .ok()
+                .and_then(NumCast::from)
+                .filter(|x: &P| x.is_finite())
+                .ok_or_else(|| error(position, ParseErrorKind::InvalidNumber)),
+            (Value::Name(_), position) => Err(error(position, ParseErrorKind::InvalidValue)),
+        }
+    }
+
+    fn number_or<P: Precision>(&self, i: usize, default: P) -> Result<P, ParseError> {
+        if self.values[i].is_some() {
+            self.number(i)
+        } else {
+            Ok(default)
+        }
+    }
+
+    /// Positions `error` of the builder at the first given argument it may be about, or at the function call.
+    fn rejected(&self, e: Error) -> ParseError {
+        let params: &[&str] = match e {
+            Error::InvalidFrequency => &["frequency", "start", "end", "rate"],
+            Error::InvalidAmplitude => &["amplitude", "bias", "std_dev"],
+            Error::InvalidPhase => &["phase"],
+            Error::InvalidDuration => &["duration"],
+            Error::InvalidDuty => &["duty"],
+            Error::InvalidSymmetry => &["symmetry"],
+            _ => &[],
+        };
+        let position = self
+            .params
+            .iter()
+            .zip(&self.values)
+            .filter(|(param, _)| params.contains(param))
+            .find_map(|(_, value)| value.map(|(_, position)| position))
+            .unwrap_or(self.position);
+
+        error(position, ParseErrorKind::Invalid(e))
+    }
+
+    fn seed(&self, i: usize) -> Result<u64, ParseError> {
+        match self.values[i] {
+            None => Ok(0),
+            Some((Value::Number(s), position)) => s
+                .parse()
+                .map_err(|_| error(position, ParseErrorKind::InvalidValue)),
+            Some((Value::Name(_), position)) => Err(error(position, ParseErrorKind::InvalidValue)),
+        }
+    }
+
+    fn name_or<T>(&self, i: usize, default: T, names: &[(&str, T)]) -> Result<T, ParseError>
+    where
+        T: Copy,
+    {
+        match self.values[i] {
+            None => Ok(default),
+            Some((Value::Name(s), position)) => names
+                .iter()
+                .find(|(name, _)| *name == s)
+                .map(|(_, value)| *value)
+                .ok_or_else(|| error(position, ParseErrorKind::InvalidValue)),
+            Some((Value::Number(_), position)) => {
+                Err(error(position, ParseErrorKind::InvalidValue))
+            }
+        }
+    }
+}
+
+#[inline]
+fn error(position: usize, kind: ParseErrorKind) -> ParseError {
+    ParseError { position, kind }
+}
+
+const TONE: &[&str] = &["frequency", "amplitude", "phase"];
+const RAMP: &[&str] = &["frequency", "amplitude", "phase", "symmetry"];
+const PULSE: &[&str] = &["frequency", "amplitude", "phase", "duty"];
+const BAND_LIMITED_TONE: &[&str] = &["frequency", "amplitude", "phase", "method"];
+const BAND_LIMITED_PULSE: &[&str] = &["frequency", "amplitude", "phase", "duty", "method"];
+const CHIRP: &[&str] = &[
+    "start",
+    "end",
+    "duration",
+    "amplitude",
+    "phase",
+    "sweep",
+    "after",
+];
+const NOISE: &[&str] = &["rate", "amplitude", "seed"];
+const GAUSSIAN_NOISE: &[&str] = &["rate", "std_dev", "seed"];
+
+const METHODS: &[(&str, BandLimit)] = &[
+    ("poly_blep", BandLimit::PolyBlep),
+    ("additive", BandLimit::Additive),
+];
+const SWEEPS: &[(&str, Sweep)] = &[
+    ("linear", Sweep::Linear),
+    ("exponential", Sweep::Exponential),
+    ("hyperbolic", Sweep::Hyperbolic),
+];
+const AFTER_SWEEPS: &[(&str, AfterSweep)] = &[
+    ("hold", AfterSweep::Hold),
+    ("repeat", AfterSweep::Repeat),
+    ("ping_pong", AfterSweep::PingPong),
+];
+
+/// Builds function `name` called with `args`, with its amplitude multiplied by `sign`.
+#[allow(clippy::too_many_lines)]
+fn function<P: Precision>(
+    name: &str,
+    args: &[Arg<'_>],
+    sign: P,
+    position: usize,
+) -> Result<PeriodicFunction<P>, ParseError> {
+    let one = P::one();
+    let zero = P::zero();
+    let bind = |params| Bound::bind(args, params, position);
+    let a;
+
+    let kind = match name {
+        "dc" | "dc_bias" => {
+            a = bind(&["bias"])?;
+            Kind::DcBias {
+                bias: sign * a.number(0)?,
+            }
+        }
+        "sine" | "square" | "sawtooth" | "triangle" => {
+            a = bind(TONE)?;
+            let (frequency, amplitude, phase) = (
+                a.number(0)?,
+                sign * a.number_or(1, one)?,
+                a.number_or(2, zero)?,
+            );
+            match name {
+                "sine" => Kind::Sine {
+                    frequency,
+                    amplitude,
+                    phase,
+                },
+                "square" => Kind::Square {
+                    frequency,
+                    amplitude,
+                    phase,
+                },
+                "sawtooth" => Kind::Sawtooth {
+                    frequency,
+                    amplitude,
+                    phase,
+                },
+                _ => Kind::Triangle {
+                    frequency,
+                    amplitude,
+                    phase,
+                },
+            }
+        }
+        "ramp" => {
+            a = bind(RAMP)?;
+            Kind::Ramp {
+                frequency: a.number(0)?,
+                amplitude: sign * a.number_or(1, one)?,
+                phase: a.number_or(2, zero)?,
+                symmetry: a.number(3)?,
+            }
+        }
+        "pulse" | "unipolar_pulse" => {
+            a = bind(PULSE)?;
+            let (frequency, amplitude, phase, duty) = (
+                a.number(0)?,
+                sign * a.number_or(1, one)?,
+                a.number_or(2, zero)?,
+                a.number(3)?,
+            );
+            if name == "pulse" {
+                Kind::Pulse {
+                    frequency,
+                    amplitude,
+                    phase,
+                    duty,
+                }
+            } else {
+                Kind::UnipolarPulse {
+                    frequency,
+                    amplitude,
+                    phase,
+                    duty,
+                }
+            }
+        }
+        "band_limited_sawtooth" | "band_limited_square" => {
+            a = bind(BAND_LIMITED_TONE)?;
+            let (frequency, amplitude, phase, method) = (
+                a.number(0)?,
+                sign * a.number_or(1, one)?,
+                a.number_or(2, zero)?,
+                a.name_or(3, BandLimit::PolyBlep, METHODS)?,
+            );
+            if name == "band_limited_sawtooth" {
+                Kind::BandLimitedSawtooth {
+                    frequency,
+                    amplitude,
+                    phase,
+                    method,
+                }
+            } else {
+                Kind::BandLimitedSquare {
+                    frequency,
+                    amplitude,
+                    phase,
+                    method,
+                }
+            }
+        }
+        "band_limited_pulse" | "band_limited_unipolar_pulse" => {
+            a = bind(BAND_LIMITED_PULSE)?;
+            let (frequency, amplitude, phase, duty, method) = (
+                a.number(0)?,
+                sign * a.number_or(1, one)?,
+                a.number_or(2, zero)?,
+                a.number(3)?,
+                a.name_or(4, BandLimit::PolyBlep, METHODS)?,
+            );
+            if name == "band_limited_pulse" {
+                Kind::BandLimitedPulse {
+                    frequency,
+                    amplitude,
+                    phase,
+                    duty,
+                    method,
+                }
+            } else {
+                Kind::BandLimitedUnipolarPulse {
+                    frequency,
+                    amplitude,
+                    phase,
+                    duty,
+                    method,
+                }
+            }
+        }
+        "chirp" => {
+            a = bind(CHIRP)?;
+            Kind::Chirp {
+                start_frequency: a.number(0)?,
+                end_frequency: a.number(1)?,
+                duration: a.number(2)?,
+                amplitude: sign * a.number_or(3, one)?,
+                phase: a.number_or(4, zero)?,
+                sweep: a.name_or(5, Sweep::Linear, SWEEPS)?,
+                after: a.name_or(6, AfterSweep::Hold, AFTER_SWEEPS)?,
+            }
+        }
+        "white_noise" | "gaussian_noise" | "pink_noise" | "brown_noise" | "blue_noise"
+        | "violet_noise" => {
+            let (color, params) = match name {
+                "white_noise" => (NoiseColor::White, NOISE),
+                "gaussian_noise" => (NoiseColor::Gaussian, GAUSSIAN_NOISE),
+                "pink_noise" => (NoiseColor::Pink, NOISE),
+                "brown_noise" => (NoiseColor::Brown, NOISE),
+                "blue_noise" => (NoiseColor::Blue, NOISE),
+                _ => (NoiseColor::Violet, NOISE),
+            };
+            a = bind(params)?;
+            Kind::Noise {
+                color,
+                rate: a.number(0)?,
+                amplitude: sign * a.number_or(1, one)?,
+                seed: a.seed(2)?,
+            }
+        }
+        _ => return Err(error(position, ParseErrorKind::UnknownFunction)),
+    };
+
+    kind.try_build().map_err(|e| a.rejected(e))
+}
+
+/// Recursive descent parser of sums of function calls.
+struct Parser<'a> {
+    src: &'a str,
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<char> {
+        self.src[self.position..].chars().next()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(c) = self.peek().filter(|c| c.is_whitespace()) {
+            self.position += c.len_utf8();
+        }
+    }
+
+    fn unexpected(&self) -> ParseError {
+        match self.peek() {
+            Some(c) => error(self.position, ParseErrorKind::UnexpectedCharacter(c)),
+            None => error(self.position, ParseErrorKind::UnexpectedEnd),
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), ParseError> {
+        self.skip_whitespace();
+        if self.peek() == Some(c) {
+            self.position += 1;
+            Ok(())
+        } else {
+            Err(self.unexpected())
+        }
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        self.skip_whitespace();
+        if self.peek() == Some(c) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn take_while(&mut self, f: impl Fn(char) -> bool) -> &'a str {
+        let start = self.position;
+        while let Some(c) = self.peek().filter(|c| f(*c)) {
+            self.position += c.len_utf8();
+        }
+
+        &self.src[start..self.position]
+    }
+
+    fn name(&mut self) -> Result<&'a str, ParseError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(c) if c.is_ascii_alphabetic() || c == '_' => {
+                Ok(self.take_while(|c| c.is_ascii_alphanumeric() || c == '_'))
+            }
+            _ => Err(self.unexpected()),
+        }
+    }
+
+    /// Number literal, with optional sign, fraction and exponent.
+    fn number(&mut self) -> Result<&'a str, ParseError> {
+        self.skip_whitespace();
+        let start = self.position;
+        if matches!(self.peek(), Some('+' | '-')) {
+            self.position += 1;
+        }
+
+        let integer = self.take_while(|c| c.is_ascii_digit());
+        let fraction = if self.peek() == Some('.') {
+            self.position += 1;
+            self.take_while(|c| c.is_ascii_digit())
+        } else {
+            ""
+        };
+        if integer.is_empty() && fraction.is_empty() {
+            return Err(error(start, ParseErrorKind::InvalidNumber));
+        }
+
+        if matches!(self.peek(), Some('e' | 'E')) {
+            self.position += 1;
+            if matches!(self.peek(), Some('+' | '-')) {
+                self.position += 1;
+            }
+            if self.take_while(|c| c.is_ascii_digit()).is_empty() {
+                return Err(error(start, ParseErrorKind::InvalidNumber));
+            }
+        }
+
+        Ok(&self.src[start..self.position])
+    }
+
+    fn is_number_start(&mut self) -> bool {
+        self.skip_whitespace();
+        matches!(self.peek(), Some(c) if c.is_ascii_digit() || matches!(c, '.' | '+' | '-'))
+    }
+
+    fn args(&mut self) -> Result<Vec<Arg<'a>>, ParseError> {
+        let mut args: Vec<Arg<'a>> = vec![];
+
+        self.expect('(')?;
+        loop {
+            if self.eat(')') {
+                return Ok(args);
+            }
+
+            self.skip_whitespace();
+            let position = self.position;
+            let arg = if self.is_number_start() {
+                Arg {
+                    name: None,
+                    value: Value::Number(self.number()?),
+                    position,
+                }
+            } else {
+                let first = self.name()?;
+                if self.eat(':') {
+                    self.skip_whitespace();
+                    let value = if self.is_number_start() {
+                        Value::Number(self.number()?)
+                    } else {
+                        Value::Name(self.name()?)
+                    };
+
+                    Arg {
+                        name: Some(first),
+                        value,
+                        position,
+                    }
+                } else {
+                    Arg {
+                        name: None,
+                        value: Value::Name(first),
+                        position,
+                    }
+                }
+            };
+
+            if arg.name.is_none() && args.iter().any(|a| a.name.is_some()) {
+                return Err(error(position, ParseErrorKind::PositionalAfterNamed));
+            }
+            args.push(arg);
+
+            if !self.eat(',') {
+                self.expect(')')?;
+                return Ok(args);
+            }
+        }
+    }
+
+    /// Single term: a number, taken as DC bias, or a function call.
+    fn term<P: Precision>(&mut self, sign: P) -> Result<PeriodicFunction<P>, ParseError> {
+        self.skip_whitespace();
+        let position = self.position;
+
+        if self.is_number_start() {
+            let bias = self.number()?;
+            function(
+                "dc",
+                &[Arg {
+                    name: None,
+                    value: Value::Number(bias),
+                    position,
+                }],
+                sign,
+                position,
+            )
+        } else {
+            let name = self.name()?;
+            let args = self.args()?;
+            function(name, &args, sign, position)
+        }
+    }
+
+    fn sum<P: Precision>(&mut self) -> Result<Vec<PeriodicFunction<P>>, ParseError> {
+        let mut terms = vec![];
+        let mut sign = if self.eat('-') {
+            P::one().neg()
+        } else {
+            P::one()
+        };
+
+        loop {
+            terms.push(self.term(sign)?);
+
+            self.skip_whitespace();
+            sign = match self.peek() {
+                Some('+') => P::one(),
+                Some('-') => P::one().neg(),
+                None => return Ok(terms),
+                Some(_) => return Err(self.unexpected()),
+            };
+            self.position += 1;
+        }
+    }
+}
+
+/// Parses a sum of function calls into components.
+fn parse<P: Precision>(expression: &str) -> Result<Vec<PeriodicFunction<P>>, ParseError> {
+    Parser {
+        src: expression,
+        position: 0,
+    }
+    .sum()
+}
+
+impl<P: Precision> PeriodicFunction<P> {
+    /// Parses a single function call expression, like `sine(50, amplitude: 10)`.
+    ///
+    /// See [`Waveform::parse`] for the syntax.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParseError`] if the expression is not a single valid function call.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wavegen::{Kind, PeriodicFunction};
+    ///
+    /// let f = PeriodicFunction::<f32>::parse("pulse(10, duty: 0.25)").unwrap();
+    ///
+    /// assert_eq!(&Kind::Pulse { frequency: 10., amplitude: 1., phase: 0., duty: 0.25 }, f.kind());
+    /// ```
+    pub fn parse(expression: &str) -> Result<Self, ParseError> {
+        let mut parser = Parser {
+            src: expression,
+            position: 0,
+        };
+        let sign = if parser.eat('-') {
+            P::one().neg()
+        } else {
+            P::one()
+        };
+        let f = parser.term(sign)?;
+
+        parser.skip_whitespace();
+        if parser.peek().is_some() {
+            return Err(parser.unexpected());
+        }
+
+        Ok(f)
+    }
+}
+
+impl<T: SampleType, P: Precision> Waveform<T, P> {
+    /// Parses a text expression into a new [`Waveform`] with given `sample_rate`.
+    ///
+    /// The expression is a sum of terms, separated by `+` or `-`. A term is either a number, taken as DC bias, or a
+    /// call of one of the built-in functions, with arguments named and ordered as in the function's macro or builder:
+    ///
+    /// | function | arguments |
+    /// |---|---|
+    /// | `dc`, `dc_bias` | `bias` |
+    /// | `sine`, `square`, `sawtooth`, `triangle` | `frequency`, \[`amplitude`, \[`phase`\]\] |
+    /// | `ramp` | `frequency`, `amplitude`, `phase`, `symmetry` |
+    /// | `pulse`, `unipolar_pulse` | `frequency`, `amplitude`, `phase`, `duty` |
+    /// | `band_limited_sawtooth`, `band_limited_square` | `frequency`, \[`amplitude`, \[`phase`, \[`method`\]\]\] |
+    /// | `band_limited_pulse`, `band_limited_unipolar_pulse` | `frequency`, `amplitude`, `phase`, `duty`, \[`method`\] |
+    /// | `chirp` | `start`, `end`, `duration`, \[`amplitude`, \[`phase`, \[`sweep`, \[`after`\]\]\]\] |
+    /// | `white_noise`, `pink_noise`, `brown_noise`, `blue_noise`, `violet_noise` | `rate`, \[`amplitude`, \[`seed`\]\] |
+    /// | `gaussian_noise` | `rate`, \[`std_dev`, \[`seed`\]\] |
+    ///
+    /// Arguments can be given positionally, by name, as in `sine(frequency: 50, amplitude: 10)`, or both, with the
+    /// named ones last. Amplitude defaults to `1`, phase and seed to `0`. `method`, `sweep` and `after` take the names
+    /// `poly_blep` or `additive`, `linear`, `exponential` or `hyperbolic`, and `hold`, `repeat` or `ping_pong`.
+    ///
+    /// Subtracted terms have their amplitude negated, so the resulting components have the same [`Kind`] as if they
+    /// were built in code.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ParseError`] with the byte offset of the offending part of the expression, or of its start if
+    /// `sample_rate` is not a finite, positive, non-zero number.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wavegen::{sawtooth, sine, dc_bias, ParseErrorKind, Waveform};
+    ///
+    /// let parsed = Waveform::<f32>::parse(200.0, "sine(50, 10) + sawtooth(20) - dc(5)").unwrap();
+    /// let built = Waveform::<f32>::with_components(200.0, vec![sine!(50., 10.), sawtooth!(20.), dc_bias!(-5.)]);
+    ///
+    /// assert!(parsed.iter().zip(built.iter()).take(100).all(|(a, b)| a == b));
+    ///
+    /// let error = Waveform::<f32>::parse(200.0, "sine(50) + cosine(20)").err().unwrap();
+    /// assert_eq!(11, error.position);
+    /// assert_eq!(ParseErrorKind::UnknownFunction, error.kind);
+    /// ```
+    ///
+    /// [`Kind`]: crate::Kind
+    pub fn parse(sample_rate: impl Into<P>, expression: &str) -> Result<Self, ParseError> {
+        let components = parse(expression)?;
+
+        Self::try_new(sample_rate)
+            .map(|mut wf| {
+                for component in components {
+                    wf.add_component(component);
+                }
+
+                wf
+            })
+            .map_err(|e| error(0, ParseErrorKind::Invalid(e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{chirp, dc_bias, pulse, ramp, sawtooth, sine, square, triangle, unipolar_pulse};
+    use alloc::{format, vec};
+    use paste::paste;
+
+    fn kinds(expression: &str) -> Vec<Kind<f64>> {
+        parse::<f64>(expression)
+            .unwrap_or_else(|e| panic!("{}: {}", expression, e))
+            .iter()
+            .map(|f| f.kind().clone())
+            .collect()
+    }
+
+    macro_rules! test_parse {
+        ($($name:ident: $expression:expr => [$($f:expr),*])*) => {
+            $(
+                paste! {
+                    #[test]
+                    fn [<parses_ $name>]() {
+                        let expected: Vec<Kind<f64>> = vec![$($f.kind().clone()),*];
+
+                        assert_eq!(expected, kinds($expression));
+                    }
+                }
+            )*
+        };
+    }
+
+    test_parse! {
+        example: "sine(50, 10) + sawtooth(20) - dc(5)" => [sine!(50., 10.), sawtooth!(20.), dc_bias!(-5.)]
+        leading_minus: "-square(1, 2)" => [square!(1., -2.)]
+        bare_number: " 3.5 - 1e-1 " => [dc_bias!(3.5), dc_bias!(-0.1)]
+        named_arguments: "sine(frequency: 50, phase: 0.25)" => [sine!(50., 1., 0.25)]
+        mixed_arguments: "triangle(5, phase: 0.5, amplitude: 2,)" => [triangle!(5., 2., 0.5)]
+        ramp: "ramp(1, 2, 0, 0.3)" => [ramp!(1., 2., 0., 0.3)]
+        pulses: "pulse(1, duty: 0.1) + unipolar_pulse(1, 2, 0.5, 0.3)" => [pulse!(1., 1., 0., 0.1), unipolar_pulse!(1., 2., 0.5, 0.3)]
+        chirp: "chirp(start: 10, end: 100, duration: 2, sweep: exponential, after: ping_pong)" => [chirp!(10., 100., 2., 1., 0., Sweep::Exponential, AfterSweep::PingPong)]
+        band_limited: "band_limited_square(100, method: additive)" => [PeriodicFunction::<f64>::band_limited_square(100., 1., 0., BandLimit::Additive)]
+        noise: "pink_noise(44100, 0.1, 42) - gaussian_noise(100, std_dev: 2)" => [PeriodicFunction::<f64>::pink_noise(44100., 0.1, 42), PeriodicFunction::<f64>::gaussian_noise(100., -2., 0)]
+    }
+
+    macro_rules! test_parse_error {
+        ($($name:ident: $expression:expr => $position:expr, $kind:expr)*) => {
+            $(
+                paste! {
+                    #[test]
+                    fn [<fails_to_parse_ $name>]() {
+                        assert_eq!(
+                            Some(ParseError { position: $position, kind: $kind }),
+                            parse::<f64>($expression).err()
+                        );
+                    }
+                }
+            )*
+        };
+    }
+
+    test_parse_error! {
+        empty: "" => 0, ParseErrorKind::UnexpectedEnd
+        unknown_function: "sine(1) + cosine(1)" => 10, ParseErrorKind::UnknownFunction
+        unclosed_call: "sine(1" => 6, ParseErrorKind::UnexpectedEnd
+        missing_operator: "sine(1) sine(2)" => 8, ParseErrorKind::UnexpectedCharacter('s')
+        invalid_number: "sine(1.e)" => 5, ParseErrorKind::InvalidNumber
+        unknown_argument: "sine(1, width: 2)" => 8, ParseErrorKind::UnknownArgument
+        duplicate_argument: "sine(1, frequency: 2)" => 8, ParseErrorKind::DuplicateArgument
+        missing_argument: "pulse(1, 1)" => 0, ParseErrorKind::MissingArgument("duty")
+        missing_frequency: "sine(amplitude: 1)" => 0, ParseErrorKind::MissingArgument("frequency")
+        too_many_arguments: "dc(1, 2)" => 6, ParseErrorKind::TooManyArguments
+        positional_after_named: "sine(frequency: 1, 2)" => 19, ParseErrorKind::PositionalAfterNamed
+        name_instead_of_number: "sine(fast)" => 5, ParseErrorKind::InvalidValue
+        unknown_sweep: "chirp(1, 2, 3, sweep: cubic)" => 15, ParseErrorKind::InvalidValue
+        fractional_seed: "white_noise(1, 1, 0.5)" => 18, ParseErrorKind::InvalidValue
+        non_ascii: "sine(1) × 2" => 8, ParseErrorKind::UnexpectedCharacter('×')
+        infinite_number: "sine(1e999)" => 5, ParseErrorKind::InvalidNumber
+        infinite_bias: "1 - 1e400" => 4, ParseErrorKind::InvalidNumber
+        duty_above_one: "pulse(10, 1, 0, 1.5)" => 16, ParseErrorKind::Invalid(Error::InvalidDuty)
+        negative_symmetry: "ramp(10, 1, 0, symmetry: -0.5)" => 15, ParseErrorKind::Invalid(Error::InvalidSymmetry)
+        zero_chirp_duration: "chirp(10, 100, duration: 0)" => 15, ParseErrorKind::Invalid(Error::InvalidDuration)
+        zero_exponential_chirp_start: "chirp(0, 100, 1, sweep: exponential)" => 6, ParseErrorKind::Invalid(Error::InvalidFrequency)
+    }
+
+    #[test]
+    fn rejects_number_beyond_single_precision() {
+        assert_eq!(
+            Some(ParseError {
+                position: 5,
+                kind: ParseErrorKind::InvalidNumber
+            }),
+            parse::<f32>("sine(1e40)").err()
+        );
+        assert!(parse::<f64>("sine(1e40)").is_ok());
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn parses_single_function() {
+        assert_eq!(
+            sine!(50., -1.).kind(),
+            PeriodicFunction::<f64>::parse(" -sine(50) ")
+                .unwrap()
+                .kind()
+        );
+        assert!(PeriodicFunction::<f64>::parse("sine(50) + 1").is_err());
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used, clippy::float_cmp)]
+    fn waveform_is_parsed_with_sample_rate() {
+        let wf = Waveform::<f32>::parse(100.0, "sine(10) + 1").unwrap();
+
+        assert_eq!(100.0, *wf.sample_rate());
+        assert_eq!(2, wf.components().len());
+        assert_eq!(
+            Some(ParseError {
+                position: 0,
+                kind: ParseErrorKind::Invalid(Error::InvalidSampleRate)
+            }),
+            Waveform::<f32>::parse(0.0, "sine(10)").err()
+        );
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn parse_error_is_displayed_with_position() {
+        let error = parse::<f64>("sine(1, 2, 3, 4)").err().unwrap();
+
+        assert_eq!("too many arguments at position 14", format!("{error}"));
+    }
+}
@@ -0,0 +1,399 @@
+//! Sequencing of periodic functions on a time axis.
+
+use crate::ops::derived_kind;
+use crate::{Error, Kind, PeriodicFunction, Precision};
+use alloc::vec::Vec;
+
+/// Placement of a function in a [`Sequence`].
+///
+/// The function is played from `start` (inclusive) to `end` (exclusive), with its own time starting at zero at `start`.
+/// Its gain rises linearly from zero during the first `fade_in` seconds, and falls to zero during the last `fade_out`
+/// seconds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Placement<P> {
+    /// Start time.
+    pub start: P,
+    /// End time.
+    pub end: P,
+    /// Fade in duration.
+    pub fade_in: P,
+    /// Fade out duration.
+    pub fade_out: P,
+}
+
+impl<P: Precision> Placement<P> {
+    /// Gain of the placed function at time `t`.
+    fn gain(&self, t: P) -> P {
+        if t < self.start || t >= self.end {
+            return P::zero();
+        }
+
+        let mut gain = P::one();
+        if t < self.start + self.fade_in {
+            gain = gain * (t - self.start) / self.fade_in;
+        }
+        if t > self.end - self.fade_out {
+            gain = gain * (self.end - t) / self.fade_out;
+        }
+
+        gain
+    }
+}
+
+/// A timeline of [`PeriodicFunction`]s, played one after another, optionally crossfaded and looped.
+///
+/// Overlapping functions are summed up. A [`Sequence`] turns into a [`PeriodicFunction`], to be sampled as a
+/// [`Waveform`] component. A [`Waveform`] can be sequenced as well, after turning it into a [`PeriodicFunction`].
+///
+/// # Examples
+///
+/// 1 s of 50 Hz sine, then 200 ms of silence, then a 2 s chirp, looped:
+///
+/// ```
+/// use wavegen::{chirp, sine, wf, Sequence};
+///
+/// let profile = Sequence::new()
+///     .then(sine!(50.), 1.)
+///     .silence(0.2)
+///     .then(chirp!(50., 500., 2.), 2.)
+///     .looped();
+///
+/// assert_eq!(3.2, profile.duration());
+///
+/// let waveform = wf!(f32, 8000., profile.into());
+/// ```
+///
+/// [`Waveform`]: crate::Waveform
+#[derive(Debug)]
+pub struct Sequence<P: Precision = f32> {
+    segments: Vec<(PeriodicFunction<P>, Placement<P>)>,
+    cursor: P,
+    looping: bool,
+}
+
+impl<P: Precision> Sequence<P> {
+    /// Initializes new, empty [`Sequence`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            segments: Vec::new(),
+            cursor: P::zero(),
+            looping: false,
+        }
+    }
+
+    /// Plays `function` for `duration` seconds, after everything sequenced so far.
+    #[must_use]
+    pub fn then(self, function: PeriodicFunction<P>, duration: impl Into<P>) -> Self {
+        let start = self.cursor;
+
+        self.at(function, start, start + duration.into())
+    }
+
+    /// Plays `function` for `duration` seconds, crossfading it with the last sequenced function during the first
+    /// `crossfade` seconds.
+    ///
+    /// The crossfade is anchored at the end of the last sequenced function, so any silence sequenced after it is
+    /// skipped. The crossfade is linear, so the gains of both functions always sum up to one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wavegen::{sine, square, Sequence};
+    ///
+    /// // 2 s sine, crossfaded into 2 s square during 0.5 s, lasting 3.5 s in total
+    /// let sequence = Sequence::<f32>::new()
+    ///     .then(sine!(50.), 2.)
+    ///     .crossfade(square!(50.), 2., 0.5);
+    ///
+    /// assert_eq!(3.5, sequence.duration());
+    /// ```
+    #[must_use]
+    pub fn crossfade(
+        mut self,
+        function: PeriodicFunction<P>,
+        duration: impl Into<P>,
+        crossfade: impl Into<P>,
+    ) -> Self {
+        let crossfade = crossfade.into();
+        let mut start = self.cursor - crossfade;
+
+        if let Some((_, previous)) = self.segments.last_mut() {
+            previous.fade_out = crossfade;
+            start = previous.end - crossfade;
+        }
+        let end = start + duration.into();
+        self.segments.push((
+            function,
+            Placement {
+                start,
+                end,
+                fade_in: crossfade,
+                fade_out: P::zero(),
+            },
+        ));
+        self.cursor = self.cursor.max(end);
+
+        self
+    }
+
+    /// Adds `duration` seconds of silence after everything sequenced so far.
+    #[must_use]
+    pub fn silence(mut self, duration: impl Into<P>) -> Self {
+        self.cursor = self.cursor + duration.into();
+
+        self
+    }
+
+    /// Plays `function` from `start` to `end` seconds, regardless of what is sequenced so far.
+    #[must_use]
+    pub fn at(self, function: PeriodicFunction<P>, start: impl Into<P>, end: impl Into<P>) -> Self {
+        self.placed(
+            function,
+            Placement {
+                start: start.into(),
+                end: end.into(),
+                fade_in: P::zero(),
+                fade_out: P::zero(),
+            },
+        )
+    }
+
+    /// Plays `function` as defined by `placement`, regardless of what is sequenced so far.
+    #[must_use]
+    pub fn placed(mut self, function: PeriodicFunction<P>, placement: Placement<P>) -> Self {
+        self.cursor = self.cursor.max(placement.end);
+        self.segments.push((function, placement));
+
+        self
+    }
+
+    /// Makes the sequence start over after its [`duration`](Sequence::duration).
+    #[must_use]
+    pub fn looped(mut self) -> Self {
+        self.looping = true;
+
+        self
+    }
+
+    /// Total duration of the sequence, including any trailing silence.
+    pub fn duration(&self) -> P {
+        self.cursor
+    }
+}
+
+impl<P: Precision> Default for Sequence<P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<P: Precision> From<Sequence<P>> for PeriodicFunction<P> {
+    /// Turns the [`Sequence`] into a single function of time.
+    fn from(sequence: Sequence<P>) -> Self {
+        let Sequence {
+            segments,
+            cursor: duration,
+            looping,
+        } = sequence;
+
+        let (functions, placements): (Vec<_>, Vec<_>) = segments.into_iter().unzip();
+        let operands = functions.iter().collect::<Vec<_>>();
+        let kind = derived_kind(&operands, |kinds| Kind::Sequence {
+            segments: kinds.into_iter().zip(placements.iter().copied()).collect(),
+            duration,
+            looping,
+        });
+        let frequency = if looping && duration.recip().is_normal() {
+            Some(duration.recip())
+        } else {
+            None
+        };
+
+        Self::derived(kind, frequency, functions, move |operands| {
+            let placements = placements.clone();
+
+            move |t| {
+                let t = if looping {
+                    t - (t / duration).floor() * duration
+                } else {
+                    t
+                };
+
+                operands
+                    .iter()
+                    .zip(&placements)
+                    .map(|(f, placement)| match placement.gain(t) {
+                        gain if gain == P::zero() => P::zero(),
+                        gain => gain * f(t - placement.start),
+                    })
+                    .sum()
+            }
+        })
+    }
+}
+
+impl<P: Precision> PeriodicFunction<P> {
+    pub(crate) fn sequence_of(
+        segments: &[(Kind<P>, Placement<P>)],
+        duration: P,
+        looping: bool,
+    ) -> Result<Self, Error> {
+        let mut sequence = Sequence::new();
+        for (kind, placement) in segments {
+            sequence = sequence.placed(kind.try_build()?, *placement);
+        }
+        sequence.cursor = duration;
+        sequence.looping = looping;
+
+        Ok(sequence.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        chirp, dc_bias, sine, square, BandLimit, Kind, PeriodicFunction, Placement, Sequence,
+        Waveform,
+    };
+    use alloc::{vec, vec::Vec};
+    use core::convert::TryFrom;
+    use float_cmp::approx_eq;
+    use paste::paste;
+
+    fn profile() -> Sequence<f64> {
+        Sequence::new()
+            .then(sine!(50.), 1.)
+            .silence(0.2)
+            .then(chirp!(50., 500., 2.), 2.)
+    }
+
+    macro_rules! test_sequence {
+        ($($name:ident: $sequence:expr => [$(($t:expr, $expected:expr)),*])*) => {
+            $(
+                paste! {
+                    #[test]
+                    fn [<$name _has_expected_samples>]() {
+                        let f: PeriodicFunction<f64> = $sequence.into();
+
+                        $(
+                            assert!(approx_eq!(f64, $expected, f.sample($t), epsilon = 1e-9), "f({}) = {}", $t, f.sample($t));
+                        )*
+                    }
+                }
+            )*
+        };
+    }
+
+    test_sequence! {
+        profile: profile() => [(0.005, 1.0), (0.5, sine!(50.).sample(0.5)), (1.0, 0.0), (1.1, 0.0), (1.2, 0.0), (1.7, chirp!(50., 500., 2.).sample(0.5)), (3.3, 0.0), (4.205, 0.0)]
+        looped_profile: profile().looped() => [(3.205, 1.0), (32.005, 1.0), (33.7, chirp!(50., 500., 2.).sample(0.5))]
+        dc_crossfade: Sequence::new().then(dc_bias!(1.), 1.).crossfade(dc_bias!(3.), 1., 0.5) => [(0.25, 1.0), (0.5, 1.0), (0.625, 1.5), (0.75, 2.0), (0.875, 2.5), (1.0, 3.0), (1.25, 3.0), (1.5, 0.0)]
+        // the gains sum up to one, as the crossfade skips the silence
+        crossfade_after_silence: Sequence::new().then(dc_bias!(1.), 1.).silence(0.2).crossfade(dc_bias!(1.), 2., 0.5) => [(0.4, 1.0), (0.6, 1.0), (0.8, 1.0), (0.9, 1.0), (1.0, 1.0), (1.2, 1.0), (2.4, 1.0), (2.5, 0.0)]
+        crossfade_after_placed: Sequence::new().at(dc_bias!(1.), 0., 1.).at(dc_bias!(2.), 0., 0.5).crossfade(dc_bias!(2.), 1., 0.25) => [(0.3, 3.0), (0.4, 3.0), (0.6, 3.0), (1.1, 2.0), (1.25, 0.0)]
+        overlapping: Sequence::new().at(dc_bias!(1.), 0., 2.).at(dc_bias!(2.), 1., 3.) => [(0.5, 1.0), (1.5, 3.0), (2.5, 2.0), (3.5, 0.0)]
+        faded: Sequence::new().placed(dc_bias!(1.), Placement { start: 1., end: 3., fade_in: 1., fade_out: 0.5 }) => [(0.5, 0.0), (1.5, 0.5), (2.0, 1.0), (2.75, 0.5)]
+        empty: Sequence::new() => [(0.0, 0.0), (1.0, 0.0)]
+    }
+
+    #[test]
+    fn sequence_duration_includes_silence() {
+        assert!(approx_eq!(f64, 3.2, profile().duration()));
+        assert!(approx_eq!(f64, 3.7, profile().silence(0.5).duration()));
+    }
+
+    #[test]
+    fn looped_sequence_is_periodic() {
+        let f: PeriodicFunction<f64> = profile().looped().into();
+        let once: PeriodicFunction<f64> = profile().into();
+
+        assert!(approx_eq!(f64, 1.0 / 3.2, f.frequency.unwrap_or(0.0)));
+        assert_eq!(None, once.frequency);
+    }
+
+    #[test]
+    fn waveform_samples_sequence() {
+        let wf = Waveform::<f64, f64>::with_components(100.0, vec![profile().looped().into()]);
+        let f: PeriodicFunction<f64> = profile().looped().into();
+        let samples = wf.iter().take(1000).collect::<Vec<_>>();
+
+        for (i, sample) in samples.into_iter().enumerate() {
+            let t = f64::from(u32::try_from(i).unwrap_or(0)) / 100.0;
+
+            assert!(
+                approx_eq!(f64, f.sample(t), sample, epsilon = 1e-9),
+                "sample {i}"
+            );
+        }
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn waveform_is_sequenced() {
+        let tones = Waveform::<f64, f64>::with_components(100.0, vec![sine!(5.), square!(7.)]);
+        let f: PeriodicFunction<f64> = Sequence::new()
+            .silence(1.)
+            .then(PeriodicFunction::try_from(tones).unwrap(), 1.)
+            .into();
+
+        let expected = sine!(5.).sample(0.25) + square!(7.).sample(0.25);
+        assert!(approx_eq!(f64, expected, f.sample(1.25)));
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used, clippy::float_cmp)]
+    fn sequence_kind_is_recorded_and_rebuilt() {
+        let f: PeriodicFunction<f64> = profile().crossfade(square!(3.), 1., 0.5).looped().into();
+        let rebuilt = f.kind().build().unwrap();
+
+        assert!(matches!(f.kind(), Kind::Sequence { looping: true, .. }));
+        assert_eq!(f.kind(), rebuilt.kind());
+        for t in [0.0, 0.3, 1.1, 2.9, 3.3, 4.0, 17.0] {
+            assert_eq!(f.sample(t), rebuilt.sample(t));
+        }
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn band_limited_segment_is_bound() {
+        let square = || PeriodicFunction::band_limited_square(1000., 1., 0., BandLimit::PolyBlep);
+        let wf = Waveform::<f64, f64>::with_components(
+            8000.0,
+            vec![Sequence::new().silence(0.5).then(square(), 1.).into()],
+        );
+        let bound = square().bound(8000.0);
+        let naive = square();
+        let times = (4000..4100).map(|i| f64::from(i) / 8000.0 - 0.5);
+
+        assert!(wf
+            .iter()
+            .skip(4000)
+            .zip(times.clone())
+            .all(|(y, t)| approx_eq!(f64, y, bound.sample(t))));
+        assert!(times.clone().any(|t| bound.sample(t) != naive.sample(t)));
+    }
+
+    #[test]
+    fn band_limited_segment_is_bound_next_to_custom_one() {
+        let square = || PeriodicFunction::band_limited_square(1000., 1., 0., BandLimit::PolyBlep);
+        let sequence = Sequence::new()
+            .then(square(), 1.)
+            .then(PeriodicFunction::custom(|t| t), 1.);
+        let wf = Waveform::<f64, f64>::with_components(8000.0, vec![sequence.into()]);
+        let bound = square().bound(8000.0);
+
+        assert!(wf
+            .iter()
+            .take(100)
+            .zip((0..100).map(|i| f64::from(i) / 8000.0))
+            .all(|(y, t)| approx_eq!(f64, y, bound.sample(t))));
+        assert!(wf
+            .iter()
+            .skip(8100)
+            .take(100)
+            .zip((100..200).map(|i| f64::from(i) / 8000.0))
+            .all(|(y, t)| approx_eq!(f64, y, t)));
+    }
+}
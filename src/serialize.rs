@@ -0,0 +1,292 @@
+//! Serde (de)serialisation of waveform definitions.
+
+use crate::{Error, Kind, PeriodicFunction, Precision, SampleType, Waveform};
+use alloc::{string::String, vec::Vec};
+use serde::de::{self, Deserialize, Deserializer};
+use serde::ser::{self, Serialize, Serializer};
+
+/// [`Precision`] types with a stable name, stored with serialised [`Waveform`]s.
+///
+/// The name is part of the serialised format, so it has to stay the same across crate and compiler versions.
+pub trait PrecisionTag: Precision {
+    /// Name of the precision type, as stored in the `precision` field.
+    const TAG: &'static str;
+}
+
+impl PrecisionTag for f32 {
+    const TAG: &'static str = "f32";
+}
+
+impl PrecisionTag for f64 {
+    const TAG: &'static str = "f64";
+}
+
+impl<P: Precision + Serialize> Serialize for PeriodicFunction<P> {
+    /// Serialises the [`Kind`] of the function.
+    ///
+    /// Fails for functions built from closures, as there is no way to describe them.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.kind() {
+            Kind::Custom => Err(ser::Error::custom(Error::CustomComponent)),
+            kind => kind.serialize(serializer),
+        }
+    }
+}
+
+impl<'de, P: Precision + Deserialize<'de>> Deserialize<'de> for PeriodicFunction<P> {
+    /// Deserialises a [`Kind`] and builds the function from it, failing with the [`Error`] of the builder if its
+    /// parameters are invalid.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Kind::deserialize(deserializer)?
+            .try_build()
+            .map_err(de::Error::custom)
+    }
+}
+
+#[derive(serde::Serialize)]
+struct WaveformRef<'a, P: Precision + Serialize> {
+    sample_rate: P,
+    precision: &'a str,
+    components: &'a [PeriodicFunction<P>],
+}
+
+#[derive(serde::Deserialize)]
+#[serde(bound(deserialize = "P: Precision + Deserialize<'de>"))]
+struct WaveformDef<P: Precision> {
+    sample_rate: P,
+    #[serde(default)]
+    precision: String,
+    #[serde(default = "Vec::new")]
+    components: Vec<PeriodicFunction<P>>,
+}
+
+impl<T: SampleType, P: PrecisionTag + Serialize> Serialize for Waveform<T, P> {
+    /// Serialises the sample rate, precision and components of the waveform.
+    ///
+    /// Fails if the waveform contains functions built from closures or stateful components.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if !self.stateful_components().is_empty() {
+            return Err(ser::Error::custom(Error::CustomComponent));
+        }
+
+        WaveformRef {
+            sample_rate: *self.sample_rate(),
+            precision: P::TAG,
+            components: self.components(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de, T: SampleType, P: PrecisionTag + Deserialize<'de>> Deserialize<'de> for Waveform<T, P> {
+    /// Deserialises a waveform definition, validating its sample rate and, if present, precision.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let def = WaveformDef::<P>::deserialize(deserializer)?;
+
+        if !def.precision.is_empty() && def.precision != P::TAG {
+            return Err(de::Error::invalid_value(
+                de::Unexpected::Str(&def.precision),
+                &P::TAG,
+            ));
+        }
+
+        let mut wf = Waveform::try_new(def.sample_rate).map_err(de::Error::custom)?;
+        for component in def.components {
+            wf.add_component(component);
+        }
+
+        Ok(wf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        adsr, chirp, dc_bias, piecewise_linear, pulse, ramp, sawtooth, sine, square, triangle,
+        unipolar_pulse, BandLimit, Dds, Error, Interpolation, Kind, NoiseColor, PeriodicFunction,
+        Sequence, StatefulFunction, Trigger, Waveform,
+    };
+    use alloc::{boxed::Box, string::ToString, vec, vec::Vec};
+    use paste::paste;
+
+    fn waveform() -> Waveform<f64, f64> {
+        Waveform::with_components(
+            1000.0,
+            vec![
+                dc_bias!(0.5),
+                sine!(10., 2., 0.25),
+                square!(20.),
+                sawtooth!(30., 0.5),
+                triangle!(40.),
+                ramp!(50., 1., 0., 0.2),
+                pulse!(60., 1., 0.1, 0.3),
+                unipolar_pulse!(70., 1., 0.1, 0.3),
+                chirp!(10., 100., 2.),
+                PeriodicFunction::band_limited_square(80., 1., 0., BandLimit::PolyBlep),
+                PeriodicFunction::pink_noise(1000., 0.1, 42),
+                PeriodicFunction::dds(90., 1., 0.2, Dds::new(24, 8, 12).with_dither(42)),
+                PeriodicFunction::fourier(25., &[(1, 1., 0.), (3, 0.3, 0.25), (40, 0.1, 0.5)]),
+                PeriodicFunction::wavetable(
+                    15.,
+                    0.5,
+                    0.,
+                    vec![0.0, 1.0, 0.5, -1.0],
+                    Interpolation::Linear,
+                ),
+                (sine!(5.) * -square!(1.)).clamp(-0.5, 0.5),
+                sine!(50.)
+                    * adsr!(
+                        0.1,
+                        0.2,
+                        0.5,
+                        0.3,
+                        Trigger::Loop {
+                            period: 1.,
+                            gate: 0.5
+                        }
+                    ),
+                piecewise_linear!([(0., 0.), (0.5, 1.)]),
+                Sequence::new()
+                    .then(sine!(3.), 1.)
+                    .crossfade(chirp!(1., 5., 1.), 1., 0.2)
+                    .looped()
+                    .into(),
+            ],
+        )
+    }
+
+    #[allow(clippy::float_cmp)]
+    fn assert_same(a: &Waveform<f64, f64>, b: &Waveform<f64, f64>) {
+        assert_eq!(a.sample_rate(), b.sample_rate());
+        assert_eq!(
+            a.components()
+                .iter()
+                .map(PeriodicFunction::kind)
+                .collect::<Vec<_>>(),
+            b.components()
+                .iter()
+                .map(PeriodicFunction::kind)
+                .collect::<Vec<_>>()
+        );
+        assert!(a.iter().zip(b.iter()).take(2000).all(|(x, y)| x == y));
+    }
+
+    macro_rules! test_round_trip {
+        ($($format:ident: $to:expr, $from:expr)*) => {
+            $(
+                paste! {
+                    #[test]
+                    #[allow(clippy::unwrap_used)]
+                    fn [<waveform_round_trips_through_ $format>]() {
+                        let wf = waveform();
+                        let serialized = $to(&wf).unwrap();
+                        let deserialized: Waveform<f64, f64> = ($from)(&serialized).unwrap();
+
+                        assert_same(&wf, &deserialized);
+                    }
+                }
+            )*
+        };
+    }
+
+    test_round_trip! {
+        json: serde_json::to_string, |s: &str| serde_json::from_str(s)
+        ron: ron::to_string, |s: &str| ron::from_str(s)
+        toml: toml::to_string, |s: &str| toml::from_str(s)
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn waveform_is_deserialized_from_hand_written_json() {
+        let json = r#"{
+            "sample_rate": 100.0,
+            "components": [
+                { "Sine": { "frequency": 10.0, "amplitude": 2.0, "phase": 0.0 } },
+                { "DcBias": { "bias": 1.0 } }
+            ]
+        }"#;
+        let wf: Waveform<f32> = serde_json::from_str(json).unwrap();
+
+        assert_eq!(2, wf.components().len());
+        assert_eq!(Some(10.0), wf.components()[0].kind().frequency());
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn custom_component_fails_to_serialize() {
+        let wf = Waveform::<f32>::with_components(100.0, vec![PeriodicFunction::custom(|t| t)]);
+        let error = serde_json::to_string(&wf).unwrap_err();
+
+        assert_eq!(Error::CustomComponent.to_string(), error.to_string());
+    }
+
+    #[test]
+    fn stateful_component_fails_to_serialize() {
+        let mut wf = Waveform::<f32>::new(100.0);
+        wf.add_stateful_component(StatefulFunction::new(|_| |t| t));
+
+        assert!(serde_json::to_string(&wf).is_err());
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn custom_kind_fails_to_deserialize() {
+        let json = r#"{ "sample_rate": 100.0, "components": ["Custom"] }"#;
+
+        assert!(serde_json::from_str::<Waveform<f32>>(json)
+            .unwrap_err()
+            .to_string()
+            .contains(&Error::CustomComponent.to_string()));
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn invalid_parameters_fail_to_deserialize_with_builder_error() {
+        let modulator = Kind::Noise {
+            color: NoiseColor::White,
+            rate: 10.0,
+            amplitude: 1.0,
+            seed: 0,
+        };
+        let fm = Kind::<f64>::Fm {
+            carrier_frequency: 100.0,
+            modulator: Box::new(modulator),
+            deviation: 10.0,
+        };
+        let json = serde_json::to_string(&fm).unwrap();
+
+        assert!(serde_json::from_str::<PeriodicFunction<f64>>(&json)
+            .err()
+            .unwrap()
+            .to_string()
+            .contains(&Error::AperiodicModulator.to_string()));
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn invalid_sample_rate_fails_to_deserialize() {
+        let json = r#"{ "sample_rate": -1.0, "components": [] }"#;
+
+        assert!(serde_json::from_str::<Waveform<f32>>(json)
+            .unwrap_err()
+            .to_string()
+            .contains(&Error::InvalidSampleRate.to_string()));
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn precision_is_stored_by_tag() {
+        let json = serde_json::to_string(&Waveform::<f32, f32>::new(100.0)).unwrap();
+
+        assert!(json.contains(r#""precision":"f32""#), "{}", json);
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn precision_mismatch_fails_to_deserialize() {
+        let json = serde_json::to_string(&Waveform::<f32, f64>::new(100.0)).unwrap();
+
+        assert!(serde_json::from_str::<Waveform<f32, f32>>(&json).is_err());
+        assert!(serde_json::from_str::<Waveform<f32, f64>>(&json).is_ok());
+    }
+}
@@ -0,0 +1,89 @@
+//! Stateful components.
+
+use crate::Precision;
+use alloc::boxed::Box;
+use core::fmt;
+
+/// Per-iterator state of a [`StatefulFunction`], called once for every sample with the sample's time.
+pub(crate) trait State<P>: FnMut(P) -> P + Send + Sync {
+    fn box_clone(&self) -> Box<dyn State<P>>;
+}
+
+impl<P, F> State<P> for F
+where
+    F: FnMut(P) -> P + Clone + Send + Sync + 'static,
+{
+    fn box_clone(&self) -> Box<dyn State<P>> {
+        Box::new(self.clone())
+    }
+}
+
+impl<P: 'static> Clone for Box<dyn State<P>> {
+    fn clone(&self) -> Self {
+        (**self).box_clone()
+    }
+}
+
+type Init<P> = dyn Fn(P) -> Box<dyn State<P>> + Send + Sync;
+
+/// Wrapper struct for a stateful function, such as a filter, a random walk or an envelope.
+///
+/// Unlike [`PeriodicFunction`], which is a pure function of time, a [`StatefulFunction`] keeps a state which is updated
+/// on every sample. The state is created anew by the initializer function every time an iterator over the [`Waveform`]
+/// is created, so separate iterators over the same [`Waveform`] are independent and always yield the same samples.
+///
+/// The initializer takes the [`Waveform`]'s sample rate and returns the state, in form of a `FnMut` closure. The
+/// closure is called exactly once for each sample, in order, with the sample's time.
+///
+/// [`PeriodicFunction`]: crate::PeriodicFunction
+/// [`Waveform`]: crate::Waveform
+pub struct StatefulFunction<P: Precision = f32> {
+    init: Box<Init<P>>,
+}
+
+impl<P: Precision> StatefulFunction<P> {
+    /// Initializes new [`StatefulFunction`] with the state initializer defined by `init` parameter.
+    ///
+    /// # Examples
+    ///
+    /// Step response of a one-pole low pass filter:
+    ///
+    /// ```
+    /// use wavegen::{StatefulFunction, Waveform};
+    ///
+    /// let filtered_step = StatefulFunction::new(|sample_rate: f32| {
+    ///     let alpha = 1.0 - (-2.0 * core::f32::consts::PI * 5.0 / sample_rate).exp();
+    ///     let mut y = 0.0;
+    ///
+    ///     move |_t| {
+    ///         y += alpha * (1.0 - y);
+    ///         y
+    ///     }
+    /// });
+    ///
+    /// let mut wf = Waveform::<f32>::new(1000.0);
+    /// wf.add_stateful_component(filtered_step);
+    ///
+    /// let samples = wf.iter().take(50).collect::<Vec<_>>();
+    /// assert!(samples.windows(2).all(|w| w[0] < w[1]));
+    /// ```
+    pub fn new<I, S>(init: I) -> Self
+    where
+        I: Fn(P) -> S + Send + Sync + 'static,
+        S: FnMut(P) -> P + Clone + Send + Sync + 'static,
+    {
+        Self {
+            init: Box::new(move |sample_rate| Box::new(init(sample_rate))),
+        }
+    }
+
+    pub(crate) fn init(&self, sample_rate: P) -> Box<dyn State<P>> {
+        (self.init)(sample_rate)
+    }
+}
+
+impl<P: Precision> fmt::Debug for StatefulFunction<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StatefulFunction").finish_non_exhaustive()
+    }
+}
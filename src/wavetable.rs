@@ -0,0 +1,679 @@
+//! Wavetable lookup of periodic functions.
+
+use crate::nyquist::Shape;
+use crate::ops::derived_kind;
+use crate::{finite, Error, Kind, PeriodicFunction, Precision, SampleType, Two, Waveform};
+use alloc::{boxed::Box, vec, vec::Vec};
+use num_traits::NumCast;
+
+/// Interpolation between the values of a wavetable.
+///
+/// The error bounds given are for a unit sine, tabulated over `size` values, with `h = 2π / size`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Interpolation {
+    /// No interpolation, the value preceding the sampled time is taken. The error is at most `h`.
+    None,
+    /// Linear interpolation between the two neighbouring values. The error is at most `h² / 8`.
+    Linear,
+    /// Cubic Catmull-Rom interpolation between the four neighbouring values. The error is at most `h³ / 60`.
+    Cubic,
+}
+
+/// One period of a function, tabulated at equally spaced points.
+pub(crate) struct Table<P> {
+    values: Vec<P>,
+    interpolation: Interpolation,
+}
+
+impl<P: Precision> Table<P> {
+    pub(crate) fn new(values: Vec<P>, interpolation: Interpolation) -> Self {
+        Self {
+            values,
+            interpolation,
+        }
+    }
+
+    /// Value at `x` periods from the start of the table.
+    #[inline]
+    pub(crate) fn at(&self, x: P) -> P {
+        let len = self.values.len();
+        if len == 0 {
+            return P::zero();
+        }
+
+        let position = (x - x.floor()) * NumCast::from(len).unwrap_or_else(P::zero);
+        let index = position.to_usize().unwrap_or(0) % len;
+        let fraction = position - position.floor();
+        let value = |offset: usize| self.values[(index + offset) % len];
+
+        match self.interpolation {
+            Interpolation::None => value(0),
+            Interpolation::Linear => value(0) + (value(1) - value(0)) * fraction,
+            Interpolation::Cubic => {
+                let (y0, y1, y2, y3) = (value(len - 1), value(0), value(1), value(2));
+                let three = P::two() + P::one();
+                let a = three * (y1 - y2) + y3 - y0;
+                let b = P::two() * y0 - (P::two() + three) * y1 + P::two() * P::two() * y2 - y3;
+                let c = y2 - y0;
+
+                y1 + fraction * (c + fraction * (b + fraction * a)) / P::two()
+            }
+        }
+    }
+}
+
+impl<P: Precision> PeriodicFunction<P> {
+    /// Replaces the function with a table of its `size` values over one period, interpolated as set by
+    /// `interpolation`.
+    ///
+    /// Looking the values up is usually cheaper than calculating them, especially for sines under `libm`, at the cost
+    /// of the error described by [`Interpolation`]. Functions depending on the sample rate, like the band-limited ones,
+    /// are tabulated again once added to a [`Waveform`].
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if the function has no known frequency, or `size` is zero. See
+    /// [`PeriodicFunction::try_tabulated`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wavegen::{sine, Interpolation, PeriodicFunction};
+    ///
+    /// let exact: PeriodicFunction<f64> = sine!(440.);
+    /// let tabulated = sine!(440.).tabulated(1024, Interpolation::Linear);
+    ///
+    /// assert!((exact.sample(0.001) - tabulated.sample(0.001)).abs() < 5e-6);
+    /// ```
+    #[must_use]
+    pub fn tabulated(self, size: usize, interpolation: Interpolation) -> Self {
+        match self.try_tabulated(size, interpolation) {
+            Ok(tabulated) => tabulated,
+            Err(error) => panic!("{}", error),
+        }
+    }
+
+    /// Fallible version of [`PeriodicFunction::tabulated`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::AperiodicFunction`] if the function has no known frequency, or [`Error::EmptyTable`] if
+    /// `size` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wavegen::{sine, Error, Interpolation, PeriodicFunction};
+    ///
+    /// let sine: PeriodicFunction<f32> = sine!(440.);
+    ///
+    /// assert!(sine.try_tabulated(256, Interpolation::Cubic).is_ok());
+    /// assert_eq!(
+    ///     Some(Error::AperiodicFunction),
+    ///     PeriodicFunction::<f32>::custom(|t| t).try_tabulated(256, Interpolation::Cubic).err()
+    /// );
+    /// ```
+    pub fn try_tabulated(self, size: usize, interpolation: Interpolation) -> Result<Self, Error> {
+        let frequency = self.frequency.ok_or(Error::AperiodicFunction)?;
+        if size == 0 {
+            return Err(Error::EmptyTable);
+        }
+
+        let step = (frequency * NumCast::from(size).unwrap_or_else(P::one)).recip();
+
+        let kind = derived_kind(&[&self], |mut kinds| Kind::Tabulated {
+            function: Box::new(kinds.remove(0)),
+            size,
+            interpolation,
+        });
+        let spectrum = self.spectrum.clone();
+        let mut tabulated = Self::derived(kind, Some(frequency), vec![self], move |operands| {
+            let values = (0..size)
+                .map(|i| operands[0](step * NumCast::from(i).unwrap_or_else(P::zero)))
+                .collect();
+            let table = Table::new(values, interpolation);
+
+            move |t| table.at(t * frequency)
+        });
+        tabulated.spectrum = spectrum;
+
+        Ok(tabulated)
+    }
+
+    /// Wavetable function builder, repeating one period given by `table` at `frequency`.
+    ///
+    /// The values of the `table` are equally spaced over the period, and interpolated as set by `interpolation`. Tables
+    /// can be rendered from a [`Waveform`] with [`Waveform::period_table`], or read from a WAV file with
+    /// `read_wav_table`, when the `hound` feature is enabled.
+    ///
+    /// The harmonics of the function are worked out from the discrete Fourier transform of the `table` and the frequency
+    /// response of the `interpolation`, so [`Waveform::validate`] checks it like the built-in functions.
+    ///
+    /// # Panics
+    ///
+    /// This method will panic if the `table` is empty. See [`PeriodicFunction::try_wavetable`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wavegen::{Interpolation, PeriodicFunction};
+    ///
+    /// let f = PeriodicFunction::<f32>::wavetable(2.0, 3.0, 0.0, vec![0.0, 1.0, 0.0, -1.0], Interpolation::Linear);
+    ///
+    /// assert_eq!(3.0, f.sample(0.125));
+    /// assert_eq!(-1.5, f.sample(0.4375));
+    /// ```
+    #[inline]
+    pub fn wavetable(
+        frequency: impl Into<P>,
+        amplitude: impl Into<P>,
+        phase: impl Into<P>,
+        table: Vec<P>,
+        interpolation: Interpolation,
+    ) -> Self {
+        match Self::try_wavetable(frequency, amplitude, phase, table, interpolation) {
+            Ok(f) => f,
+            Err(error) => panic!("{}", error),
+        }
+    }
+
+    /// Fallible version of [`PeriodicFunction::wavetable`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::EmptyTable`] if the `table` is empty, or another [`Error`] if any of the other parameters is
+    /// not a finite number.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wavegen::{Error, Interpolation, PeriodicFunction};
+    ///
+    /// assert_eq!(
+    ///     Some(Error::EmptyTable),
+    ///     PeriodicFunction::<f32>::try_wavetable(50.0, 1.0, 0.0, vec![], Interpolation::Cubic).err()
+    /// );
+    /// ```
+    #[inline]
+    pub fn try_wavetable(
+        frequency: impl Into<P>,
+        amplitude: impl Into<P>,
+        phase: impl Into<P>,
+        table: Vec<P>,
+        interpolation: Interpolation,
+    ) -> Result<Self, Error> {
+        let frequency = finite(frequency.into(), Error::InvalidFrequency)?;
+        let amplitude = finite(amplitude.into(), Error::InvalidAmplitude)?;
+        let phase = finite(phase.into(), Error::InvalidPhase)?;
+        if table.is_empty() {
+            return Err(Error::EmptyTable);
+        }
+
+        let kind = Kind::Wavetable {
+            frequency,
+            amplitude,
+            phase,
+            table: table.clone(),
+            interpolation,
+        };
+        let shape = Shape::Table(table.clone(), interpolation);
+        let table = Table::new(table, interpolation);
+
+        Ok(Self::new(Box::new(move |t| {
+            amplitude * table.at(t * frequency + phase)
+        }))
+        .periodic(frequency, shape)
+        .with_kind(kind))
+    }
+}
+
+impl<T: SampleType, P: Precision> Waveform<T, P> {
+    /// Renders one period of `frequency` of this [`Waveform`] into a table of `size` equally spaced values, to be used
+    /// by [`PeriodicFunction::wavetable`].
+    ///
+    /// The values are the sums of the components, before conversion to the sample type, so they are neither rounded nor
+    /// saturated.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::InvalidFrequency`] if `frequency` is not a finite, non-zero number, [`Error::EmptyTable`] if
+    /// `size` is zero, or [`Error::StatefulComponent`] if the [`Waveform`] has any stateful components, as they cannot
+    /// be evaluated at arbitrary time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wavegen::{sine, Interpolation, PeriodicFunction, Waveform};
+    ///
+    /// let wf = Waveform::<i16>::with_components(44100.0, vec![sine!(100., 1.), sine!(300., 0.5)]);
+    /// let table = wf.period_table(100.0, 512).unwrap();
+    ///
+    /// // the same timbre, one octave higher
+    /// let f = PeriodicFunction::wavetable(200.0, 1.0, 0.0, table, Interpolation::Cubic);
+    /// ```
+    pub fn period_table(&self, frequency: impl Into<P>, size: usize) -> Result<Vec<P>, Error> {
+        let frequency = finite(frequency.into(), Error::InvalidFrequency)?;
+        if frequency.is_zero() {
+            return Err(Error::InvalidFrequency);
+        }
+        if size == 0 {
+            return Err(Error::EmptyTable);
+        }
+        if !self.stateful_components.is_empty() {
+            return Err(Error::StatefulComponent);
+        }
+
+        let step = (frequency.abs() * NumCast::from(size).unwrap_or_else(P::one)).recip();
+
+        Ok((0..size)
+            .map(|i| {
+                let t = step * NumCast::from(i).unwrap_or_else(P::zero);
+
+                self.components
+                    .iter()
+                    .fold(P::zero(), |sum, component| sum + component.sample(t))
+            })
+            .collect())
+    }
+}
+
+/// Reads a table for [`PeriodicFunction::wavetable`] from a WAV file, usually holding a single period of a recorded
+/// waveform.
+///
+/// Integer samples are scaled to `[-1, 1)` range, while floating point samples are taken as they are. Only the first
+/// channel of multi-channel files is read.
+///
+/// # Errors
+///
+/// Returns the [`hound::Error`] encountered while reading the file.
+///
+/// # Examples
+///
+/// ```no_run
+/// use wavegen::{read_wav_table, Interpolation, PeriodicFunction};
+///
+/// let table = read_wav_table(std::fs::File::open("single_cycle.wav")?)?;
+/// let f = PeriodicFunction::<f32>::wavetable(440.0, 1.0, 0.0, table, Interpolation::Linear);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[cfg(feature = "hound")]
+pub fn read_wav_table<P: Precision, R: std::io::Read>(reader: R) -> Result<Vec<P>, hound::Error> {
+    let mut reader = hound::WavReader::new(reader)?;
+    let spec = reader.spec();
+    let channels: usize = spec.channels.max(1).into();
+
+    match spec.sample_format {
+        hound::SampleFormat::Float => reader
+            .samples::<f32>()
+            .step_by(channels)
+            .map(|sample| sample.map(|x| NumCast::from(x).unwrap_or_else(P::nan)))
+            .collect(),
+        hound::SampleFormat::Int => {
+            let scale: P =
+                NumCast::from(1u64 << (spec.bits_per_sample.max(1) - 1)).unwrap_or_else(P::one);
+
+            reader
+                .samples::<i32>()
+                .step_by(channels)
+                .map(|sample| {
+                    sample.map(|x| NumCast::from(x).map_or_else(P::nan, |x: P| x / scale))
+                })
+                .collect()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{dc_bias, sawtooth, sine, square, BandLimit, Waveform};
+    use alloc::vec;
+    use core::f64::consts::PI;
+    use paste::paste;
+
+    fn float(x: impl Into<f64>) -> f64 {
+        x.into()
+    }
+
+    /// Largest difference between `f` and the exact sine over a few periods.
+    fn max_sine_error(f: &PeriodicFunction<f64>) -> f64 {
+        let exact = sine!(50.);
+
+        (0..10_000)
+            .map(|i: u32| float(i) / 100_000.0)
+            .map(|t| (exact.sample(t) - f.sample(t)).abs())
+            .fold(0.0, f64::max)
+    }
+
+    macro_rules! test_error_bound {
+        ($($name:ident: $size:expr, $interpolation:expr => $bound:expr)*) => {
+            $(
+                paste! {
+                    #[test]
+                    fn [<$name _error_is_bounded>]() {
+                        let f = sine!(50.).tabulated($size.into(), $interpolation);
+                        let h = 2.0 * PI / float($size);
+                        let error = max_sine_error(&f);
+
+                        assert!(error <= $bound(h), "error {}", error);
+                        // the bound is tight
+                        assert!(error > $bound(h) / 2.0, "error {}", error);
+                    }
+                }
+            )*
+        };
+    }
+
+    test_error_bound! {
+        none_64: 64u16, Interpolation::None => |h: f64| h
+        none_1024: 1024u16, Interpolation::None => |h: f64| h
+        linear_64: 64u16, Interpolation::Linear => |h: f64| h * h / 8.0
+        linear_1024: 1024u16, Interpolation::Linear => |h: f64| h * h / 8.0
+        cubic_64: 64u16, Interpolation::Cubic => |h: f64| h.powi(3) / 60.0
+        cubic_256: 256u16, Interpolation::Cubic => |h: f64| h.powi(3) / 60.0
+    }
+
+    #[test]
+    fn table_values_are_exact() {
+        let exact: PeriodicFunction<f64> = sawtooth!(2., 1., 0.1);
+
+        for interpolation in [
+            Interpolation::None,
+            Interpolation::Linear,
+            Interpolation::Cubic,
+        ] {
+            let f = sawtooth!(2., 1., 0.1).tabulated(100, interpolation);
+
+            for t in [0.0, 0.005, 0.25, 0.495, 1.5, 0.75] {
+                assert!((exact.sample(t) - f.sample(t)).abs() < 1e-9, "f({t})");
+            }
+        }
+    }
+
+    #[test]
+    fn tabulated_keeps_frequency_and_spectrum() {
+        let f: PeriodicFunction<f64> = square!(100.).tabulated(64, Interpolation::None);
+        let wf = Waveform::<f64, f64>::with_components(1000.0, vec![f]);
+
+        assert_eq!(Some(100.0), wf.components()[0].frequency);
+        assert!(!wf.validate(0.1).is_ok());
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used, clippy::float_cmp)]
+    fn tabulated_kind_is_recorded_and_rebuilt() {
+        let f: PeriodicFunction<f64> = sine!(50.).tabulated(64, Interpolation::Cubic);
+        let rebuilt = f.kind().build().unwrap();
+
+        assert_eq!(
+            Kind::Tabulated {
+                function: Box::new(Kind::Sine {
+                    frequency: 50.0,
+                    amplitude: 1.0,
+                    phase: 0.0
+                }),
+                size: 64,
+                interpolation: Interpolation::Cubic
+            },
+            *f.kind()
+        );
+        for t in [0.0, 0.003, 0.011, 0.5] {
+            assert_eq!(f.sample(t), rebuilt.sample(t));
+        }
+    }
+
+    #[test]
+    fn band_limited_function_is_tabulated_when_bound() {
+        let square = || PeriodicFunction::band_limited_square(1000., 1., 0., BandLimit::PolyBlep);
+        let bound = square().bound(8000.0);
+        let wf = Waveform::<f64, f64>::with_components(
+            8000.0,
+            vec![square().tabulated(8, Interpolation::Linear)],
+        );
+
+        assert!(wf
+            .iter()
+            .take(8)
+            .zip(0u8..8)
+            .all(|(y, i)| (y - bound.sample(float(i) / 8000.0)).abs() < 1e-9));
+    }
+
+    #[test]
+    fn errors_are_reported() {
+        assert_eq!(
+            Some(Error::AperiodicFunction),
+            PeriodicFunction::<f64>::white_noise(10.0, 1.0, 0)
+                .try_tabulated(16, Interpolation::Linear)
+                .err()
+        );
+        assert_eq!(
+            Some(Error::EmptyTable),
+            PeriodicFunction::<f64>::sine(1., 1., 0.)
+                .try_tabulated(0, Interpolation::Linear)
+                .err()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "function has to have a known frequency")]
+    fn tabulating_aperiodic_function_panics() {
+        let _ = PeriodicFunction::<f64>::custom(|t| t).tabulated(16, Interpolation::None);
+    }
+
+    #[test]
+    fn wavetable_repeats_table_with_amplitude_and_phase() {
+        let f = PeriodicFunction::<f64>::wavetable(
+            10.0,
+            2.0,
+            0.25,
+            vec![1.0, 2.0, 3.0, 4.0],
+            Interpolation::None,
+        );
+
+        for (t, expected) in [
+            (0.0, 4.0),
+            (0.025, 6.0),
+            (0.05, 8.0),
+            (0.075, 2.0),
+            (0.1, 4.0),
+            (-0.05, 8.0),
+        ] {
+            assert!((f.sample(t) - expected).abs() < 1e-12, "f({t})");
+        }
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn wavetable_of_rendered_period_matches_tabulated_function() {
+        let wf = Waveform::<f64, f64>::with_components(1000.0, vec![sawtooth!(5., 2., 0.1)]);
+        let table = wf.period_table(5.0, 256).unwrap();
+
+        let wavetable = Waveform::<f64, f64>::with_components(
+            1000.0,
+            vec![PeriodicFunction::wavetable(
+                5.0,
+                1.0,
+                0.0,
+                table,
+                Interpolation::Cubic,
+            )],
+        );
+        let tabulated = Waveform::<f64, f64>::with_components(
+            1000.0,
+            vec![sawtooth!(5., 2., 0.1).tabulated(256, Interpolation::Cubic)],
+        );
+
+        assert!(wavetable
+            .iter()
+            .zip(tabulated.iter())
+            .take(2000)
+            .all(|(x, y)| (x - y).abs() < 1e-9));
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used)]
+    fn period_table_sums_bound_components_without_saturating() {
+        let square = || PeriodicFunction::band_limited_square(50., 1., 0., BandLimit::PolyBlep);
+        let wf = Waveform::<i8, f64>::with_components(400.0, vec![square(), dc_bias!(1000.)]);
+        let bound = square().bound(400.0);
+
+        let table = wf.period_table(50.0, 8).unwrap();
+
+        for (i, value) in (0u8..).zip(table) {
+            assert!((value - bound.sample(float(i) / 400.0) - 1000.0).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn period_table_errors_are_reported() {
+        let mut wf = Waveform::<f64, f64>::with_components(100.0, vec![sine!(1.)]);
+
+        assert_eq!(
+            Some(Error::InvalidFrequency),
+            wf.period_table(0.0, 16).err()
+        );
+        assert_eq!(
+            Some(Error::InvalidFrequency),
+            wf.period_table(f64::NAN, 16).err()
+        );
+        assert_eq!(Some(Error::EmptyTable), wf.period_table(1.0, 0).err());
+
+        wf.add_stateful_component(crate::StatefulFunction::new(|_| |t| t));
+        assert_eq!(
+            Some(Error::StatefulComponent),
+            wf.period_table(1.0, 16).err()
+        );
+    }
+
+    #[test]
+    fn wavetable_errors_are_reported() {
+        assert_eq!(
+            Some(Error::EmptyTable),
+            PeriodicFunction::<f64>::try_wavetable(1.0, 1.0, 0.0, vec![], Interpolation::None)
+                .err()
+        );
+        assert_eq!(
+            Some(Error::InvalidAmplitude),
+            PeriodicFunction::<f64>::try_wavetable(
+                1.0,
+                f64::NAN,
+                0.0,
+                vec![1.0],
+                Interpolation::None
+            )
+            .err()
+        );
+    }
+
+    macro_rules! test_wavetable_aliasing {
+        ($($name:ident: $table:expr, $interpolation:expr => $harmonic:expr, $magnitude:expr)*) => {
+            $(
+                paste! {
+                    #[test]
+                    fn [<$name _is_checked_for_aliasing>]() {
+                        let wf = Waveform::<f64, f64>::with_components(
+                            100.0,
+                            vec![PeriodicFunction::wavetable(40.0, 2.0, 0.3, $table, $interpolation)],
+                        );
+                        let alias = wf.validate(0.01).aliases[0];
+
+                        assert_eq!($harmonic, alias.harmonic);
+                        assert!((alias.magnitude - $magnitude).abs() < 1e-9, "magnitude {}", alias.magnitude);
+                        // the magnitudes are relative to the amplitude
+                        assert!(wf.validate($magnitude + 1e-6).is_ok());
+                    }
+                }
+            )*
+        };
+    }
+
+    test_wavetable_aliasing! {
+        // a square wave
+        held_square: vec![1.0, -1.0], Interpolation::None => 3, 4.0 / (3.0 * PI)
+        // a triangle wave
+        linear_square: vec![1.0, -1.0], Interpolation::Linear => 3, 8.0 / (9.0 * PI * PI)
+        // the images of the 1st harmonic, around the 4th one
+        held_sine: (0..4).map(|i| (PI * float(i) / 2.0).sin()).collect(), Interpolation::None => 3, 2.0 * 2.0_f64.sqrt() / (3.0 * PI)
+        // an impulse, with all the harmonics of the table of the same magnitude
+        cubic_impulse: vec![1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0], Interpolation::Cubic => 2, 0.25 * 8.0 / (PI * PI) * (24.0 / (PI * PI) - 4.0 / PI)
+    }
+
+    #[test]
+    fn wavetable_below_nyquist_is_ok() {
+        let sine = (0..64).map(|i| (PI * float(i) / 32.0).sin()).collect();
+        let wf = Waveform::<f64, f64>::with_components(
+            100.0,
+            vec![PeriodicFunction::wavetable(
+                40.0,
+                1.0,
+                0.0,
+                sine,
+                Interpolation::Cubic,
+            )],
+        );
+
+        // the images of the 1st harmonic, around the 64th one, are below the threshold
+        assert!(wf.validate(1e-4).is_ok());
+        assert_eq!(63, wf.validate(1e-6).aliases[0].harmonic);
+    }
+
+    #[cfg(feature = "hound")]
+    mod wav {
+        use super::super::read_wav_table;
+        use alloc::{vec, vec::Vec};
+        use std::io::Cursor;
+
+        #[allow(clippy::unwrap_used)]
+        fn wav<S: hound::Sample + Copy>(spec: hound::WavSpec, samples: &[S]) -> Cursor<Vec<u8>> {
+            let mut cursor = Cursor::new(Vec::new());
+            let mut writer = hound::WavWriter::new(&mut cursor, spec).unwrap();
+            for &sample in samples {
+                writer.write_sample(sample).unwrap();
+            }
+            writer.finalize().unwrap();
+            cursor.set_position(0);
+
+            cursor
+        }
+
+        #[test]
+        #[allow(clippy::unwrap_used)]
+        fn integer_samples_of_first_channel_are_scaled() {
+            let spec = hound::WavSpec {
+                channels: 2,
+                sample_rate: 44100,
+                bits_per_sample: 16,
+                sample_format: hound::SampleFormat::Int,
+            };
+            let file = wav(spec, &[0i16, 1, 16384, 2, -32768, 3, -8192, 4]);
+
+            assert_eq!(
+                vec![0.0, 0.5, -1.0, -0.25],
+                read_wav_table::<f64, _>(file).unwrap()
+            );
+        }
+
+        #[test]
+        #[allow(clippy::unwrap_used)]
+        fn float_samples_are_read_as_they_are() {
+            let spec = hound::WavSpec {
+                channels: 1,
+                sample_rate: 48000,
+                bits_per_sample: 32,
+                sample_format: hound::SampleFormat::Float,
+            };
+            let file = wav(spec, &[0.0f32, 0.75, -1.5]);
+
+            assert_eq!(
+                vec![0.0, 0.75, -1.5],
+                read_wav_table::<f32, _>(file).unwrap()
+            );
+        }
+
+        #[test]
+        fn invalid_file_is_reported() {
+            assert!(read_wav_table::<f32, _>(Cursor::new(b"not a wav file".to_vec())).is_err());
+        }
+    }
+}